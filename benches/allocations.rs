@@ -0,0 +1,79 @@
+//! Counts the heap allocations made while parsing class files
+//!
+//! Run with `cargo bench --bench allocations -- [CLASS_FILE]...`,
+//! which parses the bundled `Main.class` when no files are given
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    env, fs,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8
+    {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+    {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const ITERATIONS: usize = 10_000;
+
+fn main()
+{
+    let mut files = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(|path| {
+            let bytes = fs::read(&path).unwrap_or_else(|err| panic!("reading {path}: {err}"));
+
+            (path, bytes)
+        })
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        files.push((
+            "Main.class".into(),
+            include_bytes!("../Main.class").to_vec(),
+        ));
+    }
+
+    for (path, bytes) in &files {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+        let start = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            let class = rjvm::parse(black_box(bytes)).expect("the class file should parse");
+            black_box(class);
+        }
+
+        let elapsed = start.elapsed();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes;
+
+        println!(
+            "{path}: {} allocations ({} bytes) and {:.2?} per parse",
+            allocations / ITERATIONS,
+            allocated_bytes / ITERATIONS,
+            elapsed / ITERATIONS as u32,
+        );
+    }
+}
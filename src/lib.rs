@@ -1,24 +1,71 @@
-#![allow(dead_code, incomplete_features)]
-#![feature(generic_const_exprs)]
+#![allow(dead_code)]
 
-use parse::raw;
+use parse::{attribute::AttributeDecoder, raw, version::SupportedVersions, writer};
 
 pub mod parse;
 
-pub fn parse_raw_class_file(source: &[u8]) -> raw::error::Result<raw::ClassFile>
+pub fn parse_raw_class_file(source: &[u8]) -> raw::error::Result<raw::ClassFile<'_>>
 {
     raw::ClassFile::parse_bytes(source)
 }
 
-pub fn parse_class(class_file: raw::ClassFile) -> parse::error::Result<parse::Class>
+pub fn parse_raw_class_file_with_versions<'a>(
+    source: &'a [u8],
+    supported_versions: &SupportedVersions,
+) -> raw::error::Result<raw::ClassFile<'a>>
 {
-    parse::Class::parse_class_file(class_file)
+    raw::ClassFile::parse_bytes_with_versions(source, supported_versions)
 }
 
-pub fn parse(source: &[u8]) -> error::Result<parse::Class>
+/// Writes a class file, reproducing the bytes it was parsed from unless it was changed
+pub fn write_raw_class_file(class_file: &raw::ClassFile<'_>) -> writer::Result<Vec<u8>>
 {
-    let class_file = parse::raw::ClassFile::parse_bytes(source)?;
-    let class = parse::Class::parse_class_file(class_file)?;
+    class_file.to_bytes()
+}
+
+pub fn parse_class(class_file: raw::ClassFile<'_>) -> parse::error::Result<parse::Class<'_>>
+{
+    parse_class_with(class_file, &[])
+}
+
+pub fn parse_class_with<'a>(
+    class_file: raw::ClassFile<'a>,
+    decoders: &[&dyn AttributeDecoder],
+) -> parse::error::Result<parse::Class<'a>>
+{
+    parse_class_with_versions(class_file, decoders, &SupportedVersions::default())
+}
+
+pub fn parse_class_with_versions<'a>(
+    class_file: raw::ClassFile<'a>,
+    decoders: &[&dyn AttributeDecoder],
+    supported_versions: &SupportedVersions,
+) -> parse::error::Result<parse::Class<'a>>
+{
+    parse::Class::parse_class_file(class_file, decoders, supported_versions)
+}
+
+pub fn parse(source: &[u8]) -> error::Result<parse::Class<'_>>
+{
+    parse_with(source, &[])
+}
+
+pub fn parse_with<'a>(
+    source: &'a [u8],
+    decoders: &[&dyn AttributeDecoder],
+) -> error::Result<parse::Class<'a>>
+{
+    parse_with_versions(source, decoders, &SupportedVersions::default())
+}
+
+pub fn parse_with_versions<'a>(
+    source: &'a [u8],
+    decoders: &[&dyn AttributeDecoder],
+    supported_versions: &SupportedVersions,
+) -> error::Result<parse::Class<'a>>
+{
+    let class_file = parse::raw::ClassFile::parse_bytes_with_versions(source, supported_versions)?;
+    let class = parse::Class::parse_class_file(class_file, decoders, supported_versions)?;
 
     Ok(class)
 }
@@ -27,15 +74,30 @@ pub mod error
 {
     use std::{error, fmt, result};
 
-    use crate::parse;
+    use crate::parse::{
+        self,
+        context::{Context, Located},
+    };
 
     pub type Result<T> = result::Result<T, Error>;
 
     #[derive(Debug)]
     pub enum Error
     {
-        ParseRaw(parse::raw::error::Error),
-        Parse(parse::error::Error),
+        ParseRaw(Located<parse::raw::error::Error>),
+        Parse(Located<parse::error::Error>),
+    }
+
+    impl Error
+    {
+        /// Where in the class file the error was found
+        pub fn context(&self) -> &Context
+        {
+            match self {
+                Error::ParseRaw(parse_raw_err) => parse_raw_err.context(),
+                Error::Parse(parse_err) => parse_err.context(),
+            }
+        }
     }
 
     impl fmt::Display for Error
@@ -66,17 +128,17 @@ pub mod error
         }
     }
 
-    impl From<parse::raw::error::Error> for Error
+    impl From<Located<parse::raw::error::Error>> for Error
     {
-        fn from(parse_raw_err: parse::raw::error::Error) -> Self
+        fn from(parse_raw_err: Located<parse::raw::error::Error>) -> Self
         {
             Error::ParseRaw(parse_raw_err)
         }
     }
 
-    impl From<parse::error::Error> for Error
+    impl From<Located<parse::error::Error>> for Error
     {
-        fn from(parse_err: parse::error::Error) -> Self
+        fn from(parse_err: Located<parse::error::Error>) -> Self
         {
             Error::Parse(parse_err)
         }
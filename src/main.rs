@@ -1,16 +1,23 @@
+use std::process;
+
 const FILE: &[u8] = include_bytes!("../Main.class");
 
 fn try_main() -> rjvm::error::Result<String>
 {
     let class = rjvm::parse(FILE)?;
 
-    Ok(format!("{class:?}"))
+    Ok(format!("{class}"))
 }
 
 fn main()
 {
     match try_main() {
-        Ok(res) => println!("{res}"),
-        Err(err) => panic!("{err:?}"),
+        Ok(res) => print!("{res}"),
+        Err(err) => {
+            eprintln!("error: {err}");
+            eprint!("{}", err.context().hexdump(FILE));
+
+            process::exit(1)
+        }
     }
 }
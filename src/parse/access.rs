@@ -0,0 +1,537 @@
+use std::{fmt, ops};
+
+use self::error::{Error, Kind, Result};
+
+macro_rules! access_flags {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident
+        {
+            $(const $flag:ident = $bits:literal, $is:ident;)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name(u16);
+
+        impl $name
+        {
+            $(pub const $flag: $name = $name($bits);)*
+
+            const NAMES: &'static [(&'static str, $name)] =
+                &[$((concat!("ACC_", stringify!($flag)), $name::$flag),)*];
+
+            pub const fn empty() -> Self
+            {
+                $name(0)
+            }
+
+            pub const fn bits(&self) -> u16
+            {
+                self.0
+            }
+
+            pub const fn contains(&self, other: Self) -> bool
+            {
+                self.0 & other.0 == other.0
+            }
+
+            pub const fn intersects(&self, other: Self) -> bool
+            {
+                self.0 & other.0 != 0
+            }
+
+            pub fn names(&self) -> impl Iterator<Item = &'static str> + '_
+            {
+                $name::NAMES
+                    .iter()
+                    .filter(|(_, flag)| self.contains(*flag))
+                    .map(|(name, _)| *name)
+            }
+
+            $(
+                pub const fn $is(&self) -> bool
+                {
+                    self.contains($name::$flag)
+                }
+            )*
+
+            const fn difference(&self, other: Self) -> Self
+            {
+                $name(self.0 & !other.0)
+            }
+
+            fn name_of(flag: Self) -> &'static str
+            {
+                // Every flag we check against is one of our own constants
+                $name::NAMES
+                    .iter()
+                    .find(|(_, named)| *named == flag)
+                    .map(|(name, _)| *name)
+                    .unwrap()
+            }
+        }
+
+        impl ops::BitOr for $name
+        {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self
+            {
+                $name(self.0 | rhs.0)
+            }
+        }
+
+        impl fmt::Debug for $name
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+            {
+                write!(f, "{:#06x}", self.0)?;
+
+                let mut names = self.names().peekable();
+                if names.peek().is_some() {
+                    write!(f, " (")?;
+                    for (i, name) in names.enumerate() {
+                        if i != 0 {
+                            write!(f, " | ")?;
+                        }
+                        write!(f, "{name}")?;
+                    }
+                    write!(f, ")")?;
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+access_flags! {
+    pub struct ClassAccess
+    {
+        const PUBLIC = 0x0001, is_public;
+        const FINAL = 0x0010, is_final;
+        const SUPER = 0x0020, is_super;
+        const INTERFACE = 0x0200, is_interface;
+        const ABSTRACT = 0x0400, is_abstract;
+        const SYNTHETIC = 0x1000, is_synthetic;
+        const ANNOTATION = 0x2000, is_annotation;
+        const ENUM = 0x4000, is_enum;
+        const MODULE = 0x8000, is_module;
+    }
+}
+
+access_flags! {
+    pub struct FieldAccess
+    {
+        const PUBLIC = 0x0001, is_public;
+        const PRIVATE = 0x0002, is_private;
+        const PROTECTED = 0x0004, is_protected;
+        const STATIC = 0x0008, is_static;
+        const FINAL = 0x0010, is_final;
+        const VOLATILE = 0x0040, is_volatile;
+        const TRANSIENT = 0x0080, is_transient;
+        const SYNTHETIC = 0x1000, is_synthetic;
+        const ENUM = 0x4000, is_enum;
+    }
+}
+
+access_flags! {
+    pub struct MethodAccess
+    {
+        const PUBLIC = 0x0001, is_public;
+        const PRIVATE = 0x0002, is_private;
+        const PROTECTED = 0x0004, is_protected;
+        const STATIC = 0x0008, is_static;
+        const FINAL = 0x0010, is_final;
+        const SYNCHRONIZED = 0x0020, is_synchronized;
+        const BRIDGE = 0x0040, is_bridge;
+        const VARARGS = 0x0080, is_varargs;
+        const NATIVE = 0x0100, is_native;
+        const ABSTRACT = 0x0400, is_abstract;
+        const STRICT = 0x0800, is_strict;
+        const SYNTHETIC = 0x1000, is_synthetic;
+    }
+}
+
+access_flags! {
+    pub struct InnerClassAccess
+    {
+        const PUBLIC = 0x0001, is_public;
+        const PRIVATE = 0x0002, is_private;
+        const PROTECTED = 0x0004, is_protected;
+        const STATIC = 0x0008, is_static;
+        const FINAL = 0x0010, is_final;
+        const INTERFACE = 0x0200, is_interface;
+        const ABSTRACT = 0x0400, is_abstract;
+        const SYNTHETIC = 0x1000, is_synthetic;
+        const ANNOTATION = 0x2000, is_annotation;
+        const ENUM = 0x4000, is_enum;
+    }
+}
+
+access_flags! {
+    pub struct ParameterAccess
+    {
+        const FINAL = 0x0010, is_final;
+        const SYNTHETIC = 0x1000, is_synthetic;
+        const MANDATED = 0x8000, is_mandated;
+    }
+}
+
+impl ClassAccess
+{
+    pub(super) fn new(bits: u16) -> Result<Self>
+    {
+        let access = ClassAccess(bits);
+
+        if access.is_module() {
+            if let Some(flag) = access.difference(ClassAccess::MODULE).names().next() {
+                Err(Error::ConflictingFlags {
+                    kind: Kind::Class,
+                    first: "ACC_MODULE",
+                    second: flag,
+                })?
+            }
+
+            return Ok(access);
+        }
+
+        if access.is_interface() {
+            if !access.is_abstract() {
+                Err(Error::MissingFlag {
+                    kind: Kind::Class,
+                    flag: "ACC_ABSTRACT",
+                    required_by: "ACC_INTERFACE",
+                })?
+            }
+
+            for flag in [ClassAccess::FINAL, ClassAccess::SUPER, ClassAccess::ENUM] {
+                if access.contains(flag) {
+                    Err(Error::ConflictingFlags {
+                        kind: Kind::Class,
+                        first: "ACC_INTERFACE",
+                        second: ClassAccess::name_of(flag),
+                    })?
+                }
+            }
+        } else {
+            if access.is_annotation() {
+                Err(Error::MissingFlag {
+                    kind: Kind::Class,
+                    flag: "ACC_INTERFACE",
+                    required_by: "ACC_ANNOTATION",
+                })?
+            }
+
+            if access.is_final() && access.is_abstract() {
+                Err(Error::ConflictingFlags {
+                    kind: Kind::Class,
+                    first: "ACC_FINAL",
+                    second: "ACC_ABSTRACT",
+                })?
+            }
+        }
+
+        Ok(access)
+    }
+}
+
+impl FieldAccess
+{
+    pub(super) fn new(bits: u16, class_access: ClassAccess) -> Result<Self>
+    {
+        let access = FieldAccess(bits);
+
+        check_visibility(Kind::Field, access.names())?;
+
+        if access.is_final() && access.is_volatile() {
+            Err(Error::ConflictingFlags {
+                kind: Kind::Field,
+                first: "ACC_FINAL",
+                second: "ACC_VOLATILE",
+            })?
+        }
+
+        if class_access.is_interface() {
+            for flag in [FieldAccess::PUBLIC, FieldAccess::STATIC, FieldAccess::FINAL] {
+                if !access.contains(flag) {
+                    Err(Error::MissingFlag {
+                        kind: Kind::Field,
+                        flag: FieldAccess::name_of(flag),
+                        required_by: "ACC_INTERFACE",
+                    })?
+                }
+            }
+
+            let allowed = FieldAccess::PUBLIC
+                | FieldAccess::STATIC
+                | FieldAccess::FINAL
+                | FieldAccess::SYNTHETIC;
+            if let Some(flag) = access.difference(allowed).names().next() {
+                Err(Error::IllegalInterfaceMemberFlag {
+                    kind: Kind::Field,
+                    flag,
+                })?
+            }
+        }
+
+        Ok(access)
+    }
+}
+
+impl MethodAccess
+{
+    pub(super) fn new(bits: u16, class_access: ClassAccess, name: &str, major: u16)
+        -> Result<Self>
+    {
+        let access = MethodAccess(bits);
+
+        // Starting with version 51.0, class initialisation methods
+        // must be static, every other flag of theirs is ignored
+        if name == "<clinit>" {
+            if major >= 51 && !access.is_static() {
+                Err(Error::MissingFlag {
+                    kind: Kind::Method,
+                    flag: "ACC_STATIC",
+                    required_by: "<clinit>",
+                })?
+            }
+
+            return Ok(access);
+        }
+
+        check_visibility(Kind::Method, access.names())?;
+
+        if class_access.is_interface() {
+            let required: &[MethodAccess] = if major < 52 {
+                &[MethodAccess::PUBLIC, MethodAccess::ABSTRACT]
+            } else if access.is_private() {
+                &[]
+            } else {
+                &[MethodAccess::PUBLIC]
+            };
+            for flag in required {
+                if !access.contains(*flag) {
+                    Err(Error::MissingFlag {
+                        kind: Kind::Method,
+                        flag: MethodAccess::name_of(*flag),
+                        required_by: "ACC_INTERFACE",
+                    })?
+                }
+            }
+
+            for flag in [
+                MethodAccess::PROTECTED,
+                MethodAccess::FINAL,
+                MethodAccess::SYNCHRONIZED,
+                MethodAccess::NATIVE,
+            ] {
+                if access.contains(flag) {
+                    Err(Error::IllegalInterfaceMemberFlag {
+                        kind: Kind::Method,
+                        flag: MethodAccess::name_of(flag),
+                    })?
+                }
+            }
+        }
+
+        if access.is_abstract() {
+            let mut forbidden = vec![
+                MethodAccess::PRIVATE,
+                MethodAccess::STATIC,
+                MethodAccess::FINAL,
+                MethodAccess::SYNCHRONIZED,
+                MethodAccess::NATIVE,
+            ];
+            // ACC_STRICT is only meaningful between versions 46.0 and 60.0
+            if (46..=60).contains(&major) {
+                forbidden.push(MethodAccess::STRICT);
+            }
+
+            for flag in forbidden {
+                if access.contains(flag) {
+                    Err(Error::ConflictingFlags {
+                        kind: Kind::Method,
+                        first: "ACC_ABSTRACT",
+                        second: MethodAccess::name_of(flag),
+                    })?
+                }
+            }
+        }
+
+        if name == "<init>" {
+            let allowed = MethodAccess::PUBLIC
+                | MethodAccess::PRIVATE
+                | MethodAccess::PROTECTED
+                | MethodAccess::VARARGS
+                | MethodAccess::STRICT
+                | MethodAccess::SYNTHETIC;
+            if let Some(flag) = access.difference(allowed).names().next() {
+                Err(Error::IllegalConstructorFlag(flag))?
+            }
+        }
+
+        Ok(access)
+    }
+}
+
+impl InnerClassAccess
+{
+    pub(super) fn new(bits: u16) -> Result<Self>
+    {
+        let access = InnerClassAccess(bits);
+
+        check_visibility(Kind::InnerClass, access.names())?;
+
+        if access.is_interface() && !access.is_abstract() {
+            Err(Error::MissingFlag {
+                kind: Kind::InnerClass,
+                flag: "ACC_ABSTRACT",
+                required_by: "ACC_INTERFACE",
+            })?
+        }
+
+        if access.is_annotation() && !access.is_interface() {
+            Err(Error::MissingFlag {
+                kind: Kind::InnerClass,
+                flag: "ACC_INTERFACE",
+                required_by: "ACC_ANNOTATION",
+            })?
+        }
+
+        if access.is_final() && access.is_abstract() {
+            Err(Error::ConflictingFlags {
+                kind: Kind::InnerClass,
+                first: "ACC_FINAL",
+                second: "ACC_ABSTRACT",
+            })?
+        }
+
+        Ok(access)
+    }
+}
+
+impl ParameterAccess
+{
+    /// Any combination of the flags is allowed on a parameter
+    pub(super) const fn new(bits: u16) -> Self
+    {
+        ParameterAccess(bits)
+    }
+}
+
+/// At most one of `ACC_PUBLIC`, `ACC_PRIVATE` and `ACC_PROTECTED` may be set
+fn check_visibility(kind: Kind, names: impl Iterator<Item = &'static str>) -> Result<()>
+{
+    let mut visibilities =
+        names.filter(|name| matches!(*name, "ACC_PUBLIC" | "ACC_PRIVATE" | "ACC_PROTECTED"));
+
+    if let (Some(first), Some(second)) = (visibilities.next(), visibilities.next()) {
+        Err(Error::ConflictingFlags {
+            kind,
+            first,
+            second,
+        })?
+    }
+
+    Ok(())
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    pub(super) type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug, Clone, Copy)]
+    pub enum Kind
+    {
+        Class,
+        Field,
+        Method,
+        InnerClass,
+    }
+
+    impl fmt::Display for Kind
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Kind::Class => write!(f, "class"),
+                Kind::Field => write!(f, "field"),
+                Kind::Method => write!(f, "method"),
+                Kind::InnerClass => write!(f, "inner class"),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        ConflictingFlags
+        {
+            kind: Kind,
+            first: &'static str,
+            second: &'static str,
+        },
+        MissingFlag
+        {
+            kind: Kind,
+            flag: &'static str,
+            required_by: &'static str,
+        },
+        IllegalInterfaceMemberFlag
+        {
+            kind: Kind,
+            flag: &'static str,
+        },
+        IllegalConstructorFlag(&'static str),
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::ConflictingFlags {
+                    kind,
+                    first,
+                    second,
+                } => {
+                    write!(
+                        f,
+                        "{kind} access flags {first} and {second} cannot both be set"
+                    )
+                }
+                Error::MissingFlag {
+                    kind,
+                    flag,
+                    required_by,
+                } => {
+                    write!(
+                        f,
+                        "{kind} access flags must include {flag} because of {required_by}"
+                    )
+                }
+                Error::IllegalInterfaceMemberFlag { kind, flag } => {
+                    write!(f, "interface {kind} access flags cannot include {flag}")
+                }
+                Error::IllegalConstructorFlag(flag) => {
+                    write!(
+                        f,
+                        "instance initialisation method access flags cannot include {flag}"
+                    )
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            None
+        }
+    }
+}
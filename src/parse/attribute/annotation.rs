@@ -0,0 +1,729 @@
+use std::borrow::Cow;
+
+use crate::parse::{
+    constant_pool::{error::ConstantKind, ConstantPool},
+    cursor::Cursor,
+};
+
+use super::error::{Error, Result};
+
+/// How deeply element values may nest within annotations and arrays,
+/// which keeps parsing and formatting them from running out of stack
+pub(super) const MAX_NESTING_DEPTH: usize = 64;
+
+/// An annotation on a class, field, method, parameter or type, as found in
+/// the Runtime(In)Visible*Annotations attributes and within other annotations
+#[derive(Debug, Clone)]
+pub struct Annotation<'a>
+{
+    type_index: u16,
+    type_descriptor: Cow<'a, str>,
+    element_value_pairs: Box<[ElementValuePair<'a>]>,
+}
+
+impl<'a> Annotation<'a>
+{
+    /// Parses an annotation whose element values are at `depth`, counting
+    /// the annotations and arrays they are nested in
+    fn parse(
+        cursor: &mut Cursor<'a>,
+        constant_pool: &ConstantPool<'a>,
+        depth: usize,
+    ) -> Result<Self>
+    {
+        let (type_index, type_descriptor) = parse_utf8(cursor, constant_pool)?;
+
+        let num_element_value_pairs = cursor.read_integer::<u16>()?;
+        let element_value_pairs = (0..num_element_value_pairs)
+            .map(|_| {
+                let (element_name_index, element_name) = parse_utf8(cursor, constant_pool)?;
+                let value = ElementValue::parse(cursor, constant_pool, depth)?;
+
+                Ok(ElementValuePair {
+                    element_name_index,
+                    element_name,
+                    value,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Annotation {
+            type_index,
+            type_descriptor,
+            element_value_pairs,
+        })
+    }
+
+    pub(super) fn parse_many(
+        cursor: &mut Cursor<'a>,
+        constant_pool: &ConstantPool<'a>,
+    ) -> Result<Box<[Self]>>
+    {
+        let num_annotations = cursor.read_integer::<u16>()?;
+
+        (0..num_annotations)
+            .map(|_| Annotation::parse(cursor, constant_pool, 0))
+            .collect()
+    }
+
+    /// The index of the Utf8 constant with the annotation's type descriptor
+    pub fn type_index(&self) -> u16
+    {
+        self.type_index
+    }
+
+    /// The field descriptor of the annotation interface, such as `Ljava/lang/Deprecated;`
+    pub fn type_descriptor(&self) -> &str
+    {
+        &self.type_descriptor
+    }
+
+    /// The elements given explicitly, leaving out those that take their default value
+    pub fn element_value_pairs(&self) -> &[ElementValuePair<'a>]
+    {
+        &self.element_value_pairs
+    }
+
+    /// Finds the value given for the element with the given name
+    pub fn element(&self, name: &str) -> Option<&ElementValue<'a>>
+    {
+        self.element_value_pairs
+            .iter()
+            .find(|pair| pair.element_name() == name)
+            .map(ElementValuePair::value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementValuePair<'a>
+{
+    element_name_index: u16,
+    element_name: Cow<'a, str>,
+    value: ElementValue<'a>,
+}
+
+impl<'a> ElementValuePair<'a>
+{
+    /// The index of the Utf8 constant with the element's name
+    pub fn element_name_index(&self) -> u16
+    {
+        self.element_name_index
+    }
+
+    pub fn element_name(&self) -> &str
+    {
+        &self.element_name
+    }
+
+    pub fn value(&self) -> &ElementValue<'a>
+    {
+        &self.value
+    }
+}
+
+/// The value of an annotation element, where the indices are those of its constants
+#[derive(Debug, Clone)]
+pub enum ElementValue<'a>
+{
+    Byte
+    {
+        const_value_index: u16,
+        value: i8,
+    },
+    Char
+    {
+        const_value_index: u16,
+        /// A UTF-16 code unit, as chars are in Java
+        value: u16,
+    },
+    Double
+    {
+        const_value_index: u16,
+        value: f64,
+    },
+    Float
+    {
+        const_value_index: u16,
+        value: f32,
+    },
+    Int
+    {
+        const_value_index: u16,
+        value: i32,
+    },
+    Long
+    {
+        const_value_index: u16,
+        value: i64,
+    },
+    Short
+    {
+        const_value_index: u16,
+        value: i16,
+    },
+    Boolean
+    {
+        const_value_index: u16,
+        value: bool,
+    },
+    /// A string, given directly by a Utf8 constant rather than a String constant
+    String
+    {
+        const_value_index: u16,
+        value: Cow<'a, str>,
+    },
+    Enum
+    {
+        type_name_index: u16,
+        /// The field descriptor of the enum class, such as `Ljava/lang/annotation/ElementType;`
+        type_descriptor: Cow<'a, str>,
+        const_name_index: u16,
+        const_name: Cow<'a, str>,
+    },
+    /// A class literal
+    Class
+    {
+        class_info_index: u16,
+        /// The return descriptor of the class, such
+        /// as `Ljava/lang/String;`, `[I` or `V` for void
+        return_descriptor: Cow<'a, str>,
+    },
+    Annotation(Annotation<'a>),
+    Array(Box<[ElementValue<'a>]>),
+}
+
+impl<'a> ElementValue<'a>
+{
+    /// Parses an element value nested `depth` annotations and arrays deep
+    pub(super) fn parse(
+        cursor: &mut Cursor<'a>,
+        constant_pool: &ConstantPool<'a>,
+        depth: usize,
+    ) -> Result<Self>
+    {
+        let tag = cursor.read_integer::<u8>()?;
+        if depth > MAX_NESTING_DEPTH {
+            Err(cursor.locate(Error::NestingTooDeep))?
+        }
+
+        let mut const_value = |kind| -> Result<(u16, usize)> {
+            let const_value_index = cursor.read_integer::<u16>()?;
+            let index = constant_pool
+                .check_index(const_value_index, kind)
+                .map_err(|err| cursor.locate(err))?;
+
+            Ok((const_value_index, index))
+        };
+
+        // Booleans, bytes, chars and shorts are stored as integer constants
+        // and narrowed like the JVM narrows them when storing them into arrays
+        Ok(match tag {
+            b'B' => {
+                let (const_value_index, index) = const_value(ConstantKind::Integer)?;
+                let value = constant_pool.integer_at(index) as i8;
+
+                ElementValue::Byte {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'C' => {
+                let (const_value_index, index) = const_value(ConstantKind::Integer)?;
+                let value = constant_pool.integer_at(index) as u16;
+
+                ElementValue::Char {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'D' => {
+                let (const_value_index, index) = const_value(ConstantKind::Double)?;
+                let value = constant_pool.double_at(index);
+
+                ElementValue::Double {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'F' => {
+                let (const_value_index, index) = const_value(ConstantKind::Float)?;
+                let value = constant_pool.float_at(index);
+
+                ElementValue::Float {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'I' => {
+                let (const_value_index, index) = const_value(ConstantKind::Integer)?;
+                let value = constant_pool.integer_at(index);
+
+                ElementValue::Int {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'J' => {
+                let (const_value_index, index) = const_value(ConstantKind::Long)?;
+                let value = constant_pool.long_at(index);
+
+                ElementValue::Long {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'S' => {
+                let (const_value_index, index) = const_value(ConstantKind::Integer)?;
+                let value = constant_pool.integer_at(index) as i16;
+
+                ElementValue::Short {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'Z' => {
+                let (const_value_index, index) = const_value(ConstantKind::Integer)?;
+                let value = constant_pool.integer_at(index) != 0;
+
+                ElementValue::Boolean {
+                    const_value_index,
+                    value,
+                }
+            }
+            b's' => {
+                let (const_value_index, index) = const_value(ConstantKind::Utf8)?;
+                let value = constant_pool.utf8_cow_at(index);
+
+                ElementValue::String {
+                    const_value_index,
+                    value,
+                }
+            }
+            b'e' => {
+                let (type_name_index, type_descriptor) = parse_utf8(cursor, constant_pool)?;
+                let (const_name_index, const_name) = parse_utf8(cursor, constant_pool)?;
+
+                ElementValue::Enum {
+                    type_name_index,
+                    type_descriptor,
+                    const_name_index,
+                    const_name,
+                }
+            }
+            b'c' => {
+                let (class_info_index, return_descriptor) = parse_utf8(cursor, constant_pool)?;
+
+                ElementValue::Class {
+                    class_info_index,
+                    return_descriptor,
+                }
+            }
+            b'@' => ElementValue::Annotation(Annotation::parse(cursor, constant_pool, depth + 1)?),
+            b'[' => {
+                let num_values = cursor.read_integer::<u16>()?;
+
+                let values = (0..num_values)
+                    .map(|_| ElementValue::parse(cursor, constant_pool, depth + 1))
+                    .collect::<Result<_>>()?;
+
+                ElementValue::Array(values)
+            }
+            tag => Err(cursor.locate(Error::InvalidElementValueTag(tag)))?,
+        })
+    }
+
+    /// The tag the value is written with, such as `I` for ints or `[` for arrays
+    pub fn tag(&self) -> char
+    {
+        match self {
+            ElementValue::Byte { .. } => 'B',
+            ElementValue::Char { .. } => 'C',
+            ElementValue::Double { .. } => 'D',
+            ElementValue::Float { .. } => 'F',
+            ElementValue::Int { .. } => 'I',
+            ElementValue::Long { .. } => 'J',
+            ElementValue::Short { .. } => 'S',
+            ElementValue::Boolean { .. } => 'Z',
+            ElementValue::String { .. } => 's',
+            ElementValue::Enum { .. } => 'e',
+            ElementValue::Class { .. } => 'c',
+            ElementValue::Annotation(..) => '@',
+            ElementValue::Array(..) => '[',
+        }
+    }
+}
+
+/// An annotation on a use of a type, such as the type
+/// of a field, a type argument or the type in a cast
+#[derive(Debug, Clone)]
+pub struct TypeAnnotation<'a>
+{
+    target_type: TargetType,
+    target_info: TargetInfo,
+    type_path: Box<[TypePathEntry]>,
+    annotation: Annotation<'a>,
+}
+
+impl<'a> TypeAnnotation<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        let target_type =
+            TargetType::try_from(cursor.read_integer::<u8>()?).map_err(|err| cursor.locate(err))?;
+
+        let target_info = match target_type {
+            TargetType::ClassTypeParameter | TargetType::MethodTypeParameter => {
+                TargetInfo::TypeParameter {
+                    type_parameter_index: cursor.read_integer::<u8>()?,
+                }
+            }
+            TargetType::ClassExtends => TargetInfo::Supertype {
+                supertype_index: cursor.read_integer::<u16>()?,
+            },
+            TargetType::ClassTypeParameterBound | TargetType::MethodTypeParameterBound => {
+                TargetInfo::TypeParameterBound {
+                    type_parameter_index: cursor.read_integer::<u8>()?,
+                    bound_index: cursor.read_integer::<u8>()?,
+                }
+            }
+            TargetType::Field | TargetType::MethodReturn | TargetType::MethodReceiver => {
+                TargetInfo::Empty
+            }
+            TargetType::MethodFormalParameter => TargetInfo::FormalParameter {
+                formal_parameter_index: cursor.read_integer::<u8>()?,
+            },
+            TargetType::Throws => TargetInfo::Throws {
+                throws_type_index: cursor.read_integer::<u16>()?,
+            },
+            TargetType::LocalVariable | TargetType::ResourceVariable => {
+                let table_length = cursor.read_integer::<u16>()?;
+
+                let table = (0..table_length)
+                    .map(|_| {
+                        Ok(LocalvarTarget {
+                            start_pc: cursor.read_integer::<u16>()?,
+                            length: cursor.read_integer::<u16>()?,
+                            index: cursor.read_integer::<u16>()?,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+
+                TargetInfo::Localvar { table }
+            }
+            TargetType::ExceptionParameter => TargetInfo::Catch {
+                exception_table_index: cursor.read_integer::<u16>()?,
+            },
+            TargetType::Instanceof
+            | TargetType::New
+            | TargetType::ConstructorReference
+            | TargetType::MethodReference => TargetInfo::Offset {
+                offset: cursor.read_integer::<u16>()?,
+            },
+            TargetType::Cast
+            | TargetType::ConstructorInvocationTypeArgument
+            | TargetType::MethodInvocationTypeArgument
+            | TargetType::ConstructorReferenceTypeArgument
+            | TargetType::MethodReferenceTypeArgument => TargetInfo::TypeArgument {
+                offset: cursor.read_integer::<u16>()?,
+                type_argument_index: cursor.read_integer::<u8>()?,
+            },
+        };
+
+        let path_length = cursor.read_integer::<u8>()?;
+        let type_path = (0..path_length)
+            .map(|_| {
+                let type_path_kind = TypePathKind::try_from(cursor.read_integer::<u8>()?)
+                    .map_err(|err| cursor.locate(err))?;
+                let type_argument_index = cursor.read_integer::<u8>()?;
+
+                Ok(TypePathEntry {
+                    type_path_kind,
+                    type_argument_index,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let annotation = Annotation::parse(cursor, constant_pool, 0)?;
+
+        Ok(TypeAnnotation {
+            target_type,
+            target_info,
+            type_path,
+            annotation,
+        })
+    }
+
+    pub(super) fn parse_many(
+        cursor: &mut Cursor<'a>,
+        constant_pool: &ConstantPool<'a>,
+    ) -> Result<Box<[Self]>>
+    {
+        let num_annotations = cursor.read_integer::<u16>()?;
+
+        (0..num_annotations)
+            .map(|_| TypeAnnotation::parse(cursor, constant_pool))
+            .collect()
+    }
+
+    /// The kind of type use the annotation is on
+    pub fn target_type(&self) -> TargetType
+    {
+        self.target_type
+    }
+
+    /// Which type of its kind the annotation is on, such as
+    /// which parameter or the offset of which instruction
+    pub fn target_info(&self) -> &TargetInfo
+    {
+        &self.target_info
+    }
+
+    /// Where within the type the annotation is, such as on an array's
+    /// element type rather than the array, empty for the type itself
+    pub fn type_path(&self) -> &[TypePathEntry]
+    {
+        &self.type_path
+    }
+
+    pub fn annotation(&self) -> &Annotation<'a>
+    {
+        &self.annotation
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetType
+{
+    ClassTypeParameter = 0x00,
+    MethodTypeParameter = 0x01,
+    ClassExtends = 0x10,
+    ClassTypeParameterBound = 0x11,
+    MethodTypeParameterBound = 0x12,
+    Field = 0x13,
+    MethodReturn = 0x14,
+    MethodReceiver = 0x15,
+    MethodFormalParameter = 0x16,
+    Throws = 0x17,
+    LocalVariable = 0x40,
+    ResourceVariable = 0x41,
+    ExceptionParameter = 0x42,
+    Instanceof = 0x43,
+    New = 0x44,
+    ConstructorReference = 0x45,
+    MethodReference = 0x46,
+    Cast = 0x47,
+    ConstructorInvocationTypeArgument = 0x48,
+    MethodInvocationTypeArgument = 0x49,
+    ConstructorReferenceTypeArgument = 0x4a,
+    MethodReferenceTypeArgument = 0x4b,
+}
+
+impl TargetType
+{
+    /// The name javap gives the target type, such as `METHOD_FORMAL_PARAMETER`
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            TargetType::ClassTypeParameter => "CLASS_TYPE_PARAMETER",
+            TargetType::MethodTypeParameter => "METHOD_TYPE_PARAMETER",
+            TargetType::ClassExtends => "CLASS_EXTENDS",
+            TargetType::ClassTypeParameterBound => "CLASS_TYPE_PARAMETER_BOUND",
+            TargetType::MethodTypeParameterBound => "METHOD_TYPE_PARAMETER_BOUND",
+            TargetType::Field => "FIELD",
+            TargetType::MethodReturn => "METHOD_RETURN",
+            TargetType::MethodReceiver => "METHOD_RECEIVER",
+            TargetType::MethodFormalParameter => "METHOD_FORMAL_PARAMETER",
+            TargetType::Throws => "THROWS",
+            TargetType::LocalVariable => "LOCAL_VARIABLE",
+            TargetType::ResourceVariable => "RESOURCE_VARIABLE",
+            TargetType::ExceptionParameter => "EXCEPTION_PARAMETER",
+            TargetType::Instanceof => "INSTANCEOF",
+            TargetType::New => "NEW",
+            TargetType::ConstructorReference => "CONSTRUCTOR_REFERENCE",
+            TargetType::MethodReference => "METHOD_REFERENCE",
+            TargetType::Cast => "CAST",
+            TargetType::ConstructorInvocationTypeArgument => "CONSTRUCTOR_INVOCATION_TYPE_ARGUMENT",
+            TargetType::MethodInvocationTypeArgument => "METHOD_INVOCATION_TYPE_ARGUMENT",
+            TargetType::ConstructorReferenceTypeArgument => "CONSTRUCTOR_REFERENCE_TYPE_ARGUMENT",
+            TargetType::MethodReferenceTypeArgument => "METHOD_REFERENCE_TYPE_ARGUMENT",
+        }
+    }
+}
+
+impl TryFrom<u8> for TargetType
+{
+    type Error = Error;
+
+    fn try_from(target_type: u8) -> std::result::Result<Self, Error>
+    {
+        match target_type {
+            0x00 => Ok(TargetType::ClassTypeParameter),
+            0x01 => Ok(TargetType::MethodTypeParameter),
+            0x10 => Ok(TargetType::ClassExtends),
+            0x11 => Ok(TargetType::ClassTypeParameterBound),
+            0x12 => Ok(TargetType::MethodTypeParameterBound),
+            0x13 => Ok(TargetType::Field),
+            0x14 => Ok(TargetType::MethodReturn),
+            0x15 => Ok(TargetType::MethodReceiver),
+            0x16 => Ok(TargetType::MethodFormalParameter),
+            0x17 => Ok(TargetType::Throws),
+            0x40 => Ok(TargetType::LocalVariable),
+            0x41 => Ok(TargetType::ResourceVariable),
+            0x42 => Ok(TargetType::ExceptionParameter),
+            0x43 => Ok(TargetType::Instanceof),
+            0x44 => Ok(TargetType::New),
+            0x45 => Ok(TargetType::ConstructorReference),
+            0x46 => Ok(TargetType::MethodReference),
+            0x47 => Ok(TargetType::Cast),
+            0x48 => Ok(TargetType::ConstructorInvocationTypeArgument),
+            0x49 => Ok(TargetType::MethodInvocationTypeArgument),
+            0x4a => Ok(TargetType::ConstructorReferenceTypeArgument),
+            0x4b => Ok(TargetType::MethodReferenceTypeArgument),
+            target_type => Err(Error::InvalidTargetType(target_type)),
+        }
+    }
+}
+
+/// Which type a type annotation is on among those its target type covers
+#[derive(Debug, Clone)]
+pub enum TargetInfo
+{
+    TypeParameter
+    {
+        type_parameter_index: u8
+    },
+    /// The superclass for an index of 65535, and otherwise an
+    /// index into the interfaces of the class
+    Supertype
+    {
+        supertype_index: u16
+    },
+    TypeParameterBound
+    {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    /// The type of a field, the return type of a method or the receiver type
+    Empty,
+    FormalParameter
+    {
+        formal_parameter_index: u8
+    },
+    /// An index into the exceptions of the method's Exceptions attribute
+    Throws
+    {
+        throws_type_index: u16
+    },
+    /// The ranges of code a local variable has a value in
+    Localvar
+    {
+        table: Box<[LocalvarTarget]>
+    },
+    /// An index into the exception table of the method's code
+    Catch
+    {
+        exception_table_index: u16
+    },
+    /// The offset of the instruction the annotated type is used by
+    Offset
+    {
+        offset: u16
+    },
+    TypeArgument
+    {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LocalvarTarget
+{
+    start_pc: u16,
+    length: u16,
+    index: u16,
+}
+
+impl LocalvarTarget
+{
+    pub fn start_pc(&self) -> u16
+    {
+        self.start_pc
+    }
+
+    pub fn length(&self) -> u16
+    {
+        self.length
+    }
+
+    /// The local variable slot holding the variable
+    pub fn index(&self) -> u16
+    {
+        self.index
+    }
+}
+
+/// A step from a type into one of the types it is made up of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypePathEntry
+{
+    type_path_kind: TypePathKind,
+    type_argument_index: u8,
+}
+
+impl TypePathEntry
+{
+    pub fn type_path_kind(&self) -> TypePathKind
+    {
+        self.type_path_kind
+    }
+
+    /// Which type argument the step is into, which is 0 for other kinds of steps
+    pub fn type_argument_index(&self) -> u8
+    {
+        self.type_argument_index
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypePathKind
+{
+    /// Into the element type of an array
+    Array = 0,
+    /// Into a nested type
+    Nested = 1,
+    /// Into the bound of a wildcard type argument
+    Wildcard = 2,
+    /// Into a type argument of a parameterized type
+    TypeArgument = 3,
+}
+
+impl TryFrom<u8> for TypePathKind
+{
+    type Error = Error;
+
+    fn try_from(type_path_kind: u8) -> std::result::Result<Self, Error>
+    {
+        match type_path_kind {
+            0 => Ok(TypePathKind::Array),
+            1 => Ok(TypePathKind::Nested),
+            2 => Ok(TypePathKind::Wildcard),
+            3 => Ok(TypePathKind::TypeArgument),
+            type_path_kind => Err(Error::InvalidTypePathKind(type_path_kind)),
+        }
+    }
+}
+
+/// Reads the index of a Utf8 constant, giving it along with its value
+fn parse_utf8<'a>(
+    cursor: &mut Cursor<'a>,
+    constant_pool: &ConstantPool<'a>,
+) -> Result<(u16, Cow<'a, str>)>
+{
+    let utf8_index = cursor.read_integer::<u16>()?;
+    let index = constant_pool
+        .utf8_index(utf8_index)
+        .map_err(|err| cursor.locate(err))?;
+
+    Ok((utf8_index, constant_pool.utf8_cow_at(index)))
+}
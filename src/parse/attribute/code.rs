@@ -0,0 +1,264 @@
+use crate::{
+    parse::{
+        constant_pool::ConstantPool,
+        context::{Located, Segment},
+        cursor::Cursor,
+        instruction::Instructions,
+    },
+    raw,
+};
+
+use super::{
+    error::{Error, Result},
+    Attribute, AttributeDecoder, LocalVariable, LocalVariableType, StackMapTable,
+};
+
+#[derive(Debug)]
+pub struct Code<'a>
+{
+    max_stack: u16,
+    max_locals: u16,
+    code: &'a [u8],
+    exception_table: Box<[ExceptionHandler]>,
+    attributes: Box<[Attribute<'a>]>,
+}
+
+impl<'a> Code<'a>
+{
+    pub(super) fn parse(
+        cursor: &mut Cursor<'a>,
+        constant_pool: &ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> Result<Self>
+    {
+        let max_stack = cursor.read_integer::<u16>()?;
+        let max_locals = cursor.read_integer::<u16>()?;
+
+        let code_length = cursor.read_integer::<u32>()?;
+        // The code array must be non-empty and its offsets have to fit in a u16
+        if !(1..65536).contains(&code_length) {
+            Err(cursor.locate(Error::InvalidCodeLength(code_length)))?
+        }
+        let code = cursor.read_bytes(code_length as usize)?;
+
+        let exception_table = {
+            let exception_table_length = cursor.read_integer::<u16>()?;
+
+            (0..exception_table_length)
+                .map(|_| ExceptionHandler::parse(cursor, code_length, constant_pool))
+                .collect::<Result<_>>()?
+        };
+
+        let attribute_infos = raw::AttributeInfo::parse_many(cursor)?;
+        let attributes =
+            Attribute::parse_attribute_infos(&attribute_infos, constant_pool, decoders)?;
+
+        // Frames are only given for offsets within the code
+        for (index, (attribute, attribute_info)) in
+            attributes.iter().zip(attribute_infos.iter()).enumerate()
+        {
+            if let Attribute::StackMapTable(stack_map_table) = attribute {
+                if let Some(offset) = stack_map_table
+                    .offsets()
+                    .find(|offset| *offset >= code_length)
+                {
+                    Err(Located::new(
+                        Error::InvalidStackMapFrameOffset {
+                            offset,
+                            code_length,
+                        },
+                        attribute_info.offset,
+                    )
+                    .within(Segment::Attribute {
+                        index,
+                        name: Some("StackMapTable".into()),
+                    }))?
+                }
+            }
+        }
+
+        Ok(Code {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            attributes,
+        })
+    }
+
+    pub fn max_stack(&self) -> u16
+    {
+        self.max_stack
+    }
+
+    pub fn max_locals(&self) -> u16
+    {
+        self.max_locals
+    }
+
+    pub fn code(&self) -> &'a [u8]
+    {
+        self.code
+    }
+
+    pub fn instructions(&self) -> Instructions<'a>
+    {
+        Instructions::new(self.code)
+    }
+
+    pub fn exception_table(&self) -> &[ExceptionHandler]
+    {
+        &self.exception_table
+    }
+
+    pub fn attributes(&self) -> &[Attribute<'a>]
+    {
+        &self.attributes
+    }
+
+    /// The line of source code the instruction at `pc` was compiled from,
+    /// if the code has line numbers that cover it
+    pub fn line_for_pc(&self, pc: u16) -> Option<u16>
+    {
+        // Line numbers may come in any order and be spread over several tables,
+        // so the line is the one starting closest before the instruction
+        self.attributes
+            .iter()
+            .filter_map(|attribute| {
+                if let Attribute::LineNumberTable { line_numbers } = attribute {
+                    Some(line_numbers.iter())
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .filter(|line_number| line_number.start_pc() <= pc)
+            .max_by_key(|line_number| line_number.start_pc())
+            .map(|line_number| line_number.line_number())
+    }
+
+    /// The local variables that have a value at `pc`, as given by the LocalVariableTable
+    pub fn locals_at(&self, pc: u16) -> impl Iterator<Item = &LocalVariable<'a>>
+    {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| {
+                if let Attribute::LocalVariableTable { local_variables } = attribute {
+                    Some(local_variables.iter())
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .filter(move |local_variable| local_variable.covers(pc))
+    }
+
+    /// The generic types of the local variables that have a value
+    /// at `pc`, as given by the LocalVariableTypeTable
+    pub fn local_types_at(&self, pc: u16) -> impl Iterator<Item = &LocalVariableType<'a>>
+    {
+        self.attributes
+            .iter()
+            .filter_map(|attribute| {
+                if let Attribute::LocalVariableTypeTable {
+                    local_variable_types,
+                } = attribute
+                {
+                    Some(local_variable_types.iter())
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .filter(move |local_variable_type| local_variable_type.covers(pc))
+    }
+
+    /// The types at the start of basic blocks, which class files
+    /// from version 50 onwards need for verification by type checking
+    pub fn stack_map_table(&self) -> Option<&StackMapTable<'a>>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::StackMapTable(stack_map_table) = attribute {
+                Some(stack_map_table)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ExceptionHandler
+{
+    start_pc: u16,
+    end_pc: u16,
+    handler_pc: u16,
+    catch_type: Option<u16>,
+}
+
+impl ExceptionHandler
+{
+    fn parse(cursor: &mut Cursor, code_length: u32, constant_pool: &ConstantPool) -> Result<Self>
+    {
+        let offset = cursor.position();
+        let start_pc = cursor.read_integer::<u16>()?;
+        let end_pc = cursor.read_integer::<u16>()?;
+        let handler_pc = cursor.read_integer::<u16>()?;
+        let catch_type = cursor.read_integer::<u16>()?;
+
+        // end_pc is exclusive, so it is the only one allowed to equal the code length
+        let code_length = code_length as usize;
+        if start_pc >= end_pc || end_pc as usize > code_length || handler_pc as usize >= code_length
+        {
+            Err(Located::new(
+                Error::InvalidExceptionHandler {
+                    start_pc,
+                    end_pc,
+                    handler_pc,
+                },
+                offset,
+            ))?
+        }
+
+        // A catch type of 0 makes the handler catch every exception, as used by finally
+        let catch_type = match catch_type {
+            0 => None,
+            catch_type => {
+                constant_pool
+                    .class_index(catch_type)
+                    .map_err(|err| cursor.locate(err))?;
+
+                Some(catch_type)
+            }
+        };
+
+        Ok(ExceptionHandler {
+            start_pc,
+            end_pc,
+            handler_pc,
+            catch_type,
+        })
+    }
+
+    pub fn start_pc(&self) -> u16
+    {
+        self.start_pc
+    }
+
+    pub fn end_pc(&self) -> u16
+    {
+        self.end_pc
+    }
+
+    pub fn handler_pc(&self) -> u16
+    {
+        self.handler_pc
+    }
+
+    /// The constant pool index of the Class constant of the caught
+    /// exception, or `None` when the handler catches everything
+    pub fn catch_type(&self) -> Option<u16>
+    {
+        self.catch_type
+    }
+}
@@ -0,0 +1,1163 @@
+use std::{any::Any, borrow::Cow, fmt};
+
+use crate::{
+    parse::{
+        access::{InnerClassAccess, ParameterAccess},
+        constant_pool::{error::ConstantKind, ConstantPool},
+        context::{Located, Segment},
+        cursor::Cursor,
+        descriptor, mutf8,
+    },
+    raw,
+};
+
+use self::error::{Error, Result};
+
+mod annotation;
+mod code;
+mod record;
+mod stack_map;
+
+pub use annotation::{
+    Annotation, ElementValue, ElementValuePair, LocalvarTarget, TargetInfo, TargetType,
+    TypeAnnotation, TypePathEntry, TypePathKind,
+};
+pub use code::{Code, ExceptionHandler};
+pub use record::RecordComponent;
+pub use stack_map::{Frame, StackMapFrame, StackMapTable, VerificationType};
+
+/// Decodes attributes the parser does not know about on its own,
+/// such as the metadata emitted by the Kotlin or Scala compilers
+pub trait AttributeDecoder
+{
+    /// The name of the attributes this decoder handles, which is never
+    /// consulted for the attributes the parser decodes on its own
+    fn name(&self) -> &str;
+
+    /// Decodes the body of an attribute, everything after
+    /// the `attribute_length` item, which `cursor` is over
+    fn decode(
+        &self,
+        cursor: &mut Cursor<'_>,
+        constant_pool: &ConstantPool<'_>,
+    ) -> std::result::Result<Box<dyn CustomAttribute>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// The value an [`AttributeDecoder`] produces
+pub trait CustomAttribute: fmt::Debug + Any {}
+
+impl<T> CustomAttribute for T where T: fmt::Debug + Any {}
+
+impl dyn CustomAttribute
+{
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: CustomAttribute,
+    {
+        let any: &dyn Any = self;
+
+        any.downcast_ref()
+    }
+}
+
+#[derive(Debug)]
+pub enum Attribute<'a>
+{
+    ConstantValue(ConstantValue<'a>),
+
+    Code(Code<'a>),
+
+    StackMapTable(StackMapTable<'a>),
+
+    /// The checked exceptions a method may throw, as the
+    /// constant pool indices of their Class constants
+    Exceptions
+    {
+        exception_indices: Box<[u16]>,
+    },
+
+    LineNumberTable
+    {
+        line_numbers: Box<[LineNumber]>,
+    },
+
+    LocalVariableTable
+    {
+        local_variables: Box<[LocalVariable<'a>]>,
+    },
+
+    LocalVariableTypeTable
+    {
+        local_variable_types: Box<[LocalVariableType<'a>]>,
+    },
+
+    /// The name of the source file the class was compiled from,
+    /// as the constant pool index of a Utf8 constant
+    SourceFile
+    {
+        sourcefile_index: u16,
+    },
+
+    /// Extended debugging information, such as the SMAP
+    /// mapping of JSP pages to their generated classes
+    SourceDebugExtension
+    {
+        debug_extension: Cow<'a, str>,
+    },
+
+    /// The generic signature of a class, member or record component,
+    /// as the constant pool index of a Utf8 constant
+    Signature
+    {
+        signature_index: u16,
+    },
+
+    RuntimeVisibleAnnotations
+    {
+        annotations: Box<[Annotation<'a>]>,
+    },
+
+    RuntimeInvisibleAnnotations
+    {
+        annotations: Box<[Annotation<'a>]>,
+    },
+
+    /// The annotations of each parameter of a method, which may
+    /// leave out the implicit parameters of some constructors
+    RuntimeVisibleParameterAnnotations
+    {
+        parameter_annotations: Box<[Box<[Annotation<'a>]>]>,
+    },
+
+    RuntimeInvisibleParameterAnnotations
+    {
+        parameter_annotations: Box<[Box<[Annotation<'a>]>]>,
+    },
+
+    RuntimeVisibleTypeAnnotations
+    {
+        type_annotations: Box<[TypeAnnotation<'a>]>,
+    },
+
+    RuntimeInvisibleTypeAnnotations
+    {
+        type_annotations: Box<[TypeAnnotation<'a>]>,
+    },
+
+    /// The default value of an element of an annotation interface
+    AnnotationDefault
+    {
+        default_value: ElementValue<'a>,
+    },
+
+    BootstrapMethods
+    {
+        bootstrap_methods: Box<[BootstrapMethod]>,
+    },
+
+    /// The classes that are not members of a package which the class refers to,
+    /// among them the class itself if it is nested, and those it declares
+    InnerClasses
+    {
+        classes: Box<[InnerClass]>,
+    },
+
+    /// The class, and the method if there is one, that a local or anonymous class is declared in
+    EnclosingMethod
+    {
+        class_index: u16,
+        /// The NameAndType constant of the method
+        method_index: Option<u16>,
+    },
+
+    /// The class at the top of the nest the class is a member of
+    NestHost
+    {
+        host_class_index: u16,
+    },
+
+    /// The classes the nest hosted by the class consists of, which may access its private members
+    NestMembers
+    {
+        class_indices: Box<[u16]>,
+    },
+
+    Record
+    {
+        components: Box<[RecordComponent<'a>]>,
+    },
+
+    /// The classes that may directly extend or implement a sealed class or interface
+    PermittedSubclasses
+    {
+        class_indices: Box<[u16]>,
+    },
+
+    /// Marks a member that does not appear in the source code, from before `ACC_SYNTHETIC`
+    Synthetic,
+
+    Deprecated,
+
+    MethodParameters
+    {
+        parameters: Box<[MethodParameter<'a>]>,
+    },
+
+    /// An attribute decoded by one of the decoders the class was parsed with
+    Custom
+    {
+        name: Cow<'a, str>,
+        value: Box<dyn CustomAttribute>,
+    },
+
+    /// An attribute that neither the JVM specification nor any of the decoders
+    /// the class was parsed with defines, left as the bytes of its info
+    Unknown
+    {
+        name: Cow<'a, str>,
+        info: &'a [u8],
+    },
+}
+
+/// The initial value of a static field
+#[derive(Debug, Clone)]
+pub enum ConstantValue<'a>
+{
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(Cow<'a, str>),
+}
+
+/// The method that links an invokedynamic call site or computes a
+/// dynamically-computed constant, with the arguments it is called with
+#[derive(Debug, Clone)]
+pub struct BootstrapMethod
+{
+    bootstrap_method_ref: u16,
+    bootstrap_arguments: Box<[u16]>,
+}
+
+impl BootstrapMethod
+{
+    /// The constant pool index of the MethodHandle constant of the bootstrap method
+    pub fn bootstrap_method_ref(&self) -> u16
+    {
+        self.bootstrap_method_ref
+    }
+
+    /// The constant pool indices of the static arguments, which
+    /// are all loadable constants that [`ConstantPool::loadable`] resolves
+    pub fn bootstrap_arguments(&self) -> &[u16]
+    {
+        &self.bootstrap_arguments
+    }
+}
+
+/// A class or interface that is not a member of a package, with the
+/// class it is a member of and the name it has in the source code
+#[derive(Debug, Clone, Copy)]
+pub struct InnerClass
+{
+    inner_class_info_index: u16,
+    outer_class_info_index: Option<u16>,
+    inner_name_index: Option<u16>,
+    inner_class_access_flags: InnerClassAccess,
+}
+
+impl InnerClass
+{
+    fn parse(cursor: &mut Cursor<'_>, constant_pool: &ConstantPool<'_>) -> Result<Self>
+    {
+        let inner_class_info_index = cursor.read_integer::<u16>()?;
+        constant_pool
+            .class_index(inner_class_info_index)
+            .map_err(|err| cursor.locate(err))?;
+
+        // Local and anonymous classes are not members of a class,
+        // and anonymous classes have no name either
+        let outer_class_info_index = match cursor.read_integer::<u16>()? {
+            0 => None,
+            outer_class_info_index => {
+                constant_pool
+                    .class_index(outer_class_info_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                Some(outer_class_info_index)
+            }
+        };
+        let inner_name_index = match cursor.read_integer::<u16>()? {
+            0 => None,
+            inner_name_index => {
+                constant_pool
+                    .utf8_index(inner_name_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                Some(inner_name_index)
+            }
+        };
+
+        let inner_class_access_flags = cursor.read_integer::<u16>()?;
+        let inner_class_access_flags = InnerClassAccess::new(inner_class_access_flags)
+            .map_err(|err| cursor.locate(Error::Access(err)))?;
+
+        Ok(InnerClass {
+            inner_class_info_index,
+            outer_class_info_index,
+            inner_name_index,
+            inner_class_access_flags,
+        })
+    }
+
+    /// The constant pool index of the Class constant of the class
+    pub fn inner_class_info_index(&self) -> u16
+    {
+        self.inner_class_info_index
+    }
+
+    /// The constant pool index of the Class constant of the class it is a member of
+    pub fn outer_class_info_index(&self) -> Option<u16>
+    {
+        self.outer_class_info_index
+    }
+
+    /// The constant pool index of the Utf8 constant of its simple name
+    pub fn inner_name_index(&self) -> Option<u16>
+    {
+        self.inner_name_index
+    }
+
+    /// The flags the class was declared with in the source code, which
+    /// unlike those of the class file may make it private or static
+    pub fn inner_class_access_flags(&self) -> InnerClassAccess
+    {
+        self.inner_class_access_flags
+    }
+}
+
+/// The name and flags of a parameter of a method
+#[derive(Debug, Clone)]
+pub struct MethodParameter<'a>
+{
+    name: Option<Cow<'a, str>>,
+    access_flags: ParameterAccess,
+}
+
+impl<'a> MethodParameter<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        let name = match cursor.read_integer::<u16>()? {
+            0 => None,
+            name_index => Some(
+                constant_pool
+                    .utf8_index(name_index)
+                    .map_err(|err| cursor.locate(err))?,
+            ),
+        };
+        let access_flags = ParameterAccess::new(cursor.read_integer::<u16>()?);
+
+        Ok(MethodParameter {
+            name: name.map(|name_index| constant_pool.utf8_cow_at(name_index)),
+            access_flags,
+        })
+    }
+
+    /// The name of the parameter, which compilers may leave out, such as for synthetic ones
+    pub fn name(&self) -> Option<&str>
+    {
+        self.name.as_deref()
+    }
+
+    pub fn access_flags(&self) -> ParameterAccess
+    {
+        self.access_flags
+    }
+}
+
+/// Marks the start of the code generated for a line of source code
+#[derive(Debug, Clone, Copy)]
+pub struct LineNumber
+{
+    start_pc: u16,
+    line_number: u16,
+}
+
+impl LineNumber
+{
+    pub fn start_pc(&self) -> u16
+    {
+        self.start_pc
+    }
+
+    pub fn line_number(&self) -> u16
+    {
+        self.line_number
+    }
+}
+
+/// The name and type of a local variable over the range of code it has a value in
+#[derive(Debug, Clone)]
+pub struct LocalVariable<'a>
+{
+    start_pc: u16,
+    length: u16,
+    name: Cow<'a, str>,
+    descriptor: Cow<'a, str>,
+    index: u16,
+}
+
+impl<'a> LocalVariable<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        let start_pc = cursor.read_integer::<u16>()?;
+        let length = cursor.read_integer::<u16>()?;
+        let name_index = cursor.read_integer::<u16>()?;
+        let name_index = constant_pool
+            .utf8_index(name_index)
+            .map_err(|err| cursor.locate(err))?;
+        let descriptor_index = cursor.read_integer::<u16>()?;
+        let descriptor_index = constant_pool
+            .utf8_index(descriptor_index)
+            .map_err(|err| cursor.locate(err))?;
+        let index = cursor.read_integer::<u16>()?;
+
+        Ok(LocalVariable {
+            start_pc,
+            length,
+            name: constant_pool.utf8_cow_at(name_index),
+            descriptor: constant_pool.utf8_cow_at(descriptor_index),
+            index,
+        })
+    }
+
+    pub fn start_pc(&self) -> u16
+    {
+        self.start_pc
+    }
+
+    pub fn length(&self) -> u16
+    {
+        self.length
+    }
+
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    /// The field descriptor of the variable's type, such as `Ljava/lang/String;`
+    pub fn descriptor(&self) -> &str
+    {
+        &self.descriptor
+    }
+
+    /// The local variable slot holding the variable, which for
+    /// longs and doubles is the first of the two it takes up
+    pub fn index(&self) -> u16
+    {
+        self.index
+    }
+
+    /// Whether the variable has a value at `pc`
+    pub fn covers(&self, pc: u16) -> bool
+    {
+        covers(self.start_pc, self.length, pc)
+    }
+}
+
+/// The generic type of a local variable, given for
+/// those whose type involves type variables or arguments
+#[derive(Debug, Clone)]
+pub struct LocalVariableType<'a>
+{
+    start_pc: u16,
+    length: u16,
+    name: Cow<'a, str>,
+    signature: Cow<'a, str>,
+    index: u16,
+}
+
+impl<'a> LocalVariableType<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        // Entries only differ from those of a LocalVariableTable
+        // in giving a signature rather than a descriptor
+        let LocalVariable {
+            start_pc,
+            length,
+            name,
+            descriptor: signature,
+            index,
+        } = LocalVariable::parse(cursor, constant_pool)?;
+
+        Ok(LocalVariableType {
+            start_pc,
+            length,
+            name,
+            signature,
+            index,
+        })
+    }
+
+    pub fn start_pc(&self) -> u16
+    {
+        self.start_pc
+    }
+
+    pub fn length(&self) -> u16
+    {
+        self.length
+    }
+
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    /// The field signature of the variable's type, such as `Ljava/util/List<TT;>;`
+    pub fn signature(&self) -> &str
+    {
+        &self.signature
+    }
+
+    pub fn index(&self) -> u16
+    {
+        self.index
+    }
+
+    /// Whether the variable has a value at `pc`
+    pub fn covers(&self, pc: u16) -> bool
+    {
+        covers(self.start_pc, self.length, pc)
+    }
+}
+
+/// Whether the range of code starting at `start_pc` and `length` bytes long includes `pc`
+fn covers(start_pc: u16, length: u16, pc: u16) -> bool
+{
+    (start_pc as u32..start_pc as u32 + length as u32).contains(&(pc as u32))
+}
+
+impl<'a> Attribute<'a>
+{
+    pub(super) fn parse_attribute_infos(
+        attribute_infos: &[raw::AttributeInfo<'a>],
+        constant_pool: &ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> Result<Box<[Self]>>
+    {
+        attribute_infos
+            .iter()
+            .enumerate()
+            .map(|(index, attribute_info)| {
+                Attribute::parse_attribute_info(index, attribute_info, constant_pool, decoders)
+            })
+            .collect()
+    }
+
+    fn parse_attribute_info(
+        index: usize,
+        attribute_info: &raw::AttributeInfo<'a>,
+        constant_pool: &ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> Result<Self>
+    {
+        let name_index = constant_pool
+            .utf8_index(attribute_info.attribute_name_index)
+            .map_err(|err| {
+                Located::new(err, attribute_info.offset)
+                    .within(Segment::Item("attribute_name_index"))
+                    .within(Segment::Attribute { index, name: None })
+            })?;
+        let name = constant_pool.utf8_at(name_index);
+
+        Attribute::decode(name_index, name, attribute_info, constant_pool, decoders).map_err(
+            |err| {
+                err.within(Segment::Attribute {
+                    index,
+                    name: Some(name.into()),
+                })
+            },
+        )
+    }
+
+    fn decode(
+        name_index: usize,
+        name: &str,
+        attribute_info: &raw::AttributeInfo<'a>,
+        constant_pool: &ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> Result<Self>
+    {
+        // The info of an attribute follows its name index and length
+        let mut cursor = Cursor::with_offset(attribute_info.info, attribute_info.offset + 6);
+
+        // Decoders are only consulted for attributes we do not decode ourselves, so
+        // that those the rest of the crate relies on are always what it expects
+        let attribute = match name {
+            "ConstantValue" => {
+                let constantvalue_index = cursor.read_integer::<u16>()?;
+
+                let kind = constant_pool
+                    .kind_of(constantvalue_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                let value = match kind {
+                    (ConstantKind::Integer, index) => {
+                        ConstantValue::Integer(constant_pool.integer_at(index))
+                    }
+                    (ConstantKind::Float, index) => {
+                        ConstantValue::Float(constant_pool.float_at(index))
+                    }
+                    (ConstantKind::Long, index) => {
+                        ConstantValue::Long(constant_pool.long_at(index))
+                    }
+                    (ConstantKind::Double, index) => {
+                        ConstantValue::Double(constant_pool.double_at(index))
+                    }
+                    (ConstantKind::String, index) => {
+                        ConstantValue::String(constant_pool.string_cow_at(index))
+                    }
+                    (actual, _) => Err(cursor.locate(Error::InvalidConstantValue(actual)))?,
+                };
+
+                Attribute::ConstantValue(value)
+            }
+
+            "Code" => Attribute::Code(Code::parse(&mut cursor, constant_pool, decoders)?),
+
+            "StackMapTable" => {
+                Attribute::StackMapTable(StackMapTable::parse(&mut cursor, constant_pool)?)
+            }
+
+            "Exceptions" => Attribute::Exceptions {
+                exception_indices: parse_class_indices(&mut cursor, constant_pool)?,
+            },
+
+            "LineNumberTable" => {
+                let line_number_table_length = cursor.read_integer::<u16>()?;
+
+                let line_numbers = (0..line_number_table_length)
+                    .map(|_| {
+                        let start_pc = cursor.read_integer::<u16>()?;
+                        let line_number = cursor.read_integer::<u16>()?;
+
+                        Ok(LineNumber {
+                            start_pc,
+                            line_number,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+
+                Attribute::LineNumberTable { line_numbers }
+            }
+
+            "LocalVariableTable" => {
+                let local_variable_table_length = cursor.read_integer::<u16>()?;
+
+                let local_variables = (0..local_variable_table_length)
+                    .map(|_| LocalVariable::parse(&mut cursor, constant_pool))
+                    .collect::<Result<_>>()?;
+
+                Attribute::LocalVariableTable { local_variables }
+            }
+
+            "LocalVariableTypeTable" => {
+                let local_variable_type_table_length = cursor.read_integer::<u16>()?;
+
+                let local_variable_types = (0..local_variable_type_table_length)
+                    .map(|_| LocalVariableType::parse(&mut cursor, constant_pool))
+                    .collect::<Result<_>>()?;
+
+                Attribute::LocalVariableTypeTable {
+                    local_variable_types,
+                }
+            }
+
+            "Signature" => {
+                let signature_index = cursor.read_integer::<u16>()?;
+
+                constant_pool
+                    .utf8_index(signature_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                Attribute::Signature { signature_index }
+            }
+
+            "SourceFile" => {
+                let sourcefile_index = cursor.read_integer::<u16>()?;
+
+                constant_pool
+                    .utf8_index(sourcefile_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                Attribute::SourceFile { sourcefile_index }
+            }
+
+            "SourceDebugExtension" => {
+                // The attribute is nothing but the string, without a length of its own
+                let offset = cursor.position();
+                let debug_extension = cursor.read_bytes(cursor.len())?;
+
+                Attribute::SourceDebugExtension {
+                    debug_extension: mutf8::decode(debug_extension)
+                        .map_err(|err| Located::new(Error::Mutf8(err), offset))?,
+                }
+            }
+
+            "RuntimeVisibleAnnotations" => Attribute::RuntimeVisibleAnnotations {
+                annotations: Annotation::parse_many(&mut cursor, constant_pool)?,
+            },
+
+            "RuntimeInvisibleAnnotations" => Attribute::RuntimeInvisibleAnnotations {
+                annotations: Annotation::parse_many(&mut cursor, constant_pool)?,
+            },
+
+            "RuntimeVisibleParameterAnnotations" => Attribute::RuntimeVisibleParameterAnnotations {
+                parameter_annotations: parse_parameter_annotations(&mut cursor, constant_pool)?,
+            },
+
+            "RuntimeInvisibleParameterAnnotations" => {
+                Attribute::RuntimeInvisibleParameterAnnotations {
+                    parameter_annotations: parse_parameter_annotations(&mut cursor, constant_pool)?,
+                }
+            }
+
+            "RuntimeVisibleTypeAnnotations" => Attribute::RuntimeVisibleTypeAnnotations {
+                type_annotations: TypeAnnotation::parse_many(&mut cursor, constant_pool)?,
+            },
+
+            "RuntimeInvisibleTypeAnnotations" => Attribute::RuntimeInvisibleTypeAnnotations {
+                type_annotations: TypeAnnotation::parse_many(&mut cursor, constant_pool)?,
+            },
+
+            "AnnotationDefault" => Attribute::AnnotationDefault {
+                default_value: ElementValue::parse(&mut cursor, constant_pool, 0)?,
+            },
+
+            "BootstrapMethods" => {
+                let num_bootstrap_methods = cursor.read_integer::<u16>()?;
+
+                let bootstrap_methods = (0..num_bootstrap_methods)
+                    .map(|_| {
+                        let bootstrap_method_ref = cursor.read_integer::<u16>()?;
+                        constant_pool
+                            .check_index(bootstrap_method_ref, ConstantKind::MethodHandle)
+                            .map_err(|err| cursor.locate(err))?;
+
+                        let num_bootstrap_arguments = cursor.read_integer::<u16>()?;
+                        let bootstrap_arguments = (0..num_bootstrap_arguments)
+                            .map(|_| {
+                                let bootstrap_argument = cursor.read_integer::<u16>()?;
+                                constant_pool
+                                    .loadable(bootstrap_argument)
+                                    .map_err(|err| cursor.locate(err))?;
+
+                                Ok(bootstrap_argument)
+                            })
+                            .collect::<Result<_>>()?;
+
+                        Ok(BootstrapMethod {
+                            bootstrap_method_ref,
+                            bootstrap_arguments,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+
+                Attribute::BootstrapMethods { bootstrap_methods }
+            }
+
+            "InnerClasses" => {
+                let number_of_classes = cursor.read_integer::<u16>()?;
+
+                let classes = (0..number_of_classes)
+                    .map(|_| InnerClass::parse(&mut cursor, constant_pool))
+                    .collect::<Result<_>>()?;
+
+                Attribute::InnerClasses { classes }
+            }
+
+            "EnclosingMethod" => {
+                let class_index = cursor.read_integer::<u16>()?;
+                constant_pool
+                    .class_index(class_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                // Classes declared in initializers are not enclosed by a method
+                let method_index = match cursor.read_integer::<u16>()? {
+                    0 => None,
+                    method_index => {
+                        constant_pool
+                            .check_index(method_index, ConstantKind::NameAndType)
+                            .map_err(|err| cursor.locate(err))?;
+
+                        Some(method_index)
+                    }
+                };
+
+                Attribute::EnclosingMethod {
+                    class_index,
+                    method_index,
+                }
+            }
+
+            "NestHost" => {
+                let host_class_index = cursor.read_integer::<u16>()?;
+                constant_pool
+                    .class_index(host_class_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                Attribute::NestHost { host_class_index }
+            }
+
+            "NestMembers" => Attribute::NestMembers {
+                class_indices: parse_class_indices(&mut cursor, constant_pool)?,
+            },
+
+            "Record" => {
+                let components_count = cursor.read_integer::<u16>()?;
+
+                let components = (0..components_count)
+                    .map(|index| {
+                        RecordComponent::parse(&mut cursor, constant_pool, decoders)
+                            .map_err(|err| err.within(Segment::RecordComponent(index as usize)))
+                    })
+                    .collect::<Result<_>>()?;
+
+                Attribute::Record { components }
+            }
+
+            "PermittedSubclasses" => Attribute::PermittedSubclasses {
+                class_indices: parse_class_indices(&mut cursor, constant_pool)?,
+            },
+
+            "Synthetic" => Attribute::Synthetic,
+
+            "Deprecated" => Attribute::Deprecated,
+
+            "MethodParameters" => {
+                let parameters_count = cursor.read_integer::<u8>()?;
+
+                let parameters = (0..parameters_count)
+                    .map(|_| MethodParameter::parse(&mut cursor, constant_pool))
+                    .collect::<Result<_>>()?;
+
+                Attribute::MethodParameters { parameters }
+            }
+
+            _ => match decoders.iter().find(|decoder| decoder.name() == name) {
+                Some(decoder) => {
+                    let value = decoder
+                        .decode(&mut cursor, constant_pool)
+                        .map_err(|source| {
+                            Located::new(
+                                Error::Decoder {
+                                    name: name.into(),
+                                    source,
+                                },
+                                attribute_info.offset + 6,
+                            )
+                        })?;
+
+                    Attribute::Custom {
+                        name: constant_pool.utf8_cow_at(name_index),
+                        value,
+                    }
+                }
+
+                None => {
+                    return Ok(Attribute::Unknown {
+                        name: constant_pool.utf8_cow_at(name_index),
+                        info: attribute_info.info,
+                    })
+                }
+            },
+        };
+
+        if !cursor.is_empty() {
+            Err(Located::new(
+                Error::TrailingBytes {
+                    name: name.into(),
+                    count: cursor.len(),
+                },
+                cursor.position(),
+            ))?
+        }
+
+        Ok(attribute)
+    }
+}
+
+/// Parses the Signature attribute among `attributes` with `parse`, if there is
+/// one, with `invalid` making the error for a signature that it rejects
+pub(super) fn parse_signature<T, E>(
+    attributes: &[Attribute<'_>],
+    attribute_infos: &[raw::AttributeInfo<'_>],
+    constant_pool: &ConstantPool<'_>,
+    parse: fn(&str) -> descriptor::error::Result<T>,
+    invalid: fn(Box<str>, descriptor::error::Error) -> E,
+) -> std::result::Result<Option<T>, Located<E>>
+{
+    let signature = attributes
+        .iter()
+        .enumerate()
+        .find_map(|(index, attribute)| {
+            if let Attribute::Signature { signature_index } = attribute {
+                Some((index, *signature_index))
+            } else {
+                None
+            }
+        });
+
+    signature
+        .map(|(index, signature_index)| {
+            let signature = constant_pool.utf8_at(constant_pool.checked_index(signature_index));
+
+            parse(signature).map_err(|source| {
+                // The signature index is the first item of the attribute's info
+                Located::new(
+                    invalid(signature.into(), source),
+                    attribute_infos[index].offset + 6,
+                )
+                .within(Segment::Item("signature_index"))
+                .within(Segment::Attribute {
+                    index,
+                    name: Some("Signature".into()),
+                })
+            })
+        })
+        .transpose()
+}
+
+/// Parses a count followed by that many indices of Class constants
+fn parse_class_indices(
+    cursor: &mut Cursor<'_>,
+    constant_pool: &ConstantPool<'_>,
+) -> Result<Box<[u16]>>
+{
+    let number_of_classes = cursor.read_integer::<u16>()?;
+
+    (0..number_of_classes)
+        .map(|_| {
+            let class_index = cursor.read_integer::<u16>()?;
+            constant_pool
+                .class_index(class_index)
+                .map_err(|err| cursor.locate(err))?;
+
+            Ok(class_index)
+        })
+        .collect()
+}
+
+fn parse_parameter_annotations<'a>(
+    cursor: &mut Cursor<'a>,
+    constant_pool: &ConstantPool<'a>,
+) -> Result<Box<[Box<[Annotation<'a>]>]>>
+{
+    let num_parameters = cursor.read_integer::<u8>()?;
+
+    (0..num_parameters)
+        .map(|_| Annotation::parse_many(cursor, constant_pool))
+        .collect()
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    use crate::parse::{access, constant_pool, context::Located, cursor, descriptor, mutf8, raw};
+
+    pub type Result<T> = result::Result<T, Located<Error>>;
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        Cursor(cursor::Error),
+        Raw(raw::error::Error),
+        ConstantPool(constant_pool::error::Error),
+        Mutf8(mutf8::error::Error),
+        Access(access::error::Error),
+        InvalidConstantValue(constant_pool::error::ConstantKind),
+        InvalidCodeLength(u32),
+        InvalidStackMapFrameType(u8),
+        InvalidVerificationType(u8),
+        InvalidElementValueTag(u8),
+        NestingTooDeep,
+        InvalidTargetType(u8),
+        InvalidTypePathKind(u8),
+        InvalidStackMapFrameOffset
+        {
+            offset: u32,
+            code_length: u32,
+        },
+        ChopUnderflow
+        {
+            offset: u16,
+            count: usize,
+            locals: usize,
+        },
+        InvalidExceptionHandler
+        {
+            start_pc: u16,
+            end_pc: u16,
+            handler_pc: u16,
+        },
+        InvalidSignature
+        {
+            signature: Box<str>,
+            source: descriptor::error::Error,
+        },
+        TrailingBytes
+        {
+            name: Box<str>,
+            count: usize,
+        },
+        Decoder
+        {
+            name: Box<str>,
+            source: Box<dyn error::Error + Send + Sync>,
+        },
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::Cursor(cursor_err) => write!(f, "{cursor_err}"),
+                Error::Raw(raw_err) => write!(f, "{raw_err}"),
+                Error::ConstantPool(constant_pool_err) => write!(f, "{constant_pool_err}"),
+                Error::Mutf8(mutf8_err) => write!(f, "{mutf8_err}"),
+                Error::Access(access_err) => write!(f, "{access_err}"),
+                Error::InvalidConstantValue(kind) => {
+                    write!(
+                        f,
+                        "constant value attribute cannot refer to a {} constant",
+                        format!("{kind:?}").to_ascii_lowercase()
+                    )
+                }
+                Error::InvalidCodeLength(code_length) => {
+                    write!(f, "invalid code length {code_length}")
+                }
+                Error::InvalidStackMapFrameType(frame_type) => {
+                    write!(f, "invalid stack map frame type {frame_type}")
+                }
+                Error::InvalidVerificationType(tag) => {
+                    write!(f, "invalid verification type tag {tag}")
+                }
+                Error::InvalidElementValueTag(tag) => {
+                    write!(f, "invalid element value tag {tag}")
+                }
+                Error::NestingTooDeep => {
+                    write!(
+                        f,
+                        "element values are nested more than {} deep",
+                        super::annotation::MAX_NESTING_DEPTH
+                    )
+                }
+                Error::InvalidTargetType(target_type) => {
+                    write!(f, "invalid type annotation target type {target_type:#04x}")
+                }
+                Error::InvalidTypePathKind(type_path_kind) => {
+                    write!(f, "invalid type path kind {type_path_kind}")
+                }
+                Error::InvalidStackMapFrameOffset {
+                    offset,
+                    code_length,
+                } => {
+                    write!(
+                        f,
+                        "stack map frame at offset {offset} is past the end \
+                         of the code, which is {code_length} long"
+                    )
+                }
+                Error::ChopUnderflow {
+                    offset,
+                    count,
+                    locals,
+                } => {
+                    write!(
+                        f,
+                        "stack map frame at offset {offset} chops {count} locals, \
+                         but there are only {locals}"
+                    )
+                }
+                Error::InvalidExceptionHandler {
+                    start_pc,
+                    end_pc,
+                    handler_pc,
+                } => {
+                    write!(
+                        f,
+                        "invalid exception handler covering {start_pc}..{end_pc} \
+                         with its handler at {handler_pc}"
+                    )
+                }
+                Error::InvalidSignature { signature, source } => {
+                    write!(f, "invalid signature {signature:?}: {source}")
+                }
+                Error::TrailingBytes { name, count } => {
+                    write!(f, "found {count} trailing bytes after the {name} attribute")
+                }
+                Error::Decoder { name, source } => {
+                    write!(f, "failed decoding the {name} attribute: {source}")
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            match self {
+                Error::Cursor(cursor_err) => Some(cursor_err),
+                Error::Raw(raw_err) => Some(raw_err),
+                Error::ConstantPool(constant_pool_err) => Some(constant_pool_err),
+                Error::Mutf8(mutf8_err) => Some(mutf8_err),
+                Error::Access(access_err) => Some(access_err),
+                Error::InvalidSignature { source, .. } => Some(source),
+                Error::Decoder { source, .. } => Some(source.as_ref()),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<cursor::Error> for Located<Error>
+    {
+        fn from(cursor_err: cursor::Error) -> Self
+        {
+            let offset = cursor_err.offset();
+
+            Located::new(Error::Cursor(cursor_err), offset)
+        }
+    }
+
+    impl From<Located<raw::error::Error>> for Located<Error>
+    {
+        fn from(raw_err: Located<raw::error::Error>) -> Self
+        {
+            raw_err.map(Error::Raw)
+        }
+    }
+
+    impl From<Located<constant_pool::error::Error>> for Located<Error>
+    {
+        fn from(constant_pool_err: Located<constant_pool::error::Error>) -> Self
+        {
+            constant_pool_err.map(Error::ConstantPool)
+        }
+    }
+
+    impl From<constant_pool::error::Error> for Error
+    {
+        fn from(constant_pool_err: constant_pool::error::Error) -> Self
+        {
+            Error::ConstantPool(constant_pool_err)
+        }
+    }
+}
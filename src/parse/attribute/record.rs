@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+
+use crate::{
+    parse::{
+        self, constant_pool::ConstantPool, cursor::Cursor, descriptor::FieldType,
+        signature::FieldSignature,
+    },
+    raw,
+};
+
+use super::{
+    error::{Error, Result},
+    parse_signature, Annotation, Attribute, AttributeDecoder, TypeAnnotation,
+};
+
+/// A component of a record class, which the class declares
+/// a private field and an accessor method for as well
+#[derive(Debug)]
+pub struct RecordComponent<'a>
+{
+    name: Cow<'a, str>,
+    descriptor: FieldType,
+
+    attributes: Box<[Attribute<'a>]>,
+
+    signature: Option<FieldSignature>,
+}
+
+impl<'a> RecordComponent<'a>
+{
+    pub(super) fn parse(
+        cursor: &mut Cursor<'a>,
+        constant_pool: &ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> Result<Self>
+    {
+        let name_index = cursor.read_integer::<u16>()?;
+        let name_index = constant_pool
+            .utf8_index(name_index)
+            .map_err(|err| cursor.locate(err))?;
+        let descriptor_index = cursor.read_integer::<u16>()?;
+        let descriptor = constant_pool
+            .utf8_index(descriptor_index)
+            .and_then(|descriptor_index| constant_pool.field_descriptor_at(descriptor_index))
+            .map_err(|err| cursor.locate(err))?;
+
+        let attribute_infos = raw::AttributeInfo::parse_many(cursor)?;
+        let attributes =
+            Attribute::parse_attribute_infos(&attribute_infos, constant_pool, decoders)?;
+
+        // Components are generic the way fields are, so they take a field signature
+        let signature = parse_signature(
+            &attributes,
+            &attribute_infos,
+            constant_pool,
+            FieldSignature::parse,
+            |signature, source| Error::InvalidSignature { signature, source },
+        )?;
+
+        Ok(RecordComponent {
+            name: constant_pool.utf8_cow_at(name_index),
+            descriptor,
+
+            attributes,
+
+            signature,
+        })
+    }
+
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    pub fn descriptor(&self) -> &FieldType
+    {
+        &self.descriptor
+    }
+
+    pub fn attributes(&self) -> &[Attribute<'a>]
+    {
+        &self.attributes
+    }
+
+    /// The generic type of the component, if it involves type variables or parameterized types
+    pub fn signature(&self) -> Option<&FieldSignature>
+    {
+        self.signature.as_ref()
+    }
+
+    /// The annotations on the component, the runtime visible ones followed by the invisible ones
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation<'a>>
+    {
+        parse::annotations(&self.attributes)
+    }
+
+    /// The annotation of the given type, such as `Ljavax/annotation/Nonnull;`,
+    /// if the component has it
+    pub fn annotation(&self, type_descriptor: &str) -> Option<&Annotation<'a>>
+    {
+        self.annotations()
+            .find(|annotation| annotation.type_descriptor() == type_descriptor)
+    }
+
+    /// The annotations on types used in the type of the component, the
+    /// runtime visible ones followed by the invisible ones
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation<'a>>
+    {
+        parse::type_annotations(&self.attributes)
+    }
+}
@@ -0,0 +1,379 @@
+use std::borrow::Cow;
+
+use crate::parse::{
+    constant_pool::ConstantPool,
+    cursor::Cursor,
+    descriptor::{BaseType, FieldType, MethodDescriptor},
+};
+
+use super::error::{Error, Result};
+
+/// The type of a local variable or operand stack entry as seen by the verifier
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VerificationType<'a>
+{
+    Top,
+    /// Any of int, short, char, byte and boolean
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    /// The receiver of a constructor before it called another constructor
+    UninitializedThis,
+    /// An instance of a class or array, given by the name of its class constant,
+    /// which is the descriptor for arrays
+    Object(Cow<'a, str>),
+    /// The object created by the `new` instruction at an offset, before its constructor ran
+    Uninitialized
+    {
+        offset: u16,
+    },
+}
+
+impl<'a> VerificationType<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        let tag = cursor.read_integer::<u8>()?;
+
+        Ok(match tag {
+            0 => VerificationType::Top,
+            1 => VerificationType::Integer,
+            2 => VerificationType::Float,
+            3 => VerificationType::Double,
+            4 => VerificationType::Long,
+            5 => VerificationType::Null,
+            6 => VerificationType::UninitializedThis,
+            7 => {
+                let cpool_index = cursor.read_integer::<u16>()?;
+                let index = constant_pool
+                    .class_index(cpool_index)
+                    .map_err(|err| cursor.locate(err))?;
+
+                VerificationType::Object(constant_pool.class_name_cow_at(index))
+            }
+            8 => VerificationType::Uninitialized {
+                offset: cursor.read_integer::<u16>()?,
+            },
+            tag => Err(cursor.locate(Error::InvalidVerificationType(tag)))?,
+        })
+    }
+
+    fn parse_many(
+        cursor: &mut Cursor<'a>,
+        count: u16,
+        constant_pool: &ConstantPool<'a>,
+    ) -> Result<Box<[Self]>>
+    {
+        (0..count)
+            .map(|_| VerificationType::parse(cursor, constant_pool))
+            .collect()
+    }
+
+    /// The type a parameter of that type starts out as
+    fn of_field_type(field_type: &FieldType) -> Self
+    {
+        match field_type {
+            FieldType::Base(BaseType::Float) => VerificationType::Float,
+            FieldType::Base(BaseType::Double) => VerificationType::Double,
+            FieldType::Base(BaseType::Long) => VerificationType::Long,
+            FieldType::Base(..) => VerificationType::Integer,
+            FieldType::Object(class_name) => {
+                VerificationType::Object(Cow::Owned(class_name.to_string()))
+            }
+            array => VerificationType::Object(Cow::Owned(array.to_string())),
+        }
+    }
+}
+
+/// A frame of a StackMapTable attribute, which gives the types
+/// at its offset relative to those of the previous frame
+#[derive(Debug, Clone)]
+pub enum StackMapFrame<'a>
+{
+    /// The same locals as the previous frame and an empty stack
+    Same
+    {
+        offset_delta: u8
+    },
+    SameExtended
+    {
+        offset_delta: u16
+    },
+    /// The same locals as the previous frame and a single stack entry
+    SameLocals1StackItem
+    {
+        offset_delta: u8,
+        stack: VerificationType<'a>,
+    },
+    SameLocals1StackItemExtended
+    {
+        offset_delta: u16,
+        stack: VerificationType<'a>,
+    },
+    /// The locals of the previous frame without its last
+    /// `count` locals, between 1 and 3, and an empty stack
+    Chop
+    {
+        offset_delta: u16, count: u8
+    },
+    /// The locals of the previous frame followed by between 1 and 3 more, and an empty stack
+    Append
+    {
+        offset_delta: u16,
+        locals: Box<[VerificationType<'a>]>,
+    },
+    Full
+    {
+        offset_delta: u16,
+        locals: Box<[VerificationType<'a>]>,
+        stack: Box<[VerificationType<'a>]>,
+    },
+}
+
+impl<'a> StackMapFrame<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        let frame_type = cursor.read_integer::<u8>()?;
+
+        Ok(match frame_type {
+            0..=63 => StackMapFrame::Same {
+                offset_delta: frame_type,
+            },
+            64..=127 => StackMapFrame::SameLocals1StackItem {
+                offset_delta: frame_type - 64,
+                stack: VerificationType::parse(cursor, constant_pool)?,
+            },
+            247 => StackMapFrame::SameLocals1StackItemExtended {
+                offset_delta: cursor.read_integer::<u16>()?,
+                stack: VerificationType::parse(cursor, constant_pool)?,
+            },
+            248..=250 => StackMapFrame::Chop {
+                offset_delta: cursor.read_integer::<u16>()?,
+                count: 251 - frame_type,
+            },
+            251 => StackMapFrame::SameExtended {
+                offset_delta: cursor.read_integer::<u16>()?,
+            },
+            252..=254 => StackMapFrame::Append {
+                offset_delta: cursor.read_integer::<u16>()?,
+                locals: VerificationType::parse_many(
+                    cursor,
+                    frame_type as u16 - 251,
+                    constant_pool,
+                )?,
+            },
+            255 => {
+                let offset_delta = cursor.read_integer::<u16>()?;
+                let number_of_locals = cursor.read_integer::<u16>()?;
+                let locals = VerificationType::parse_many(cursor, number_of_locals, constant_pool)?;
+                let number_of_stack_items = cursor.read_integer::<u16>()?;
+                let stack =
+                    VerificationType::parse_many(cursor, number_of_stack_items, constant_pool)?;
+
+                StackMapFrame::Full {
+                    offset_delta,
+                    locals,
+                    stack,
+                }
+            }
+            // 128 through 246 are reserved for future use
+            frame_type => Err(cursor.locate(Error::InvalidStackMapFrameType(frame_type)))?,
+        })
+    }
+
+    /// The `frame_type` item the frame is written with
+    pub fn frame_type(&self) -> u8
+    {
+        match self {
+            StackMapFrame::Same { offset_delta } => *offset_delta,
+            StackMapFrame::SameLocals1StackItem { offset_delta, .. } => 64 + offset_delta,
+            StackMapFrame::SameLocals1StackItemExtended { .. } => 247,
+            StackMapFrame::Chop { count, .. } => 251 - count,
+            StackMapFrame::SameExtended { .. } => 251,
+            StackMapFrame::Append { locals, .. } => 251 + locals.len() as u8,
+            StackMapFrame::Full { .. } => 255,
+        }
+    }
+
+    pub fn offset_delta(&self) -> u16
+    {
+        match self {
+            StackMapFrame::Same { offset_delta }
+            | StackMapFrame::SameLocals1StackItem { offset_delta, .. } => *offset_delta as u16,
+            StackMapFrame::SameExtended { offset_delta }
+            | StackMapFrame::SameLocals1StackItemExtended { offset_delta, .. }
+            | StackMapFrame::Chop { offset_delta, .. }
+            | StackMapFrame::Append { offset_delta, .. }
+            | StackMapFrame::Full { offset_delta, .. } => *offset_delta,
+        }
+    }
+}
+
+/// The types of the locals and the stack at an offset of the code, with
+/// longs and doubles taking up a single entry rather than two slots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame<'a>
+{
+    offset: u16,
+    locals: Box<[VerificationType<'a>]>,
+    stack: Box<[VerificationType<'a>]>,
+}
+
+impl<'a> Frame<'a>
+{
+    /// The implicit frame at the start of a method, holding its receiver and parameters
+    pub fn initial(
+        class_name: Cow<'a, str>,
+        method_name: &str,
+        descriptor: &MethodDescriptor,
+        is_static: bool,
+    ) -> Self
+    {
+        let receiver = if is_static {
+            None
+        } else if method_name == "<init>" && class_name != "java/lang/Object" {
+            Some(VerificationType::UninitializedThis)
+        } else {
+            Some(VerificationType::Object(class_name))
+        };
+
+        let locals = receiver
+            .into_iter()
+            .chain(
+                descriptor
+                    .parameters()
+                    .iter()
+                    .map(VerificationType::of_field_type),
+            )
+            .collect();
+
+        Frame {
+            offset: 0,
+            locals,
+            stack: Box::new([]),
+        }
+    }
+
+    pub fn offset(&self) -> u16
+    {
+        self.offset
+    }
+
+    pub fn locals(&self) -> &[VerificationType<'a>]
+    {
+        &self.locals
+    }
+
+    pub fn stack(&self) -> &[VerificationType<'a>]
+    {
+        &self.stack
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StackMapTable<'a>
+{
+    entries: Box<[StackMapFrame<'a>]>,
+}
+
+impl<'a> StackMapTable<'a>
+{
+    pub(super) fn parse(cursor: &mut Cursor<'a>, constant_pool: &ConstantPool<'a>) -> Result<Self>
+    {
+        let number_of_entries = cursor.read_integer::<u16>()?;
+
+        let entries = (0..number_of_entries)
+            .map(|_| StackMapFrame::parse(cursor, constant_pool))
+            .collect::<Result<_>>()?;
+
+        Ok(StackMapTable { entries })
+    }
+
+    pub fn entries(&self) -> &[StackMapFrame<'a>]
+    {
+        &self.entries
+    }
+
+    /// The offsets of the frames, each past the one before it
+    pub fn offsets(&self) -> impl Iterator<Item = u32> + '_
+    {
+        self.entries
+            .iter()
+            .scan(None, |previous: &mut Option<u32>, frame| {
+                // Every frame but the first is at least one past the previous one
+                let offset = match *previous {
+                    None => frame.offset_delta() as u32,
+                    Some(previous) => previous + frame.offset_delta() as u32 + 1,
+                };
+                *previous = Some(offset);
+
+                Some(offset)
+            })
+    }
+
+    /// Applies the frames one after another, starting from the
+    /// implicit frame of the method given by [`Frame::initial`]
+    ///
+    /// The initial frame itself is not part of the result
+    pub fn expand(&self, initial: &Frame<'a>) -> std::result::Result<Box<[Frame<'a>]>, Error>
+    {
+        let mut locals = initial.locals.to_vec();
+
+        self.entries
+            .iter()
+            .zip(self.offsets())
+            .map(|(frame, offset)| {
+                // Code::parse made sure the offsets are within the code
+                let offset = offset as u16;
+
+                let stack = match frame {
+                    StackMapFrame::Same { .. } | StackMapFrame::SameExtended { .. } => {
+                        Box::new([]) as Box<[_]>
+                    }
+                    StackMapFrame::SameLocals1StackItem { stack, .. }
+                    | StackMapFrame::SameLocals1StackItemExtended { stack, .. } => {
+                        Box::new([stack.clone()])
+                    }
+                    StackMapFrame::Chop { count, .. } => {
+                        let count = *count as usize;
+                        if count > locals.len() {
+                            Err(Error::ChopUnderflow {
+                                offset,
+                                count,
+                                locals: locals.len(),
+                            })?
+                        }
+                        locals.truncate(locals.len() - count);
+
+                        Box::new([])
+                    }
+                    StackMapFrame::Append {
+                        locals: appended, ..
+                    } => {
+                        locals.extend_from_slice(appended);
+
+                        Box::new([])
+                    }
+                    StackMapFrame::Full {
+                        locals: full,
+                        stack,
+                        ..
+                    } => {
+                        locals = full.to_vec();
+
+                        stack.clone()
+                    }
+                };
+
+                Ok(Frame {
+                    offset,
+                    locals: locals.as_slice().into(),
+                    stack,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,461 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{parse::mutf8, raw};
+
+use super::{
+    error::{self, ConstantKind},
+    ConstantPool, Dynamic, InvokeDynamic, MemberRef, MethodHandle, NameAndType, Reference,
+    ReferenceKind,
+};
+
+/// Index 0 of the constant pool is never used, and its count is a u16
+const MAX_ENTRIES: usize = u16::MAX as usize - 1;
+
+/// Builds a constant pool for a class file to be written, handing out the index
+/// of an equal constant whenever one is already in the pool instead of adding it
+///
+/// A builder starting from an existing constant pool keeps all of its constants at
+/// their indices, so that the instructions and attributes referring to them stay valid
+#[derive(Debug, Default)]
+pub struct ConstantPoolBuilder<'a>
+{
+    entries: Vec<Entry<'a>>,
+    indices: HashMap<Entry<'a>, u16>,
+}
+
+/// A constant, with Utf8 constants owning their bytes unless taken from a class file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Entry<'a>
+{
+    Utf8(Cow<'a, [u8]>),
+    /// Any other constant, which never borrows anything
+    Constant(raw::Constant<'a>),
+}
+
+impl<'a> ConstantPoolBuilder<'a>
+{
+    pub fn new() -> Self
+    {
+        ConstantPoolBuilder::default()
+    }
+
+    /// Starts from the constants of a class file as they were parsed
+    pub fn from_raw(constants: &[raw::Constant<'a>]) -> Self
+    {
+        let mut builder = ConstantPoolBuilder::new();
+        for constant in constants {
+            let entry = match *constant {
+                raw::Constant::Utf8 { bytes } => Entry::Utf8(Cow::Borrowed(bytes)),
+                constant => Entry::Constant(constant),
+            };
+
+            builder.keep(entry);
+        }
+
+        builder
+    }
+
+    /// Starts from the constants of a parsed class
+    pub fn from_pool(constant_pool: &ConstantPool<'a>) -> Self
+    {
+        // Constants refer to each other by their local indices,
+        // which have to be mapped back to the original ones
+        let original = |kind, index| constant_pool.original_index(kind, index) as u16;
+
+        let mut builder = ConstantPoolBuilder::new();
+        for (kind, &index) in constant_pool.kinds.iter().zip(constant_pool.indices.iter()) {
+            let constant = match kind {
+                ConstantKind::Utf8 => {
+                    // The bytes as they were read, keeping unpaired surrogates
+                    let bytes = constant_pool.encoded_utf8_at(index);
+
+                    builder.keep(Entry::Utf8(Cow::Borrowed(bytes)));
+                    continue;
+                }
+                ConstantKind::Class => raw::Constant::Class {
+                    name_index: original(
+                        ConstantKind::Utf8,
+                        constant_pool.classes[index].name_index,
+                    ),
+                },
+                ConstantKind::FieldRef
+                | ConstantKind::MethodRef
+                | ConstantKind::InterfaceMethodRef => {
+                    let reference = match kind {
+                        ConstantKind::FieldRef => Reference::Field(index),
+                        ConstantKind::MethodRef => Reference::Method(index),
+                        _ => Reference::InterfaceMethod(index),
+                    };
+                    let (class_index, name_and_type_index) = constant_pool.reference_at(&reference);
+                    let class_index = original(ConstantKind::Class, class_index);
+                    let name_and_type_index =
+                        original(ConstantKind::NameAndType, name_and_type_index);
+
+                    match kind {
+                        ConstantKind::FieldRef => raw::Constant::FieldRef {
+                            class_index,
+                            name_and_type_index,
+                        },
+                        ConstantKind::MethodRef => raw::Constant::MethodRef {
+                            class_index,
+                            name_and_type_index,
+                        },
+                        _ => raw::Constant::InterfaceMethodRef {
+                            class_index,
+                            name_and_type_index,
+                        },
+                    }
+                }
+                ConstantKind::String => raw::Constant::String {
+                    string_index: original(
+                        ConstantKind::Utf8,
+                        constant_pool.strings[index].string_index,
+                    ),
+                },
+                ConstantKind::Integer => raw::Constant::Integer {
+                    bytes: constant_pool.integer_at(index) as u32,
+                },
+                ConstantKind::Float => raw::Constant::Float {
+                    bytes: constant_pool.float_at(index).to_bits(),
+                },
+                ConstantKind::Long => wide(ConstantKind::Long, constant_pool.long_at(index) as u64),
+                ConstantKind::Double => wide(
+                    ConstantKind::Double,
+                    constant_pool.double_at(index).to_bits(),
+                ),
+                ConstantKind::NameAndType => {
+                    let NameAndType {
+                        name_index,
+                        descriptor_index,
+                    } = constant_pool.name_and_types[index];
+
+                    raw::Constant::NameAndType {
+                        name_index: original(ConstantKind::Utf8, name_index),
+                        descriptor_index: original(ConstantKind::Utf8, descriptor_index),
+                    }
+                }
+                ConstantKind::MethodHandle => {
+                    let MethodHandle {
+                        reference_kind,
+                        ref reference,
+                    } = constant_pool.method_handles[index];
+                    let reference_index = match *reference {
+                        Reference::Field(index) => original(ConstantKind::FieldRef, index),
+                        Reference::Method(index) => original(ConstantKind::MethodRef, index),
+                        Reference::InterfaceMethod(index) => {
+                            original(ConstantKind::InterfaceMethodRef, index)
+                        }
+                    };
+
+                    raw::Constant::MethodHandle {
+                        reference_kind: reference_kind as u8,
+                        reference_index,
+                    }
+                }
+                ConstantKind::MethodType => raw::Constant::MethodType {
+                    descriptor_index: original(
+                        ConstantKind::Utf8,
+                        constant_pool.method_types[index].descriptor_index,
+                    ),
+                },
+                ConstantKind::Dynamic => {
+                    let Dynamic {
+                        bootstrap_method_attr_index,
+                        name_and_type_index,
+                    } = constant_pool.dynamics[index];
+
+                    raw::Constant::Dynamic {
+                        bootstrap_method_attr_index: bootstrap_method_attr_index as u16,
+                        name_and_type_index: original(
+                            ConstantKind::NameAndType,
+                            name_and_type_index,
+                        ),
+                    }
+                }
+                ConstantKind::InvokeDynamic => {
+                    let InvokeDynamic {
+                        bootstrap_method_attr_index,
+                        name_and_type_index,
+                    } = constant_pool.invoke_dynamics[index];
+
+                    raw::Constant::InvokeDynamic {
+                        bootstrap_method_attr_index: bootstrap_method_attr_index as u16,
+                        name_and_type_index: original(
+                            ConstantKind::NameAndType,
+                            name_and_type_index,
+                        ),
+                    }
+                }
+                ConstantKind::Module => raw::Constant::Module {
+                    name_index: original(
+                        ConstantKind::Utf8,
+                        constant_pool.modules[index].name_index,
+                    ),
+                },
+                ConstantKind::Package => raw::Constant::Package {
+                    name_index: original(
+                        ConstantKind::Utf8,
+                        constant_pool.packages[index].name_index,
+                    ),
+                },
+                ConstantKind::Unusable => raw::Constant::Unusable,
+            };
+
+            builder.keep(Entry::Constant(constant));
+        }
+
+        builder
+    }
+
+    pub fn utf8(&mut self, string: &str) -> error::Result<u16>
+    {
+        self.intern(Entry::Utf8(Cow::Owned(mutf8::encode(string).into_vec())))
+    }
+
+    pub fn class(&mut self, name: &str) -> error::Result<u16>
+    {
+        let name_index = self.utf8(name)?;
+
+        self.intern(Entry::Constant(raw::Constant::Class { name_index }))
+    }
+
+    pub fn string(&mut self, string: &str) -> error::Result<u16>
+    {
+        let string_index = self.utf8(string)?;
+
+        self.intern(Entry::Constant(raw::Constant::String { string_index }))
+    }
+
+    pub fn integer(&mut self, value: i32) -> error::Result<u16>
+    {
+        self.intern(Entry::Constant(raw::Constant::Integer {
+            bytes: value as u32,
+        }))
+    }
+
+    /// Interns a float by its bits, so that every NaN and
+    /// both zeros are kept apart from each other
+    pub fn float(&mut self, value: f32) -> error::Result<u16>
+    {
+        self.intern(Entry::Constant(raw::Constant::Float {
+            bytes: value.to_bits(),
+        }))
+    }
+
+    /// Interns a long, which takes up the index following its own as well
+    pub fn long(&mut self, value: i64) -> error::Result<u16>
+    {
+        self.intern(Entry::Constant(wide(ConstantKind::Long, value as u64)))
+    }
+
+    /// Interns a double by its bits, which takes up the index following its own as well
+    pub fn double(&mut self, value: f64) -> error::Result<u16>
+    {
+        self.intern(Entry::Constant(wide(ConstantKind::Double, value.to_bits())))
+    }
+
+    pub fn name_and_type(&mut self, name: &str, descriptor: &str) -> error::Result<u16>
+    {
+        let name_index = self.utf8(name)?;
+        let descriptor_index = self.utf8(descriptor)?;
+
+        self.intern(Entry::Constant(raw::Constant::NameAndType {
+            name_index,
+            descriptor_index,
+        }))
+    }
+
+    pub fn field_ref(&mut self, reference: MemberRef<'_>) -> error::Result<u16>
+    {
+        let (class_index, name_and_type_index) = self.member_ref(reference)?;
+
+        self.intern(Entry::Constant(raw::Constant::FieldRef {
+            class_index,
+            name_and_type_index,
+        }))
+    }
+
+    pub fn method_ref(&mut self, reference: MemberRef<'_>) -> error::Result<u16>
+    {
+        let (class_index, name_and_type_index) = self.member_ref(reference)?;
+
+        self.intern(Entry::Constant(raw::Constant::MethodRef {
+            class_index,
+            name_and_type_index,
+        }))
+    }
+
+    pub fn interface_method_ref(&mut self, reference: MemberRef<'_>) -> error::Result<u16>
+    {
+        let (class_index, name_and_type_index) = self.member_ref(reference)?;
+
+        self.intern(Entry::Constant(raw::Constant::InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        }))
+    }
+
+    /// Interns a method handle along with the reference its kind calls for, which for
+    /// `REF_invokeStatic` and `REF_invokeSpecial` is an interface method reference
+    /// when `interface` is set, as the methods of interfaces are referred to that way
+    pub fn method_handle(
+        &mut self,
+        reference_kind: ReferenceKind,
+        reference: MemberRef<'_>,
+        interface: bool,
+    ) -> error::Result<u16>
+    {
+        let reference_index = match reference_kind {
+            ReferenceKind::GetField
+            | ReferenceKind::GetStatic
+            | ReferenceKind::PutField
+            | ReferenceKind::PutStatic => self.field_ref(reference)?,
+            ReferenceKind::InvokeInterface => self.interface_method_ref(reference)?,
+            ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial if interface => {
+                self.interface_method_ref(reference)?
+            }
+            _ => self.method_ref(reference)?,
+        };
+
+        self.intern(Entry::Constant(raw::Constant::MethodHandle {
+            reference_kind: reference_kind as u8,
+            reference_index,
+        }))
+    }
+
+    pub fn method_type(&mut self, descriptor: &str) -> error::Result<u16>
+    {
+        let descriptor_index = self.utf8(descriptor)?;
+
+        self.intern(Entry::Constant(raw::Constant::MethodType {
+            descriptor_index,
+        }))
+    }
+
+    /// Interns a dynamically-computed constant, whose bootstrap method
+    /// is the given entry of the BootstrapMethods attribute of the class
+    pub fn dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> error::Result<u16>
+    {
+        let name_and_type_index = self.name_and_type(name, descriptor)?;
+
+        self.intern(Entry::Constant(raw::Constant::Dynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }))
+    }
+
+    /// Interns an invokedynamic call site, whose bootstrap method is
+    /// the given entry of the BootstrapMethods attribute of the class
+    pub fn invoke_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> error::Result<u16>
+    {
+        let name_and_type_index = self.name_and_type(name, descriptor)?;
+
+        self.intern(Entry::Constant(raw::Constant::InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }))
+    }
+
+    /// Interns a module, which only `module-info` classes may refer to
+    pub fn module(&mut self, name: &str) -> error::Result<u16>
+    {
+        let name_index = self.utf8(name)?;
+
+        self.intern(Entry::Constant(raw::Constant::Module { name_index }))
+    }
+
+    /// Interns a package by its binary name, which only `module-info` classes may refer to
+    pub fn package(&mut self, name: &str) -> error::Result<u16>
+    {
+        let name_index = self.utf8(name)?;
+
+        self.intern(Entry::Constant(raw::Constant::Package { name_index }))
+    }
+
+    /// The constants built so far, borrowing the strings added to the builder,
+    /// ready to replace those of a class file with [`raw::ClassFile::set_constant_pool`]
+    pub fn constants(&self) -> Box<[raw::Constant<'_>]>
+    {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Utf8(bytes) => raw::Constant::Utf8 { bytes },
+                Entry::Constant(constant) => *constant,
+            })
+            .collect()
+    }
+
+    fn member_ref(&mut self, reference: MemberRef<'_>) -> error::Result<(u16, u16)>
+    {
+        let class_index = self.class(reference.class_name)?;
+        let name_and_type_index = self.name_and_type(reference.name, reference.descriptor)?;
+
+        Ok((class_index, name_and_type_index))
+    }
+
+    /// Adds a constant of an existing pool at the next index, even if it is a duplicate
+    fn keep(&mut self, entry: Entry<'a>)
+    {
+        let index = self.entries.len() as u16 + 1;
+        if entry != Entry::Constant(raw::Constant::Unusable) {
+            // Later duplicates are left alone, with lookups finding the first one
+            self.indices.entry(entry.clone()).or_insert(index);
+        }
+
+        self.entries.push(entry);
+    }
+
+    fn intern(&mut self, entry: Entry<'a>) -> error::Result<u16>
+    {
+        if let Some(&index) = self.indices.get(&entry) {
+            return Ok(index);
+        }
+
+        let is_wide = matches!(
+            entry,
+            Entry::Constant(raw::Constant::Long { .. } | raw::Constant::Double { .. })
+        );
+        let slots = if is_wide { 2 } else { 1 };
+        if self.entries.len() + slots > MAX_ENTRIES {
+            Err(error::Error::PoolFull)?
+        }
+
+        let index = self.entries.len() as u16 + 1;
+        self.indices.insert(entry.clone(), index);
+        self.entries.push(entry);
+        // The index following a long or double must never be referred to
+        if is_wide {
+            self.entries.push(Entry::Constant(raw::Constant::Unusable));
+        }
+
+        Ok(index)
+    }
+}
+
+/// Splits the bits of a long or double into the halves they are stored as
+fn wide<'a>(kind: ConstantKind, bits: u64) -> raw::Constant<'a>
+{
+    let high_bytes = (bits >> 32) as u32;
+    let low_bytes = bits as u32;
+
+    match kind {
+        ConstantKind::Long => raw::Constant::Long {
+            high_bytes,
+            low_bytes,
+        },
+        _ => raw::Constant::Double {
+            high_bytes,
+            low_bytes,
+        },
+    }
+}
@@ -0,0 +1,1735 @@
+use std::{borrow::Cow, fmt};
+
+use crate::raw;
+
+use super::{
+    context::{Located, Segment},
+    descriptor::{FieldType, MethodDescriptor},
+    disassemble, mutf8,
+};
+
+mod builder;
+
+pub use builder::ConstantPoolBuilder;
+
+use self::error::ConstantKind;
+
+/// Looks up the kind of the constant at `index`, alongside its position among the constants
+/// of that kind, with the valid indices running from 1 through the number of constants, but
+/// for those following long and double constants
+fn kind_of(
+    kinds: &[ConstantKind],
+    indices: &[usize],
+    index: u16,
+) -> error::Result<(ConstantKind, usize)>
+{
+    let position = (index as usize)
+        .checked_sub(1)
+        .filter(|position| *position < kinds.len())
+        .ok_or(error::Error::OutOfRangeIndex(index as usize))?;
+
+    let kind = kinds[position];
+    if kind == ConstantKind::Unusable {
+        Err(error::Error::UnusableIndex(index as usize))?
+    }
+
+    Ok((kind, indices[position]))
+}
+
+/// Looks up the position of the constant at `index` among the constants
+/// of its kind, checking that it is of the `expected` kind
+fn check_kind(
+    kinds: &[ConstantKind],
+    indices: &[usize],
+    index: u16,
+    expected: ConstantKind,
+) -> error::Result<usize>
+{
+    let (actual, index) = kind_of(kinds, indices, index)?;
+    if actual != expected {
+        Err(error::Error::UnexpectedConstantKind { expected, actual })?
+    }
+
+    Ok(index)
+}
+
+/// Checks a NameAndType descriptor, which describes a method when it
+/// starts with a parenthesis and a field otherwise
+fn check_descriptor(descriptor: &str) -> error::Result<()>
+{
+    let checked = if descriptor.starts_with('(') {
+        MethodDescriptor::parse(descriptor).map(drop)
+    } else {
+        FieldType::parse(descriptor).map(drop)
+    };
+
+    checked.map_err(|source| error::Error::InvalidDescriptor {
+        descriptor: descriptor.into(),
+        source,
+    })
+}
+
+#[derive(Debug)]
+pub struct ConstantPool<'a>
+{
+    classes: Box<[Class]>,
+    field_refs: Box<[FieldRef]>,
+    method_refs: Box<[MethodRef]>,
+    interface_method_refs: Box<[InterfaceMethodRef]>,
+    strings: Box<[constant::String]>,
+    integers: Box<[Integer]>,
+    floats: Box<[Float]>,
+    longs: Box<[Long]>,
+    doubles: Box<[Double]>,
+    name_and_types: Box<[NameAndType]>,
+    utf8s: Box<[Utf8<'a>]>,
+    method_handles: Box<[MethodHandle]>,
+    method_types: Box<[MethodType]>,
+    dynamics: Box<[Dynamic]>,
+    invoke_dynamics: Box<[InvokeDynamic]>,
+    modules: Box<[Module]>,
+    packages: Box<[Package]>,
+
+    /// The kind of the constant at every index, starting with index 1,
+    /// and where it is found among the constants of its kind
+    kinds: Box<[error::ConstantKind]>,
+    indices: Box<[usize]>,
+    /// The other way around, the index of every constant, grouped by kind
+    /// with the constants of each kind starting at their `kind_starts`
+    originals: Box<[usize]>,
+    kind_starts: [usize; KINDS],
+}
+
+/// The number of kinds of constants, of which unusable is the last
+const KINDS: usize = error::ConstantKind::Unusable as usize + 1;
+
+impl<'a> ConstantPool<'a>
+{
+    /// Resolves the constants in a single pass, in which they may refer to constants
+    /// that come after them, as the kind of every constant is known up front
+    ///
+    /// The major version of the class file decides which methods method handles may refer to,
+    /// and along with whether the class is a module, whether it may hold module and package
+    /// constants, which only the `module-info` classes of Java 9 onwards do
+    pub(super) fn new(
+        constant_pool: &[raw::Constant<'a>],
+        offsets: &[usize],
+        major: u16,
+        is_module: bool,
+    ) -> Result<Self, Located<error::Error>>
+    {
+        // Locates an error in the item of the constant at `original_index`
+        // that starts `item_offset` bytes after the constant's tag
+        let locate = |original_index: usize, item: &'static str, item_offset: usize| {
+            move |err: error::Error| {
+                Located::new(err, offsets[original_index] + item_offset)
+                    .within(Segment::Item(item))
+                    .within(Segment::Constant(original_index + 1))
+            }
+        };
+
+        let kinds = constant_pool
+            .iter()
+            .map(ConstantKind::from)
+            .collect::<Box<_>>();
+
+        let mut counts = [0; KINDS];
+        let indices = kinds
+            .iter()
+            .map(|kind| {
+                let count = &mut counts[*kind as usize];
+                *count += 1;
+
+                *count - 1
+            })
+            .collect::<Box<[usize]>>();
+
+        // The constants of each kind follow those of the kinds before it
+        let kind_starts = {
+            let mut start = 0;
+
+            counts.map(|count| {
+                start += count;
+
+                start - count
+            })
+        };
+        let originals = {
+            let mut originals = vec![0; kinds.len()].into_boxed_slice();
+            for (original_index, (kind, index)) in kinds.iter().zip(indices.iter()).enumerate() {
+                originals[kind_starts[*kind as usize] + index] = original_index + 1;
+            }
+
+            originals
+        };
+
+        // Every kind of constant only refers to kinds of constants that refer to none,
+        // so a constant referring to itself, or to a constant referring back to it,
+        // is caught as referring to a constant of an unexpected kind
+        let resolve = |index, expected| check_kind(&kinds, &indices, index, expected);
+
+        let mut classes = Vec::with_capacity(counts[ConstantKind::Class as usize]);
+        let mut field_refs = Vec::with_capacity(counts[ConstantKind::FieldRef as usize]);
+        let mut method_refs = Vec::with_capacity(counts[ConstantKind::MethodRef as usize]);
+        let mut interface_method_refs =
+            Vec::with_capacity(counts[ConstantKind::InterfaceMethodRef as usize]);
+        let mut strings = Vec::with_capacity(counts[ConstantKind::String as usize]);
+        let mut integers = Vec::with_capacity(counts[ConstantKind::Integer as usize]);
+        let mut floats = Vec::with_capacity(counts[ConstantKind::Float as usize]);
+        let mut longs = Vec::with_capacity(counts[ConstantKind::Long as usize]);
+        let mut doubles = Vec::with_capacity(counts[ConstantKind::Double as usize]);
+        let mut name_and_types = Vec::with_capacity(counts[ConstantKind::NameAndType as usize]);
+        let mut utf8s = Vec::with_capacity(counts[ConstantKind::Utf8 as usize]);
+        let mut method_handles = Vec::with_capacity(counts[ConstantKind::MethodHandle as usize]);
+        let mut method_types = Vec::with_capacity(counts[ConstantKind::MethodType as usize]);
+        let mut dynamics = Vec::with_capacity(counts[ConstantKind::Dynamic as usize]);
+        let mut invoke_dynamics = Vec::with_capacity(counts[ConstantKind::InvokeDynamic as usize]);
+        let mut modules = Vec::with_capacity(counts[ConstantKind::Module as usize]);
+        let mut packages = Vec::with_capacity(counts[ConstantKind::Package as usize]);
+
+        let allows_modules = major >= 53 && is_module;
+
+        for (original_index, constant) in constant_pool.iter().enumerate() {
+            let locate = |item, item_offset| locate(original_index, item, item_offset);
+
+            // Field and method references both refer to a class and a name and type
+            let resolve_member_ref = |class_index, name_and_type_index| {
+                let class_index =
+                    resolve(class_index, ConstantKind::Class).map_err(locate("class_index", 1))?;
+                let name_and_type_index = resolve(name_and_type_index, ConstantKind::NameAndType)
+                    .map_err(locate("name_and_type_index", 3))?;
+
+                Ok::<_, Located<error::Error>>((class_index, name_and_type_index))
+            };
+
+            match *constant {
+                raw::Constant::Class { name_index } => {
+                    let name_index =
+                        resolve(name_index, ConstantKind::Utf8).map_err(locate("name_index", 1))?;
+
+                    classes.push(Class { name_index });
+                }
+
+                raw::Constant::FieldRef {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    let (class_index, name_and_type_index) =
+                        resolve_member_ref(class_index, name_and_type_index)?;
+
+                    field_refs.push(FieldRef {
+                        class_index,
+                        name_and_type_index,
+                    });
+                }
+
+                raw::Constant::MethodRef {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    let (class_index, name_and_type_index) =
+                        resolve_member_ref(class_index, name_and_type_index)?;
+
+                    method_refs.push(MethodRef {
+                        class_index,
+                        name_and_type_index,
+                    });
+                }
+
+                raw::Constant::InterfaceMethodRef {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    let (class_index, name_and_type_index) =
+                        resolve_member_ref(class_index, name_and_type_index)?;
+
+                    interface_method_refs.push(InterfaceMethodRef {
+                        class_index,
+                        name_and_type_index,
+                    });
+                }
+
+                raw::Constant::String { string_index } => {
+                    let string_index = resolve(string_index, ConstantKind::Utf8)
+                        .map_err(locate("string_index", 1))?;
+
+                    strings.push(constant::String { string_index });
+                }
+
+                raw::Constant::Integer { bytes } => integers.push(Integer {
+                    value: bytes as i32,
+                }),
+
+                raw::Constant::Float { bytes } => floats.push(Float {
+                    value: f32::from_bits(bytes),
+                }),
+
+                raw::Constant::Long {
+                    high_bytes,
+                    low_bytes,
+                } => longs.push(Long {
+                    value: (((high_bytes as u64) << 32) | low_bytes as u64) as i64,
+                }),
+
+                raw::Constant::Double {
+                    high_bytes,
+                    low_bytes,
+                } => doubles.push(Double {
+                    value: f64::from_bits(((high_bytes as u64) << 32) | low_bytes as u64),
+                }),
+
+                raw::Constant::NameAndType {
+                    name_index,
+                    descriptor_index,
+                } => {
+                    let name_index =
+                        resolve(name_index, ConstantKind::Utf8).map_err(locate("name_index", 1))?;
+                    let descriptor_index = resolve(descriptor_index, ConstantKind::Utf8)
+                        .map_err(locate("descriptor_index", 3))?;
+
+                    name_and_types.push(NameAndType {
+                        name_index,
+                        descriptor_index,
+                    });
+                }
+
+                raw::Constant::Utf8 { bytes: encoded } => {
+                    let bytes = match mutf8::decode(encoded) {
+                        Ok(bytes) => bytes,
+                        // Java strings may hold unpaired surrogates, which read as U+FFFD,
+                        // while the encoded bytes keep them for writing them out again
+                        Err(mutf8::error::Error::UnpairedSurrogate { .. }) => {
+                            let units = mutf8::decode_utf16(encoded)
+                                .map_err(error::Error::from)
+                                .map_err(locate("bytes", 3))?;
+
+                            Cow::Owned(String::from_utf16_lossy(&units))
+                        }
+                        Err(err) => Err(locate("bytes", 3)(err.into()))?,
+                    };
+
+                    utf8s.push(Utf8 { bytes, encoded });
+                }
+
+                raw::Constant::MethodHandle {
+                    reference_kind,
+                    reference_index,
+                } => {
+                    let reference_kind = ReferenceKind::try_from(reference_kind)
+                        .map_err(locate("reference_kind", 1))?;
+
+                    let at_reference = locate("reference_index", 2);
+                    let reference = match kind_of(&kinds, &indices, reference_index)
+                        .map_err(at_reference)?
+                    {
+                        (ConstantKind::FieldRef, index)
+                            if reference_kind.expected_constant_kind()
+                                == ConstantKind::FieldRef =>
+                        {
+                            Reference::Field(index)
+                        }
+
+                        (ConstantKind::MethodRef, index)
+                            if reference_kind.expected_constant_kind()
+                                == ConstantKind::MethodRef =>
+                        {
+                            Reference::Method(index)
+                        }
+
+                        (ConstantKind::InterfaceMethodRef, index)
+                            if reference_kind == ReferenceKind::InvokeInterface =>
+                        {
+                            Reference::InterfaceMethod(index)
+                        }
+
+                        // Static and private interface methods are called through
+                        // invokestatic and invokespecial with interface method
+                        // references, which method handles may refer to since Java 8
+                        (ConstantKind::InterfaceMethodRef, index)
+                            if major >= 52
+                                && matches!(
+                                    reference_kind,
+                                    ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial
+                                ) =>
+                        {
+                            Reference::InterfaceMethod(index)
+                        }
+
+                        (actual, _) => Err(at_reference(error::Error::UnexpectedConstantKind {
+                            expected: reference_kind.expected_constant_kind(),
+                            actual,
+                        }))?,
+                    };
+
+                    method_handles.push(MethodHandle {
+                        reference_kind,
+                        reference,
+                    });
+                }
+
+                raw::Constant::MethodType { descriptor_index } => {
+                    let descriptor_index = resolve(descriptor_index, ConstantKind::Utf8)
+                        .map_err(locate("descriptor_index", 1))?;
+
+                    method_types.push(MethodType { descriptor_index });
+                }
+
+                raw::Constant::Dynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } => {
+                    let name_and_type_index =
+                        resolve(name_and_type_index, ConstantKind::NameAndType)
+                            .map_err(locate("name_and_type_index", 3))?;
+
+                    // The bootstrap method index points into the BootstrapMethods attribute
+                    // of the class, which check_bootstrap_method_attr_indices checks later
+                    dynamics.push(Dynamic {
+                        bootstrap_method_attr_index: bootstrap_method_attr_index as usize,
+                        name_and_type_index,
+                    });
+                }
+
+                raw::Constant::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } => {
+                    let name_and_type_index =
+                        resolve(name_and_type_index, ConstantKind::NameAndType)
+                            .map_err(locate("name_and_type_index", 3))?;
+
+                    // Like that of dynamic constants, the bootstrap method index points
+                    // into the BootstrapMethods attribute rather than the constant pool
+                    invoke_dynamics.push(InvokeDynamic {
+                        bootstrap_method_attr_index: bootstrap_method_attr_index as usize,
+                        name_and_type_index,
+                    });
+                }
+
+                raw::Constant::Module { name_index } => {
+                    if !allows_modules {
+                        Err(locate("tag", 0)(error::Error::OutsideModule(
+                            ConstantKind::Module,
+                        )))?
+                    }
+                    let name_index =
+                        resolve(name_index, ConstantKind::Utf8).map_err(locate("name_index", 1))?;
+
+                    modules.push(Module { name_index });
+                }
+
+                raw::Constant::Package { name_index } => {
+                    if !allows_modules {
+                        Err(locate("tag", 0)(error::Error::OutsideModule(
+                            ConstantKind::Package,
+                        )))?
+                    }
+                    let name_index =
+                        resolve(name_index, ConstantKind::Utf8).map_err(locate("name_index", 1))?;
+
+                    packages.push(Package { name_index });
+                }
+
+                raw::Constant::Unusable => {}
+            }
+        }
+
+        // The descriptors may only be decoded after the NameAndType constants referring to them
+        let name_and_types_start = kind_starts[ConstantKind::NameAndType as usize];
+        for (index, name_and_type) in name_and_types.iter().enumerate() {
+            let original_index = originals[name_and_types_start + index] - 1;
+
+            check_descriptor(&utf8s[name_and_type.descriptor_index].bytes).map_err(locate(
+                original_index,
+                "descriptor_index",
+                3,
+            ))?;
+        }
+
+        let constant_pool = ConstantPool {
+            classes: classes.into(),
+            field_refs: field_refs.into(),
+            method_refs: method_refs.into(),
+            interface_method_refs: interface_method_refs.into(),
+            strings: strings.into(),
+            integers: integers.into(),
+            floats: floats.into(),
+            longs: longs.into(),
+            doubles: doubles.into(),
+            name_and_types: name_and_types.into(),
+            utf8s: utf8s.into(),
+            method_handles: method_handles.into(),
+            method_types: method_types.into(),
+            dynamics: dynamics.into(),
+            invoke_dynamics: invoke_dynamics.into(),
+            modules: modules.into(),
+            packages: packages.into(),
+
+            kinds,
+            indices,
+            originals,
+            kind_starts,
+        };
+
+        // Which methods a method handle may refer to depends on their names as well
+        for (index, method_handle) in constant_pool.method_handles.iter().enumerate() {
+            let original_index = constant_pool.original_index(ConstantKind::MethodHandle, index);
+            let reference = constant_pool.reference_ref_at(&method_handle.reference);
+
+            method_handle
+                .reference_kind
+                .check_name(reference.name)
+                .map_err(locate(original_index - 1, "reference_index", 2))?;
+        }
+
+        Ok(constant_pool)
+    }
+
+    /// Looks up the Utf8 constant found at `index`
+    ///
+    /// Unpaired surrogates, which Java strings may hold but a `str` cannot,
+    /// read as U+FFFD, while [`ConstantPool::utf16`] gives them back exactly
+    pub fn utf8(&self, index: u16) -> error::Result<&str>
+    {
+        let index = self.utf8_index(index)?;
+
+        Ok(self.utf8_at(index))
+    }
+
+    /// Looks up the Utf8 constant found at `index` as the UTF-16 code
+    /// units of a Java string, unpaired surrogates included
+    pub fn utf16(&self, index: u16) -> error::Result<Box<[u16]>>
+    {
+        let index = self.utf8_index(index)?;
+
+        Ok(self.utf16_at(index))
+    }
+
+    /// The modified UTF-8 bytes of a Utf8 constant, as found in the class file
+    pub(super) fn encoded_utf8_at(&self, index: usize) -> &'a [u8]
+    {
+        self.utf8s[index].encoded
+    }
+
+    fn utf16_at(&self, index: usize) -> Box<[u16]>
+    {
+        // The bytes were decoded once already, so they are valid
+        mutf8::decode_utf16(self.encoded_utf8_at(index)).unwrap()
+    }
+
+    pub(super) fn utf8_index(&self, index: u16) -> error::Result<usize>
+    {
+        self.check_index(index, error::ConstantKind::Utf8)
+    }
+
+    pub(super) fn class_index(&self, index: u16) -> error::Result<usize>
+    {
+        self.check_index(index, error::ConstantKind::Class)
+    }
+
+    pub(super) fn utf8_at(&self, index: usize) -> &str
+    {
+        &self.utf8s[index].bytes
+    }
+
+    pub(super) fn field_descriptor_at(&self, index: usize) -> error::Result<FieldType>
+    {
+        let descriptor = self.utf8_at(index);
+
+        FieldType::parse(descriptor).map_err(|source| error::Error::InvalidDescriptor {
+            descriptor: descriptor.into(),
+            source,
+        })
+    }
+
+    pub(super) fn method_descriptor_at(&self, index: usize) -> error::Result<MethodDescriptor>
+    {
+        let descriptor = self.utf8_at(index);
+
+        MethodDescriptor::parse(descriptor).map_err(|source| error::Error::InvalidDescriptor {
+            descriptor: descriptor.into(),
+            source,
+        })
+    }
+
+    pub(super) fn class_name_at(&self, index: usize) -> &str
+    {
+        self.utf8_at(self.classes[index].name_index)
+    }
+
+    pub(super) fn integer_at(&self, index: usize) -> i32
+    {
+        self.integers[index].value
+    }
+
+    pub(super) fn float_at(&self, index: usize) -> f32
+    {
+        self.floats[index].value
+    }
+
+    pub(super) fn long_at(&self, index: usize) -> i64
+    {
+        self.longs[index].value
+    }
+
+    pub(super) fn double_at(&self, index: usize) -> f64
+    {
+        self.doubles[index].value
+    }
+
+    pub(super) fn string_at(&self, index: usize) -> &str
+    {
+        self.utf8_at(self.strings[index].string_index)
+    }
+
+    /// Shares a Utf8 constant with the values that outlive the constant pool,
+    /// which only copies the strings that decoding modified UTF-8 changed
+    pub(super) fn utf8_cow_at(&self, index: usize) -> Cow<'a, str>
+    {
+        self.utf8s[index].bytes.clone()
+    }
+
+    pub(super) fn string_cow_at(&self, index: usize) -> Cow<'a, str>
+    {
+        self.utf8_cow_at(self.strings[index].string_index)
+    }
+
+    pub(super) fn class_name_cow_at(&self, index: usize) -> Cow<'a, str>
+    {
+        self.utf8_cow_at(self.classes[index].name_index)
+    }
+
+    pub fn class_name(&self, index: u16) -> error::Result<&str>
+    {
+        let index = self.class_index(index)?;
+
+        Ok(self.class_name_at(index))
+    }
+
+    pub fn name_and_type(&self, index: u16) -> error::Result<(&str, &str)>
+    {
+        let index = self.check_index(index, error::ConstantKind::NameAndType)?;
+
+        Ok(self.name_and_type_at(index))
+    }
+
+    pub fn field_ref(&self, index: u16) -> error::Result<MemberRef<'_>>
+    {
+        let index = self.check_index(index, error::ConstantKind::FieldRef)?;
+        let FieldRef {
+            class_index,
+            name_and_type_index,
+        } = self.field_refs[index];
+
+        Ok(self.member_ref_at(class_index, name_and_type_index))
+    }
+
+    /// Looks up a method reference, which may point at either a class or an
+    /// interface method, as `invokestatic` and `invokespecial` allow both
+    pub fn method_ref(&self, index: u16) -> error::Result<MemberRef<'_>>
+    {
+        let (class_index, name_and_type_index) = match self.kind_of(index)? {
+            (error::ConstantKind::MethodRef, index) => {
+                let MethodRef {
+                    class_index,
+                    name_and_type_index,
+                } = self.method_refs[index];
+
+                (class_index, name_and_type_index)
+            }
+            (error::ConstantKind::InterfaceMethodRef, index) => {
+                let InterfaceMethodRef {
+                    class_index,
+                    name_and_type_index,
+                } = self.interface_method_refs[index];
+
+                (class_index, name_and_type_index)
+            }
+            (actual, _) => Err(error::Error::UnexpectedConstantKind {
+                expected: error::ConstantKind::MethodRef,
+                actual,
+            })?,
+        };
+
+        Ok(self.member_ref_at(class_index, name_and_type_index))
+    }
+
+    pub fn interface_method_ref(&self, index: u16) -> error::Result<MemberRef<'_>>
+    {
+        let index = self.check_index(index, error::ConstantKind::InterfaceMethodRef)?;
+        let InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        } = self.interface_method_refs[index];
+
+        Ok(self.member_ref_at(class_index, name_and_type_index))
+    }
+
+    /// Looks up an invokedynamic call site, returning the index of its bootstrap
+    /// method within the BootstrapMethods attribute alongside its name and descriptor
+    pub fn invoke_dynamic(&self, index: u16) -> error::Result<(usize, &str, &str)>
+    {
+        let index = self.check_index(index, error::ConstantKind::InvokeDynamic)?;
+        let InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } = self.invoke_dynamics[index];
+        let (name, descriptor) = self.name_and_type_at(name_and_type_index);
+
+        Ok((bootstrap_method_attr_index, name, descriptor))
+    }
+
+    /// Looks up a dynamically-computed constant, returning the index of its bootstrap
+    /// method within the BootstrapMethods attribute alongside its name and descriptor
+    pub fn dynamic(&self, index: u16) -> error::Result<(usize, &str, &str)>
+    {
+        let index = self.check_index(index, error::ConstantKind::Dynamic)?;
+        let Dynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } = self.dynamics[index];
+        let (name, descriptor) = self.name_and_type_at(name_and_type_index);
+
+        Ok((bootstrap_method_attr_index, name, descriptor))
+    }
+
+    /// Looks up the name of a module, such as `java.base`
+    pub fn module(&self, index: u16) -> error::Result<&str>
+    {
+        let index = self.check_index(index, error::ConstantKind::Module)?;
+
+        Ok(self.utf8_at(self.modules[index].name_index))
+    }
+
+    /// Looks up the binary name of a package, such as `java/lang`
+    pub fn package(&self, index: u16) -> error::Result<&str>
+    {
+        let index = self.check_index(index, error::ConstantKind::Package)?;
+
+        Ok(self.utf8_at(self.packages[index].name_index))
+    }
+
+    /// Checks that the InvokeDynamic and Dynamic constants refer to
+    /// one of the `count` entries of the BootstrapMethods attribute
+    pub(super) fn check_bootstrap_method_attr_indices(
+        &self,
+        count: usize,
+        offsets: &[usize],
+    ) -> Result<(), Located<error::Error>>
+    {
+        let dynamics = self
+            .dynamics
+            .iter()
+            .map(|dynamic| dynamic.bootstrap_method_attr_index)
+            .enumerate()
+            .map(|(index, bootstrap_method_attr_index)| {
+                (ConstantKind::Dynamic, index, bootstrap_method_attr_index)
+            });
+        let invoke_dynamics = self
+            .invoke_dynamics
+            .iter()
+            .map(|invoke_dynamic| invoke_dynamic.bootstrap_method_attr_index)
+            .enumerate()
+            .map(|(index, bootstrap_method_attr_index)| {
+                (
+                    ConstantKind::InvokeDynamic,
+                    index,
+                    bootstrap_method_attr_index,
+                )
+            });
+
+        for (kind, index, bootstrap_method_attr_index) in dynamics.chain(invoke_dynamics) {
+            if bootstrap_method_attr_index >= count {
+                let original_index = self.original_index(kind, index);
+
+                Err(Located::new(
+                    error::Error::BootstrapMethodOutOfRange {
+                        index: bootstrap_method_attr_index,
+                        count,
+                    },
+                    offsets[original_index - 1] + 1,
+                )
+                .within(Segment::Item("bootstrap_method_attr_index"))
+                .within(Segment::Constant(original_index)))?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Looks up a constant that `ldc`, `ldc_w` or `ldc2_w` can push onto the stack
+    pub fn loadable(&self, index: u16) -> error::Result<Loadable<'_>>
+    {
+        let loadable = match self.kind_of(index)? {
+            (error::ConstantKind::Integer, index) => Loadable::Integer(self.integer_at(index)),
+            (error::ConstantKind::Float, index) => Loadable::Float(self.float_at(index)),
+            (error::ConstantKind::Long, index) => Loadable::Long(self.long_at(index)),
+            (error::ConstantKind::Double, index) => Loadable::Double(self.double_at(index)),
+            (error::ConstantKind::String, index) => Loadable::String(self.string_at(index)),
+            (error::ConstantKind::Class, index) => Loadable::Class(self.class_name_at(index)),
+            (error::ConstantKind::MethodType, index) => {
+                Loadable::MethodType(self.utf8_at(self.method_types[index].descriptor_index))
+            }
+            (error::ConstantKind::MethodHandle, index) => {
+                let (reference_kind, reference) = self.method_handle_at(index);
+
+                Loadable::MethodHandle {
+                    reference_kind,
+                    reference,
+                }
+            }
+            (error::ConstantKind::Dynamic, index) => {
+                let Dynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } = self.dynamics[index];
+                let (name, descriptor) = self.name_and_type_at(name_and_type_index);
+
+                Loadable::Dynamic {
+                    bootstrap_method_attr_index,
+                    name,
+                    descriptor,
+                }
+            }
+            (actual, _) => Err(error::Error::UnexpectedConstantKind {
+                expected: error::ConstantKind::Integer,
+                actual,
+            })?,
+        };
+
+        Ok(loadable)
+    }
+
+    /// Looks up the constant at `index`, counting from 1 like the class file does,
+    /// or `None` for index 0, indices past the end and the unusable second index
+    /// of a long or double constant
+    pub fn get(&self, index: u16) -> Option<ConstantRef<'_>>
+    {
+        let (kind, index) = self.kind_of(index).ok()?;
+
+        let constant = match kind {
+            error::ConstantKind::Class => ConstantRef::Class(self.class_name_at(index)),
+            error::ConstantKind::FieldRef => {
+                ConstantRef::FieldRef(self.reference_ref_at(&Reference::Field(index)))
+            }
+            error::ConstantKind::MethodRef => {
+                ConstantRef::MethodRef(self.reference_ref_at(&Reference::Method(index)))
+            }
+            error::ConstantKind::InterfaceMethodRef => ConstantRef::InterfaceMethodRef(
+                self.reference_ref_at(&Reference::InterfaceMethod(index)),
+            ),
+            error::ConstantKind::String => ConstantRef::String(self.string_at(index)),
+            error::ConstantKind::Integer => ConstantRef::Integer(self.integer_at(index)),
+            error::ConstantKind::Float => ConstantRef::Float(self.float_at(index)),
+            error::ConstantKind::Long => ConstantRef::Long(self.long_at(index)),
+            error::ConstantKind::Double => ConstantRef::Double(self.double_at(index)),
+            error::ConstantKind::NameAndType => {
+                let (name, descriptor) = self.name_and_type_at(index);
+
+                ConstantRef::NameAndType { name, descriptor }
+            }
+            error::ConstantKind::Utf8 => ConstantRef::Utf8(self.utf8_at(index)),
+            error::ConstantKind::MethodHandle => {
+                let (reference_kind, reference) = self.method_handle_at(index);
+
+                ConstantRef::MethodHandle {
+                    reference_kind,
+                    reference,
+                }
+            }
+            error::ConstantKind::MethodType => {
+                ConstantRef::MethodType(self.utf8_at(self.method_types[index].descriptor_index))
+            }
+            error::ConstantKind::Dynamic => {
+                let Dynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } = self.dynamics[index];
+                let (name, descriptor) = self.name_and_type_at(name_and_type_index);
+
+                ConstantRef::Dynamic {
+                    bootstrap_method_attr_index,
+                    name,
+                    descriptor,
+                }
+            }
+            error::ConstantKind::InvokeDynamic => {
+                let InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                } = self.invoke_dynamics[index];
+                let (name, descriptor) = self.name_and_type_at(name_and_type_index);
+
+                ConstantRef::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name,
+                    descriptor,
+                }
+            }
+            error::ConstantKind::Module => {
+                ConstantRef::Module(self.utf8_at(self.modules[index].name_index))
+            }
+            error::ConstantKind::Package => {
+                ConstantRef::Package(self.utf8_at(self.packages[index].name_index))
+            }
+            error::ConstantKind::Unusable => return None,
+        };
+
+        Some(constant)
+    }
+
+    /// The constants alongside their indices, skipping the
+    /// unusable second indices of long and double constants
+    pub fn iter(&self) -> impl Iterator<Item = (u16, ConstantRef<'_>)>
+    {
+        (1..=self.kinds.len() as u16).filter_map(|index| Some((index, self.get(index)?)))
+    }
+
+    /// The number of indices the constants take up, which is one less than
+    /// the constant pool count of the class file, as index 0 is never used
+    pub fn len(&self) -> usize
+    {
+        self.kinds.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.kinds.is_empty()
+    }
+
+    pub fn string(&self, index: u16) -> error::Result<&str>
+    {
+        let index = self.check_index(index, error::ConstantKind::String)?;
+
+        Ok(self.string_at(index))
+    }
+
+    /// Looks up the String constant found at `index` as the UTF-16 code
+    /// units of a Java string, unpaired surrogates included
+    pub fn string_utf16(&self, index: u16) -> error::Result<Box<[u16]>>
+    {
+        let index = self.check_index(index, error::ConstantKind::String)?;
+
+        Ok(self.utf16_at(self.strings[index].string_index))
+    }
+
+    pub fn integer(&self, index: u16) -> error::Result<i32>
+    {
+        let index = self.check_index(index, error::ConstantKind::Integer)?;
+
+        Ok(self.integer_at(index))
+    }
+
+    pub fn float(&self, index: u16) -> error::Result<f32>
+    {
+        let index = self.check_index(index, error::ConstantKind::Float)?;
+
+        Ok(self.float_at(index))
+    }
+
+    pub fn long(&self, index: u16) -> error::Result<i64>
+    {
+        let index = self.check_index(index, error::ConstantKind::Long)?;
+
+        Ok(self.long_at(index))
+    }
+
+    pub fn double(&self, index: u16) -> error::Result<f64>
+    {
+        let index = self.check_index(index, error::ConstantKind::Double)?;
+
+        Ok(self.double_at(index))
+    }
+
+    /// Looks up the method descriptor of a MethodType constant
+    pub fn method_type(&self, index: u16) -> error::Result<&str>
+    {
+        let index = self.check_index(index, error::ConstantKind::MethodType)?;
+
+        Ok(self.utf8_at(self.method_types[index].descriptor_index))
+    }
+
+    pub fn method_handle(&self, index: u16) -> error::Result<(ReferenceKind, MemberRef<'_>)>
+    {
+        let index = self.check_index(index, error::ConstantKind::MethodHandle)?;
+
+        Ok(self.method_handle_at(index))
+    }
+
+    pub(super) fn method_handle_at(&self, index: usize) -> (ReferenceKind, MemberRef<'_>)
+    {
+        let MethodHandle {
+            reference_kind,
+            ref reference,
+        } = self.method_handles[index];
+
+        (reference_kind, self.reference_ref_at(reference))
+    }
+
+    fn reference_ref_at(&self, reference: &Reference) -> MemberRef<'_>
+    {
+        let (class_index, name_and_type_index) = self.reference_at(reference);
+
+        self.member_ref_at(class_index, name_and_type_index)
+    }
+
+    fn reference_at(&self, reference: &Reference) -> (usize, usize)
+    {
+        match *reference {
+            Reference::Field(index) => {
+                let FieldRef {
+                    class_index,
+                    name_and_type_index,
+                } = self.field_refs[index];
+
+                (class_index, name_and_type_index)
+            }
+            Reference::Method(index) => {
+                let MethodRef {
+                    class_index,
+                    name_and_type_index,
+                } = self.method_refs[index];
+
+                (class_index, name_and_type_index)
+            }
+            Reference::InterfaceMethod(index) => {
+                let InterfaceMethodRef {
+                    class_index,
+                    name_and_type_index,
+                } = self.interface_method_refs[index];
+
+                (class_index, name_and_type_index)
+            }
+        }
+    }
+
+    pub(super) fn name_and_type_at(&self, index: usize) -> (&str, &str)
+    {
+        let NameAndType {
+            name_index,
+            descriptor_index,
+        } = self.name_and_types[index];
+
+        (self.utf8_at(name_index), self.utf8_at(descriptor_index))
+    }
+
+    fn member_ref_at(&self, class_index: usize, name_and_type_index: usize) -> MemberRef<'_>
+    {
+        let class_name = self.class_name_at(class_index);
+        let (name, descriptor) = self.name_and_type_at(name_and_type_index);
+
+        MemberRef {
+            class_name,
+            name,
+            descriptor,
+        }
+    }
+
+    /// Returns the kind of the constant at `index`, alongside
+    /// its position within the entries of that kind
+    pub(super) fn kind_of(&self, index: u16) -> error::Result<(error::ConstantKind, usize)>
+    {
+        kind_of(&self.kinds, &self.indices, index)
+    }
+
+    /// Finds the original index of the `index`th constant of the given kind,
+    /// which is how javap and the instructions refer to constants
+    pub(super) fn original_index(&self, kind: error::ConstantKind, index: usize) -> usize
+    {
+        let kind = kind as usize;
+        let end = self
+            .kind_starts
+            .get(kind + 1)
+            .map_or(self.originals.len(), |end| *end);
+
+        self.originals[self.kind_starts[kind]..end]
+            .get(index)
+            .map_or(0, |original_index| *original_index)
+    }
+
+    pub(super) fn check_index(
+        &self,
+        index: u16,
+        expected: error::ConstantKind,
+    ) -> error::Result<usize>
+    {
+        check_kind(&self.kinds, &self.indices, index, expected)
+    }
+
+    /// The position among the constants of its kind of the constant at `index`,
+    /// which parsing already checked, such as the constants attributes refer to
+    pub(super) fn checked_index(&self, index: u16) -> usize
+    {
+        self.indices[index as usize - 1]
+    }
+}
+
+/// Prints the constant pool the way `javap -v` does, one
+/// `#n = Kind operands // resolved value` line per constant
+impl fmt::Display for ConstantPool<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        // Constants refer to each other by their local indices, which we
+        // have to map back to the original ones for the operands
+        let original = |kind, index| self.original_index(kind, index);
+
+        let class = |index| disassemble::quote_class_name(self.class_name_at(index));
+        let member = |class_index, name_and_type_index| {
+            disassemble::qualified_member(self.member_ref_at(class_index, name_and_type_index))
+        };
+        let name_and_type = |index| {
+            let (name, descriptor) = self.name_and_type_at(index);
+
+            format!("{}:{descriptor}", disassemble::quote_member_name(name))
+        };
+
+        let width = format!("#{}", self.kinds.len()).len() + 2;
+
+        writeln!(f, "Constant pool:")?;
+        for (original_index, (kind, &index)) in
+            self.kinds.iter().zip(self.indices.iter()).enumerate()
+        {
+            use error::ConstantKind as Kind;
+
+            let (name, operands, comment) = match kind {
+                Kind::Class => {
+                    let name_index = self.classes[index].name_index;
+
+                    (
+                        "Class",
+                        format!("#{}", original(Kind::Utf8, name_index)),
+                        Some(class(index)),
+                    )
+                }
+                Kind::FieldRef | Kind::MethodRef | Kind::InterfaceMethodRef => {
+                    let (name, reference) = match kind {
+                        Kind::FieldRef => ("Fieldref", Reference::Field(index)),
+                        Kind::MethodRef => ("Methodref", Reference::Method(index)),
+                        _ => ("InterfaceMethodref", Reference::InterfaceMethod(index)),
+                    };
+                    let (class_index, name_and_type_index) = self.reference_at(&reference);
+
+                    (
+                        name,
+                        format!(
+                            "#{}.#{}",
+                            original(Kind::Class, class_index),
+                            original(Kind::NameAndType, name_and_type_index)
+                        ),
+                        Some(member(class_index, name_and_type_index)),
+                    )
+                }
+                Kind::String => {
+                    let string_index = self.strings[index].string_index;
+
+                    (
+                        "String",
+                        format!("#{}", original(Kind::Utf8, string_index)),
+                        Some(disassemble::escape(self.utf8_at(string_index))),
+                    )
+                }
+                Kind::Integer => ("Integer", self.integer_at(index).to_string(), None),
+                Kind::Float => (
+                    "Float",
+                    format!("{}f", disassemble::java_float(self.float_at(index))),
+                    None,
+                ),
+                Kind::Long => ("Long", format!("{}l", self.long_at(index)), None),
+                Kind::Double => (
+                    "Double",
+                    format!("{}d", disassemble::java_float(self.double_at(index))),
+                    None,
+                ),
+                Kind::NameAndType => {
+                    let NameAndType {
+                        name_index,
+                        descriptor_index,
+                    } = self.name_and_types[index];
+
+                    (
+                        "NameAndType",
+                        format!(
+                            "#{}:#{}",
+                            original(Kind::Utf8, name_index),
+                            original(Kind::Utf8, descriptor_index)
+                        ),
+                        Some(name_and_type(index)),
+                    )
+                }
+                Kind::Utf8 => ("Utf8", disassemble::escape(self.utf8_at(index)), None),
+                Kind::MethodHandle => {
+                    let MethodHandle {
+                        reference_kind,
+                        ref reference,
+                    } = self.method_handles[index];
+                    let reference_index = match *reference {
+                        Reference::Field(index) => original(Kind::FieldRef, index),
+                        Reference::Method(index) => original(Kind::MethodRef, index),
+                        Reference::InterfaceMethod(index) => {
+                            original(Kind::InterfaceMethodRef, index)
+                        }
+                    };
+                    let (class_index, name_and_type_index) = self.reference_at(reference);
+
+                    (
+                        "MethodHandle",
+                        format!("{}:#{reference_index}", reference_kind as u8),
+                        Some(format!(
+                            "{} {}",
+                            reference_kind.name(),
+                            member(class_index, name_and_type_index)
+                        )),
+                    )
+                }
+                Kind::MethodType => {
+                    let descriptor_index = self.method_types[index].descriptor_index;
+
+                    // javap leaves room for the method's name before its descriptor
+                    (
+                        "MethodType",
+                        format!("#{}", original(Kind::Utf8, descriptor_index)),
+                        Some(format!(" {}", self.utf8_at(descriptor_index))),
+                    )
+                }
+                Kind::Dynamic | Kind::InvokeDynamic => {
+                    let (name, bootstrap_method_attr_index, name_and_type_index) = match kind {
+                        Kind::Dynamic => {
+                            let Dynamic {
+                                bootstrap_method_attr_index,
+                                name_and_type_index,
+                            } = self.dynamics[index];
+
+                            ("Dynamic", bootstrap_method_attr_index, name_and_type_index)
+                        }
+                        _ => {
+                            let InvokeDynamic {
+                                bootstrap_method_attr_index,
+                                name_and_type_index,
+                            } = self.invoke_dynamics[index];
+
+                            (
+                                "InvokeDynamic",
+                                bootstrap_method_attr_index,
+                                name_and_type_index,
+                            )
+                        }
+                    };
+
+                    (
+                        name,
+                        format!(
+                            "#{bootstrap_method_attr_index}:#{}",
+                            original(Kind::NameAndType, name_and_type_index)
+                        ),
+                        Some(format!(
+                            "#{bootstrap_method_attr_index}:{}",
+                            name_and_type(name_and_type_index)
+                        )),
+                    )
+                }
+                Kind::Module | Kind::Package => {
+                    let (name, name_index) = match kind {
+                        Kind::Module => ("Module", self.modules[index].name_index),
+                        _ => ("Package", self.packages[index].name_index),
+                    };
+                    let comment = disassemble::escape(self.utf8_at(name_index));
+
+                    // javap quotes module names, which are dotted, but not package names
+                    (
+                        name,
+                        format!("#{}", original(Kind::Utf8, name_index)),
+                        Some(match kind {
+                            Kind::Module => format!("\"{comment}\""),
+                            _ => comment,
+                        }),
+                    )
+                }
+                Kind::Unusable => continue,
+            };
+
+            let line = format!(
+                "{:>width$} = {name:<18} {operands}",
+                format!("#{}", original_index + 1)
+            );
+            match comment {
+                Some(comment) => writeln!(f, "{}", disassemble::comment(&line, 2, &comment))?,
+                None => writeln!(f, "{line}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A field or method reference, resolved down to the names it refers to
+#[derive(Debug, Clone, Copy)]
+pub struct MemberRef<'a>
+{
+    pub class_name: &'a str,
+    pub name: &'a str,
+    pub descriptor: &'a str,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Loadable<'a>
+{
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(&'a str),
+    Class(&'a str),
+    MethodType(&'a str),
+    MethodHandle
+    {
+        reference_kind: ReferenceKind,
+        reference: MemberRef<'a>,
+    },
+    /// A dynamically-computed constant, whose value comes from its bootstrap method
+    Dynamic
+    {
+        /// The index of the bootstrap method within the BootstrapMethods attribute
+        bootstrap_method_attr_index: usize,
+        name: &'a str,
+        descriptor: &'a str,
+    },
+}
+
+/// A constant of the constant pool, resolved down to the values it refers to
+#[derive(Debug, Clone, Copy)]
+pub enum ConstantRef<'a>
+{
+    /// The binary name of the class, or the descriptor of an array type
+    Class(&'a str),
+    FieldRef(MemberRef<'a>),
+    MethodRef(MemberRef<'a>),
+    InterfaceMethodRef(MemberRef<'a>),
+    String(&'a str),
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    NameAndType
+    {
+        name: &'a str,
+        descriptor: &'a str,
+    },
+    Utf8(&'a str),
+    MethodHandle
+    {
+        reference_kind: ReferenceKind,
+        reference: MemberRef<'a>,
+    },
+    /// The method descriptor of the type
+    MethodType(&'a str),
+    Dynamic
+    {
+        /// The index of the bootstrap method within the BootstrapMethods attribute
+        bootstrap_method_attr_index: usize,
+        name: &'a str,
+        /// The field descriptor of the constant's type
+        descriptor: &'a str,
+    },
+    InvokeDynamic
+    {
+        /// The index of the bootstrap method within the BootstrapMethods attribute
+        bootstrap_method_attr_index: usize,
+        name: &'a str,
+        descriptor: &'a str,
+    },
+    /// The name of the module
+    Module(&'a str),
+    /// The binary name of the package
+    Package(&'a str),
+}
+
+#[derive(Debug)]
+pub(super) struct Class
+{
+    pub(super) name_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct FieldRef
+{
+    pub(super) class_index: usize,
+    pub(super) name_and_type_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct MethodRef
+{
+    pub(super) class_index: usize,
+    pub(super) name_and_type_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct InterfaceMethodRef
+{
+    pub(super) class_index: usize,
+    pub(super) name_and_type_index: usize,
+}
+
+pub(super) mod constant
+{
+    #[derive(Debug)]
+    pub(super) struct String
+    {
+        pub(super) string_index: usize,
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Integer
+{
+    pub(super) value: i32,
+}
+
+#[derive(Debug)]
+pub(super) struct Float
+{
+    pub(super) value: f32,
+}
+
+#[derive(Debug)]
+pub(super) struct Long
+{
+    pub(super) value: i64,
+}
+
+#[derive(Debug)]
+pub(super) struct Double
+{
+    pub(super) value: f64,
+}
+
+#[derive(Debug)]
+pub(super) struct NameAndType
+{
+    pub(super) name_index: usize,
+    pub(super) descriptor_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct Utf8<'a>
+{
+    /// Borrowed from the class file unless decoding modified UTF-8 changed it
+    pub(super) bytes: Cow<'a, str>,
+    /// The modified UTF-8 the string was decoded from
+    pub(super) encoded: &'a [u8],
+}
+
+#[derive(Debug)]
+pub(super) struct MethodHandle
+{
+    pub(super) reference_kind: ReferenceKind,
+    pub(super) reference: Reference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind
+{
+    GetField = 1,
+    GetStatic = 2,
+    PutField = 3,
+    PutStatic = 4,
+    InvokeVirtual = 5,
+    InvokeStatic = 6,
+    InvokeSpecial = 7,
+    NewInvokeSpecial = 8,
+    InvokeInterface = 9,
+}
+
+impl ReferenceKind
+{
+    /// The name javap gives the kind, such as `REF_invokeStatic`
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            ReferenceKind::GetField => "REF_getField",
+            ReferenceKind::GetStatic => "REF_getStatic",
+            ReferenceKind::PutField => "REF_putField",
+            ReferenceKind::PutStatic => "REF_putStatic",
+            ReferenceKind::InvokeVirtual => "REF_invokeVirtual",
+            ReferenceKind::InvokeStatic => "REF_invokeStatic",
+            ReferenceKind::InvokeSpecial => "REF_invokeSpecial",
+            ReferenceKind::NewInvokeSpecial => "REF_newInvokeSpecial",
+            ReferenceKind::InvokeInterface => "REF_invokeInterface",
+        }
+    }
+
+    fn expected_constant_kind(&self) -> error::ConstantKind
+    {
+        match self {
+            ReferenceKind::GetField
+            | ReferenceKind::GetStatic
+            | ReferenceKind::PutField
+            | ReferenceKind::PutStatic => error::ConstantKind::FieldRef,
+            ReferenceKind::InvokeVirtual
+            | ReferenceKind::InvokeStatic
+            | ReferenceKind::InvokeSpecial
+            | ReferenceKind::NewInvokeSpecial => error::ConstantKind::MethodRef,
+            ReferenceKind::InvokeInterface => error::ConstantKind::InterfaceMethodRef,
+        }
+    }
+
+    /// Checks the name of the member the method handle refers to, as constructors
+    /// are only called through `REF_newInvokeSpecial` and initializers not at all
+    fn check_name(&self, name: &str) -> error::Result<()>
+    {
+        let valid = match self {
+            ReferenceKind::NewInvokeSpecial => name == "<init>",
+            ReferenceKind::InvokeVirtual
+            | ReferenceKind::InvokeStatic
+            | ReferenceKind::InvokeSpecial
+            | ReferenceKind::InvokeInterface => name != "<init>" && name != "<clinit>",
+            _ => true,
+        };
+        if !valid {
+            Err(error::Error::InvalidMethodHandleReference {
+                reference_kind: *self,
+                name: name.into(),
+            })?
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<u8> for ReferenceKind
+{
+    type Error = error::Error;
+
+    fn try_from(reference_kind: u8) -> error::Result<Self>
+    {
+        match reference_kind {
+            1 => Ok(ReferenceKind::GetField),
+            2 => Ok(ReferenceKind::GetStatic),
+            3 => Ok(ReferenceKind::PutField),
+            4 => Ok(ReferenceKind::PutStatic),
+            5 => Ok(ReferenceKind::InvokeVirtual),
+            6 => Ok(ReferenceKind::InvokeStatic),
+            7 => Ok(ReferenceKind::InvokeSpecial),
+            8 => Ok(ReferenceKind::NewInvokeSpecial),
+            9 => Ok(ReferenceKind::InvokeInterface),
+            reference_kind => Err(error::Error::InvalidReferenceKind(reference_kind)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum Reference
+{
+    Field(usize),
+    Method(usize),
+    InterfaceMethod(usize),
+}
+
+#[derive(Debug)]
+pub(super) struct MethodType
+{
+    pub(super) descriptor_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct Dynamic
+{
+    pub(super) bootstrap_method_attr_index: usize,
+    pub(super) name_and_type_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct InvokeDynamic
+{
+    pub(super) bootstrap_method_attr_index: usize,
+    pub(super) name_and_type_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct Module
+{
+    pub(super) name_index: usize,
+}
+
+#[derive(Debug)]
+pub(super) struct Package
+{
+    pub(super) name_index: usize,
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    use crate::{
+        parse::{descriptor, mutf8},
+        raw,
+    };
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum ConstantKind
+    {
+        Class,
+        FieldRef,
+        MethodRef,
+        InterfaceMethodRef,
+        String,
+        Integer,
+        Float,
+        Long,
+        Double,
+        NameAndType,
+        Utf8,
+        MethodHandle,
+        MethodType,
+        Dynamic,
+        InvokeDynamic,
+        Module,
+        Package,
+        Unusable,
+    }
+
+    impl From<&raw::Constant<'_>> for ConstantKind
+    {
+        fn from(value: &raw::Constant<'_>) -> Self
+        {
+            match value {
+                raw::Constant::Class { .. } => ConstantKind::Class,
+                raw::Constant::FieldRef { .. } => ConstantKind::FieldRef,
+                raw::Constant::MethodRef { .. } => ConstantKind::MethodRef,
+                raw::Constant::InterfaceMethodRef { .. } => ConstantKind::InterfaceMethodRef,
+                raw::Constant::String { .. } => ConstantKind::String,
+                raw::Constant::Integer { .. } => ConstantKind::Integer,
+                raw::Constant::Float { .. } => ConstantKind::Float,
+                raw::Constant::Long { .. } => ConstantKind::Long,
+                raw::Constant::Double { .. } => ConstantKind::Double,
+                raw::Constant::NameAndType { .. } => ConstantKind::NameAndType,
+                raw::Constant::Utf8 { .. } => ConstantKind::Utf8,
+                raw::Constant::MethodHandle { .. } => ConstantKind::MethodHandle,
+                raw::Constant::MethodType { .. } => ConstantKind::MethodType,
+                raw::Constant::Dynamic { .. } => ConstantKind::Dynamic,
+                raw::Constant::InvokeDynamic { .. } => ConstantKind::InvokeDynamic,
+                raw::Constant::Module { .. } => ConstantKind::Module,
+                raw::Constant::Package { .. } => ConstantKind::Package,
+                raw::Constant::Unusable => ConstantKind::Unusable,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        OutOfRangeIndex(usize),
+        UnusableIndex(usize),
+        UnexpectedConstantKind
+        {
+            expected: ConstantKind,
+            actual: ConstantKind,
+        },
+        InvalidReferenceKind(u8),
+        InvalidMethodHandleReference
+        {
+            reference_kind: super::ReferenceKind,
+            name: Box<str>,
+        },
+        BootstrapMethodOutOfRange
+        {
+            index: usize,
+            count: usize,
+        },
+        InvalidDescriptor
+        {
+            descriptor: Box<str>,
+            source: descriptor::error::Error,
+        },
+        Utf8(mutf8::error::Error),
+        /// A module or package constant in a class other than a module
+        OutsideModule(ConstantKind),
+        PoolFull,
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::OutOfRangeIndex(index) => {
+                    write!(f, "out of range constant pool index {index}")
+                }
+                Error::UnusableIndex(index) => {
+                    write!(
+                        f,
+                        "constant pool index {index} is the unusable second half of a long or double"
+                    )
+                }
+                Error::UnexpectedConstantKind { expected, actual } => {
+                    write!(
+                        f,
+                        "expected {} constant, but instead got a {} constant",
+                        format!("{expected:?}").to_ascii_lowercase(),
+                        format!("{actual:?}").to_ascii_lowercase()
+                    )
+                }
+                Error::InvalidReferenceKind(reference_kind) => {
+                    write!(f, "invalid method handle reference kind {reference_kind}")
+                }
+                Error::InvalidMethodHandleReference {
+                    reference_kind,
+                    name,
+                } => {
+                    write!(
+                        f,
+                        "{} method handle cannot refer to method {name:?}",
+                        reference_kind.name()
+                    )
+                }
+                Error::BootstrapMethodOutOfRange { index, count } => {
+                    write!(
+                        f,
+                        "bootstrap method index {index} out of range for {count} bootstrap methods"
+                    )
+                }
+                Error::InvalidDescriptor { descriptor, source } => {
+                    write!(f, "invalid descriptor {descriptor:?}: {source}")
+                }
+                Error::Utf8(utf8_err) => write!(f, "{utf8_err}"),
+                Error::OutsideModule(kind) => {
+                    write!(
+                        f,
+                        "{} constant outside of a module-info class of version 53 or above",
+                        format!("{kind:?}").to_ascii_lowercase()
+                    )
+                }
+                Error::PoolFull => {
+                    write!(
+                        f,
+                        "the constant pool is full, it holds at most 65534 entries"
+                    )
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            match self {
+                Error::InvalidDescriptor { source, .. } => Some(source),
+                Error::Utf8(utf8_err) => Some(utf8_err),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<mutf8::error::Error> for Error
+    {
+        fn from(utf8_err: mutf8::error::Error) -> Self
+        {
+            Error::Utf8(utf8_err)
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Where in a class file an error was found, as a byte offset and
+//! the path of structures leading to it, such as `constant #14 -> name_index`
+
+use std::{error, fmt};
+
+/// Where in a class file an error was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Context
+{
+    offset: usize,
+    path: Vec<Segment>,
+}
+
+impl Context
+{
+    /// The offset of the byte the error points to from the start of the class file
+    pub fn offset(&self) -> usize
+    {
+        self.offset
+    }
+
+    /// The structures containing the error, outermost first
+    pub fn path(&self) -> &[Segment]
+    {
+        &self.path
+    }
+
+    /// Renders the bytes around the error, marking the one it points to
+    pub fn hexdump<'a>(&self, bytes: &'a [u8]) -> Hexdump<'a>
+    {
+        Hexdump {
+            bytes,
+            offset: self.offset,
+        }
+    }
+}
+
+impl fmt::Display for Context
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        for (index, segment) in self.path.iter().enumerate() {
+            if index > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{segment}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// One step of the path to an error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment
+{
+    /// The constant at an index of the constant pool
+    Constant(usize),
+    Interface(usize),
+    Field(usize),
+    Method(usize),
+    /// A component of a Record attribute
+    RecordComponent(usize),
+    Attribute
+    {
+        index: usize,
+        /// The name of the attribute, once it is known
+        name: Option<Box<str>>,
+    },
+    /// An item of a structure, named as in the JVM specification
+    Item(&'static str),
+}
+
+impl fmt::Display for Segment
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Segment::Constant(index) => write!(f, "constant #{index}"),
+            Segment::Interface(index) => write!(f, "interface #{index}"),
+            Segment::Field(index) => write!(f, "field #{index}"),
+            Segment::Method(index) => write!(f, "method #{index}"),
+            Segment::RecordComponent(index) => write!(f, "record component #{index}"),
+            Segment::Attribute { index, name: None } => write!(f, "attribute #{index}"),
+            Segment::Attribute {
+                index,
+                name: Some(name),
+            } => write!(f, "attribute #{index} ({name})"),
+            Segment::Item(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// An error together with where in the class file it was found
+#[derive(Debug)]
+pub struct Located<E>
+{
+    error: E,
+    context: Context,
+}
+
+impl<E> Located<E>
+{
+    pub(crate) fn new(error: E, offset: usize) -> Self
+    {
+        Located {
+            error,
+            context: Context {
+                offset,
+                path: Vec::new(),
+            },
+        }
+    }
+
+    /// Places the error inside `segment`, as it is passed outwards
+    pub(crate) fn within(mut self, segment: Segment) -> Self
+    {
+        self.context.path.insert(0, segment);
+
+        self
+    }
+
+    /// Converts the error while keeping where it was found
+    pub(crate) fn map<F>(self, map: impl FnOnce(E) -> F) -> Located<F>
+    {
+        Located {
+            error: map(self.error),
+            context: self.context,
+        }
+    }
+
+    pub fn error(&self) -> &E
+    {
+        &self.error
+    }
+
+    pub fn context(&self) -> &Context
+    {
+        &self.context
+    }
+}
+
+impl<E> fmt::Display for Located<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if !self.context.path.is_empty() {
+            write!(f, "{}: ", self.context)?;
+        }
+
+        write!(f, "{} (at offset {:#x})", self.error, self.context.offset)
+    }
+}
+
+impl<E> error::Error for Located<E>
+where
+    E: error::Error,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)>
+    {
+        self.error.source()
+    }
+}
+
+/// The rows of a hexdump surrounding an offset, with the byte at the offset marked
+pub struct Hexdump<'a>
+{
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Hexdump<'_>
+{
+    const ROW: usize = 16;
+    /// How many rows are shown before and after the marked one
+    const SURROUNDING: usize = 2;
+}
+
+impl fmt::Display for Hexdump<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let marked_row = self.offset / Hexdump::ROW;
+        let first_row = marked_row.saturating_sub(Hexdump::SURROUNDING);
+        // The offset may be the end of the file, when there was nothing left to read
+        let last_row = (marked_row + Hexdump::SURROUNDING)
+            .min(self.bytes.len().saturating_sub(1) / Hexdump::ROW)
+            .max(marked_row);
+
+        for row in first_row..=last_row {
+            let start = row * Hexdump::ROW;
+            let bytes = self.bytes.get(start..).unwrap_or_default();
+            let bytes = &bytes[..bytes.len().min(Hexdump::ROW)];
+
+            write!(f, "{start:08x}: ")?;
+            for column in 0..Hexdump::ROW {
+                match bytes.get(column) {
+                    Some(byte) => write!(f, " {byte:02x}")?,
+                    None => write!(f, "   ")?,
+                }
+            }
+            write!(f, "  |")?;
+            for byte in bytes {
+                let character = match byte {
+                    0x20..=0x7e => *byte as char,
+                    _ => '.',
+                };
+                write!(f, "{character}")?;
+            }
+            writeln!(f, "|")?;
+
+            if row == marked_row {
+                let column = self.offset % Hexdump::ROW;
+                writeln!(f, "{:>width$}^^", "", width = 11 + column * 3)?;
+            }
+        }
+
+        Ok(())
+    }
+}
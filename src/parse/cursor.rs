@@ -1,32 +1,88 @@
 use std::mem;
 
 pub use self::error::{Error, Result};
+use super::context::Located;
 
 #[derive(Debug)]
 pub struct Cursor<'a>
 {
     bytes: &'a [u8],
+    position: usize,
+    last_read: usize,
 }
 
 impl<'a> Cursor<'a>
 {
     pub const fn new(bytes: &'a [u8]) -> Self
     {
-        Cursor { bytes }
+        Cursor::with_offset(bytes, 0)
+    }
+
+    /// Creates a cursor over `bytes` which were found `offset` bytes into
+    /// the class file, so that its positions are relative to the whole file
+    pub const fn with_offset(bytes: &'a [u8], offset: usize) -> Self
+    {
+        Cursor {
+            bytes,
+            position: offset,
+            last_read: offset,
+        }
+    }
+
+    /// The offset of the next byte to be read
+    pub fn position(&self) -> usize
+    {
+        self.position
+    }
+
+    /// The offset of the value read last, which is where
+    /// an error found while checking that value points to
+    pub fn last_read(&self) -> usize
+    {
+        self.last_read
+    }
+
+    /// Attaches the offset of the value read last to `error`
+    pub fn locate<E>(&self, error: E) -> Located<E>
+    {
+        Located::new(error, self.last_read)
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.bytes.is_empty()
     }
 
     pub fn read_integer<I>(&mut self) -> Result<I>
     where
         I: Integer,
-        [(); I::SIZE]:,
     {
-        self.read::<{ I::SIZE }>().map(I::from_be_bytes)
+        self.advance(I::SIZE).map(I::from_be_slice)
+    }
+
+    pub fn read_float<F>(&mut self) -> Result<F>
+    where
+        F: Float,
+    {
+        self.advance(F::SIZE).map(F::from_be_slice)
     }
 
-    pub fn read_bytes(&mut self, count: usize) -> Result<Box<[u8]>>
+    /// Reads `count` bytes, borrowing them from the underlying slice
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8]>
+    {
+        self.advance(count)
+    }
+
+    fn advance(&mut self, count: usize) -> Result<&'a [u8]>
     {
         if count > self.bytes.len() {
             Err(Error::ReadPastEnd {
+                offset: self.position,
                 tried: count,
                 left: self.bytes.len(),
             })
@@ -34,58 +90,64 @@ impl<'a> Cursor<'a>
             let bytes = &self.bytes[..count];
             self.bytes = &self.bytes[count..];
 
-            Ok(bytes.into())
-        }
-    }
+            self.last_read = self.position;
+            self.position += count;
 
-    fn read<const C: usize>(&mut self) -> Result<[u8; C]>
-    {
-        if C > self.bytes.len() {
-            Err(Error::ReadPastEnd {
-                tried: C,
-                left: self.bytes.len(),
-            })
-        } else {
-            let bytes = &self.bytes[..C];
-            self.bytes = &self.bytes[C..];
-
-            // This will never fail as we have sliced off
-            // exactly the length of our array from
-            // the inner bytes ref slice
-            Ok(bytes.try_into().unwrap())
+            Ok(bytes)
         }
     }
 }
 
+/// An integer stored in big-endian byte order
 pub trait Integer: Sized
 {
-    const SIZE: usize = mem::size_of::<Self>();
+    const SIZE: usize;
 
-    fn from_be_bytes(bytes: [u8; Self::SIZE]) -> Self;
-}
+    /// Converts exactly `SIZE` bytes
+    fn from_be_slice(bytes: &[u8]) -> Self;
 
-impl Integer for u8
-{
-    fn from_be_bytes(bytes: [u8; Self::SIZE]) -> Self
-    {
-        u8::from_be_bytes(bytes)
-    }
+    /// Appends the `SIZE` bytes of the value to `bytes`
+    fn extend_be(self, bytes: &mut Vec<u8>);
 }
-impl Integer for u16
+
+/// A floating point number stored in big-endian byte order
+pub trait Float: Sized
 {
-    fn from_be_bytes(bytes: [u8; Self::SIZE]) -> Self
-    {
-        u16::from_be_bytes(bytes)
-    }
+    const SIZE: usize;
+
+    /// Converts exactly `SIZE` bytes
+    fn from_be_slice(bytes: &[u8]) -> Self;
+
+    /// Appends the `SIZE` bytes of the value to `bytes`
+    fn extend_be(self, bytes: &mut Vec<u8>);
 }
-impl Integer for u32
-{
-    fn from_be_bytes(bytes: [u8; Self::SIZE]) -> Self
-    {
-        u32::from_be_bytes(bytes)
-    }
+
+macro_rules! big_endian {
+    ($trait:ident for $($ty:ty),*) => {
+        $(
+            impl $trait for $ty
+            {
+                const SIZE: usize = mem::size_of::<$ty>();
+
+                fn from_be_slice(bytes: &[u8]) -> Self
+                {
+                    // This will never fail as the cursor slices
+                    // off exactly the size of the type
+                    <$ty>::from_be_bytes(bytes.try_into().unwrap())
+                }
+
+                fn extend_be(self, bytes: &mut Vec<u8>)
+                {
+                    bytes.extend_from_slice(&self.to_be_bytes())
+                }
+            }
+        )*
+    };
 }
 
+big_endian!(Integer for u8, u16, u32, u64, i8, i16, i32, i64);
+big_endian!(Float for f32, f64);
+
 pub mod error
 {
     use std::{error, fmt, result};
@@ -97,16 +159,29 @@ pub mod error
     {
         ReadPastEnd
         {
-            tried: usize, left: usize
+            offset: usize,
+            tried: usize,
+            left: usize,
         },
     }
 
+    impl Error
+    {
+        /// Where in the class file the error occurred
+        pub fn offset(&self) -> usize
+        {
+            match self {
+                Error::ReadPastEnd { offset, .. } => *offset,
+            }
+        }
+    }
+
     impl fmt::Display for Error
     {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
         {
             match self {
-                Error::ReadPastEnd { tried, left } => {
+                Error::ReadPastEnd { tried, left, .. } => {
                     write!(f, "tried reading {tried} bytes when only {left} are left")
                 }
             }
@@ -117,9 +192,7 @@ pub mod error
     {
         fn source(&self) -> Option<&(dyn error::Error + 'static)>
         {
-            match self {
-                _ => None,
-            }
+            None
         }
     }
 }
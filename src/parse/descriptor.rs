@@ -0,0 +1,425 @@
+use std::fmt;
+
+use self::error::{Error, Result};
+
+/// The type of a field, parameter or local variable, as
+/// described by a field descriptor such as `[Ljava/lang/String;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldType
+{
+    Base(BaseType),
+    /// A class or interface type, given by its binary name in internal form
+    Object(Box<str>),
+    Array
+    {
+        dimensions: u8,
+        /// The type of the innermost elements, which is never an array itself
+        element: Box<FieldType>,
+    },
+}
+
+impl FieldType
+{
+    pub fn parse(descriptor: &str) -> Result<Self>
+    {
+        let mut reader = Reader::new(descriptor);
+        let field_type = reader.field_type()?;
+        reader.finish()?;
+
+        Ok(field_type)
+    }
+
+    /// How many local variable or operand stack slots a value
+    /// of this type takes up, which is 2 for longs and doubles
+    pub fn slot_size(&self) -> usize
+    {
+        match self {
+            FieldType::Base(BaseType::Long | BaseType::Double) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for FieldType
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            FieldType::Base(base_type) => write!(f, "{}", base_type.descriptor()),
+            FieldType::Object(class_name) => write!(f, "L{class_name};"),
+            FieldType::Array {
+                dimensions,
+                element,
+            } => write!(f, "{}{element}", "[".repeat(*dimensions as usize)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseType
+{
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+}
+
+impl BaseType
+{
+    pub(super) fn from_descriptor(descriptor: char) -> Option<Self>
+    {
+        match descriptor {
+            'B' => Some(BaseType::Byte),
+            'C' => Some(BaseType::Char),
+            'D' => Some(BaseType::Double),
+            'F' => Some(BaseType::Float),
+            'I' => Some(BaseType::Int),
+            'J' => Some(BaseType::Long),
+            'S' => Some(BaseType::Short),
+            'Z' => Some(BaseType::Boolean),
+            _ => None,
+        }
+    }
+
+    pub fn descriptor(&self) -> char
+    {
+        match self {
+            BaseType::Byte => 'B',
+            BaseType::Char => 'C',
+            BaseType::Double => 'D',
+            BaseType::Float => 'F',
+            BaseType::Int => 'I',
+            BaseType::Long => 'J',
+            BaseType::Short => 'S',
+            BaseType::Boolean => 'Z',
+        }
+    }
+
+    /// The keyword Java uses for the type, such as `int`
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            BaseType::Byte => "byte",
+            BaseType::Char => "char",
+            BaseType::Double => "double",
+            BaseType::Float => "float",
+            BaseType::Int => "int",
+            BaseType::Long => "long",
+            BaseType::Short => "short",
+            BaseType::Boolean => "boolean",
+        }
+    }
+}
+
+/// The parameter and return types of a method, as described
+/// by a method descriptor such as `([Ljava/lang/String;)V`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodDescriptor
+{
+    parameters: Box<[FieldType]>,
+    return_type: Option<FieldType>,
+}
+
+impl MethodDescriptor
+{
+    pub fn parse(descriptor: &str) -> Result<Self>
+    {
+        let mut reader = Reader::new(descriptor);
+
+        reader.expect('(')?;
+        let mut parameters = Vec::new();
+        while reader.peek() != Some(')') {
+            parameters.push(reader.field_type()?);
+        }
+        reader.expect(')')?;
+
+        let return_type = match reader.peek() {
+            Some('V') => {
+                reader.next()?;
+
+                None
+            }
+            _ => Some(reader.field_type()?),
+        };
+        reader.finish()?;
+
+        Ok(MethodDescriptor {
+            parameters: parameters.into(),
+            return_type,
+        })
+    }
+
+    pub fn parameters(&self) -> &[FieldType]
+    {
+        &self.parameters
+    }
+
+    /// The type the method returns, or `None` for `void`
+    pub fn return_type(&self) -> Option<&FieldType>
+    {
+        self.return_type.as_ref()
+    }
+
+    /// How many local variable slots the parameters take up, not
+    /// counting the `this` reference passed to instance methods
+    pub fn parameter_slots(&self) -> usize
+    {
+        self.parameters.iter().map(FieldType::slot_size).sum()
+    }
+}
+
+impl fmt::Display for MethodDescriptor
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "(")?;
+        for parameter in self.parameters.iter() {
+            write!(f, "{parameter}")?;
+        }
+        write!(f, ")")?;
+
+        match &self.return_type {
+            Some(return_type) => write!(f, "{return_type}"),
+            None => write!(f, "V"),
+        }
+    }
+}
+
+/// How deeply type arguments may nest in a signature, which
+/// keeps reading them from running out of stack
+const MAX_DEPTH: usize = 256;
+
+/// Walks over a descriptor, keeping track of the position for errors
+///
+/// Signatures extend the grammar of descriptors, so they are read with it as well
+pub(super) struct Reader<'a>
+{
+    descriptor: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Reader<'a>
+{
+    pub(super) fn new(descriptor: &'a str) -> Self
+    {
+        Reader {
+            descriptor,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    /// Goes one level deeper into the type arguments of a signature
+    pub(super) fn enter(&mut self) -> Result<()>
+    {
+        if self.depth == MAX_DEPTH {
+            Err(Error::NestingTooDeep {
+                position: self.position,
+            })?
+        }
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    pub(super) fn leave(&mut self)
+    {
+        self.depth -= 1;
+    }
+
+    pub(super) fn position(&self) -> usize
+    {
+        self.position
+    }
+
+    pub(super) fn peek(&self) -> Option<char>
+    {
+        self.descriptor[self.position..].chars().next()
+    }
+
+    pub(super) fn next(&mut self) -> Result<char>
+    {
+        let character = self.peek().ok_or(Error::UnexpectedEnd)?;
+        self.position += character.len_utf8();
+
+        Ok(character)
+    }
+
+    pub(super) fn expect(&mut self, expected: char) -> Result<()>
+    {
+        let position = self.position;
+        match self.next()? {
+            character if character == expected => Ok(()),
+            character => Err(Error::UnexpectedCharacter {
+                character,
+                position,
+            }),
+        }
+    }
+
+    pub(super) fn finish(&self) -> Result<()>
+    {
+        match self.peek() {
+            Some(character) => Err(Error::UnexpectedCharacter {
+                character,
+                position: self.position,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn field_type(&mut self) -> Result<FieldType>
+    {
+        let start = self.position;
+
+        let mut dimensions = 0usize;
+        while self.peek() == Some('[') {
+            self.next()?;
+            dimensions += 1;
+        }
+        // An array type may have at most 255 dimensions
+        let dimensions =
+            u8::try_from(dimensions).map_err(|_| Error::TooManyDimensions { position: start })?;
+
+        let position = self.position;
+        let element = match self.next()? {
+            'L' => FieldType::Object(self.class_name()?.into()),
+            character => match BaseType::from_descriptor(character) {
+                Some(base_type) => FieldType::Base(base_type),
+                None => Err(Error::UnexpectedCharacter {
+                    character,
+                    position,
+                })?,
+            },
+        };
+
+        if dimensions == 0 {
+            Ok(element)
+        } else {
+            Ok(FieldType::Array {
+                dimensions,
+                element: Box::new(element),
+            })
+        }
+    }
+
+    /// Reads an unqualified name, which may not contain any of `. ; [ / < > :`
+    pub(super) fn identifier(&mut self) -> Result<&'a str>
+    {
+        let start = self.position;
+        while let Some(character) = self.peek() {
+            if matches!(character, '.' | ';' | '[' | '/' | '<' | '>' | ':') {
+                break;
+            }
+            self.next()?;
+        }
+
+        if self.position == start {
+            Err(Error::EmptyName { position: start })?
+        }
+
+        Ok(&self.descriptor[start..self.position])
+    }
+
+    /// Reads a binary name up to and including its terminating semicolon,
+    /// checking that it is made of non-empty unqualified names
+    fn class_name(&mut self) -> Result<&'a str>
+    {
+        let start = self.position;
+
+        let mut segment_start = start;
+        loop {
+            let position = self.position;
+            match self.next()? {
+                ';' | '/' if position == segment_start => Err(Error::EmptyName { position })?,
+                ';' => break,
+                '/' => segment_start = self.position,
+                character @ ('.' | '[') => Err(Error::UnexpectedCharacter {
+                    character,
+                    position,
+                })?,
+                _ => {}
+            }
+        }
+
+        Ok(&self.descriptor[start..self.position - 1])
+    }
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        UnexpectedEnd,
+        UnexpectedCharacter
+        {
+            character: char,
+            position: usize,
+        },
+        TooManyDimensions
+        {
+            position: usize,
+        },
+        EmptyName
+        {
+            position: usize,
+        },
+        NestingTooDeep
+        {
+            position: usize,
+        },
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::UnexpectedEnd => write!(f, "unexpected end of input"),
+                Error::UnexpectedCharacter {
+                    character,
+                    position,
+                } => {
+                    write!(
+                        f,
+                        "unexpected character {character:?} at position {position}"
+                    )
+                }
+                Error::TooManyDimensions { position } => {
+                    write!(
+                        f,
+                        "array type at position {position} has more than 255 dimensions"
+                    )
+                }
+                Error::EmptyName { position } => {
+                    write!(f, "empty class name segment at position {position}")
+                }
+                Error::NestingTooDeep { position } => {
+                    write!(
+                        f,
+                        "type arguments at position {position} are nested more than {} deep",
+                        super::MAX_DEPTH
+                    )
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            None
+        }
+    }
+}
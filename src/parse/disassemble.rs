@@ -0,0 +1,1681 @@
+//! Prints parsed classes the way `javap -c -v` does
+
+use std::fmt::{self, Write};
+
+use super::{
+    attribute::{
+        Annotation, Attribute, Code, ConstantValue, ElementValue, InnerClass, RecordComponent,
+        StackMapFrame, TargetInfo, TypeAnnotation, TypePathKind, VerificationType,
+    },
+    constant_pool::{error::ConstantKind, Loadable, MemberRef},
+    descriptor::{FieldType, MethodDescriptor},
+    instruction::{Instruction, Wide},
+    signature::{
+        ClassTypeSignature, JavaTypeSignature, ReferenceTypeSignature, TypeArgument, TypeParameter,
+    },
+    Class, Field, Method,
+};
+
+/// How far past its indentation javap lines up the comment of a line
+const COMMENT_COLUMN: usize = 40;
+
+impl fmt::Display for Class<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let constant_pool = &self.constant_pool;
+
+        if let Some(source_file) = self.source_file() {
+            writeln!(f, "  Compiled from \"{source_file}\"")?;
+        }
+
+        writeln!(f, "{}", self.declaration())?;
+        writeln!(f, "  minor version: {}", self.version.minor())?;
+        writeln!(f, "  major version: {}", self.version.major())?;
+        writeln!(
+            f,
+            "  flags: {}",
+            flags(self.access_flags.bits(), self.access_flags.names())
+        )?;
+
+        let this_class = constant_pool.original_index(ConstantKind::Class, self.this_class);
+        writeln!(
+            f,
+            "{}",
+            comment(
+                &format!("  this_class: #{this_class}"),
+                2,
+                &quote_class_name(self.name())
+            )
+        )?;
+        match self.super_class {
+            Some(super_class) => {
+                let index = constant_pool.original_index(ConstantKind::Class, super_class);
+
+                writeln!(
+                    f,
+                    "{}",
+                    comment(
+                        &format!("  super_class: #{index}"),
+                        2,
+                        &quote_class_name(constant_pool.class_name_at(super_class))
+                    )
+                )?;
+            }
+            None => writeln!(f, "  super_class: #0")?,
+        }
+        writeln!(
+            f,
+            "  interfaces: {}, fields: {}, methods: {}, attributes: {}",
+            self.interfaces.len(),
+            self.fields.len(),
+            self.methods.len(),
+            self.attributes.len()
+        )?;
+
+        write!(f, "{constant_pool}")?;
+
+        writeln!(f, "{{")?;
+        for (i, field) in self.fields.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            self.fmt_field(f, field)?;
+        }
+        for (i, method) in self.methods.iter().enumerate() {
+            if i != 0 || !self.fields.is_empty() {
+                writeln!(f)?;
+            }
+            self.fmt_method(f, method)?;
+        }
+        writeln!(f, "}}")?;
+
+        for attribute in self.attributes.iter() {
+            self.fmt_attribute(f, attribute, 0)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Class<'_>
+{
+    /// The class as it would be declared in Java, such as
+    /// `public class Main extends Base implements java.lang.Runnable`
+    fn declaration(&self) -> String
+    {
+        let access_flags = self.access_flags;
+        let is_interface = access_flags.is_interface();
+
+        let mut declaration = String::new();
+        if access_flags.is_public() {
+            declaration.push_str("public ");
+        }
+        if access_flags.is_final() {
+            declaration.push_str("final ");
+        }
+        if access_flags.is_abstract() && !is_interface {
+            declaration.push_str("abstract ");
+        }
+        declaration.push_str(if is_interface { "interface " } else { "class " });
+        declaration.push_str(&java_name(self.name()));
+
+        // The signature replaces the erased supertypes when the class is generic
+        if let Some(signature) = &self.signature {
+            declaration.push_str(&java_type_parameters(signature.type_parameters()));
+            if !is_interface {
+                write!(
+                    declaration,
+                    " extends {}",
+                    java_class_type(signature.superclass())
+                )
+                .unwrap();
+            }
+
+            let superinterfaces = signature
+                .superinterfaces()
+                .iter()
+                .map(java_class_type)
+                .collect::<Vec<_>>();
+            if !superinterfaces.is_empty() {
+                let keyword = if is_interface {
+                    "extends"
+                } else {
+                    "implements"
+                };
+
+                write!(declaration, " {keyword} {}", superinterfaces.join(", ")).unwrap();
+            }
+
+            return declaration;
+        }
+
+        let super_name = self
+            .super_class
+            .map(|super_class| self.constant_pool.class_name_at(super_class));
+        if let Some(super_name) = super_name.filter(|&name| name != "java/lang/Object") {
+            write!(declaration, " extends {}", java_name(super_name)).unwrap();
+        }
+
+        // Interfaces extend their superinterfaces rather than implement them
+        for (i, &interface) in self.interfaces.iter().enumerate() {
+            let keyword = match (i, is_interface) {
+                (0, true) => " extends ",
+                (0, false) => " implements ",
+                _ => ", ",
+            };
+            let interface = java_name(self.constant_pool.class_name_at(interface));
+
+            write!(declaration, "{keyword}{interface}").unwrap();
+        }
+
+        declaration
+    }
+
+    fn fmt_field(&self, f: &mut fmt::Formatter<'_>, field: &Field<'_>) -> fmt::Result
+    {
+        let access_flags = field.access_flags;
+        let descriptor = self.constant_pool.utf8_at(field.descriptor_index);
+
+        let modifiers = [
+            (access_flags.is_public(), "public"),
+            (access_flags.is_private(), "private"),
+            (access_flags.is_protected(), "protected"),
+            (access_flags.is_static(), "static"),
+            (access_flags.is_final(), "final"),
+            (access_flags.is_volatile(), "volatile"),
+            (access_flags.is_transient(), "transient"),
+        ];
+
+        let field_type = match &field.signature {
+            Some(signature) => java_reference_type(signature.field_type()),
+            None => java_type(&field.descriptor),
+        };
+
+        writeln!(
+            f,
+            "  {}{field_type} {};",
+            modifiers_of(&modifiers),
+            field.name()
+        )?;
+        writeln!(f, "    descriptor: {descriptor}")?;
+        writeln!(
+            f,
+            "    flags: {}",
+            flags(access_flags.bits(), access_flags.names())
+        )?;
+
+        for attribute in field.attributes.iter() {
+            self.fmt_attribute(f, attribute, 4)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_method(&self, f: &mut fmt::Formatter<'_>, method: &Method<'_>) -> fmt::Result
+    {
+        let access_flags = method.access_flags;
+        let name = method.name();
+        let descriptor = self.constant_pool.utf8_at(method.descriptor_index);
+
+        // Interface methods with a body that is neither static nor private are default methods
+        let is_default = self.access_flags.is_interface()
+            && !access_flags.is_abstract()
+            && !access_flags.is_static()
+            && !access_flags.is_private();
+        let modifiers = [
+            (access_flags.is_public(), "public"),
+            (access_flags.is_private(), "private"),
+            (access_flags.is_protected(), "protected"),
+            (access_flags.is_static(), "static"),
+            (is_default, "default"),
+            (access_flags.is_final(), "final"),
+            (access_flags.is_synchronized(), "synchronized"),
+            (access_flags.is_native(), "native"),
+            (access_flags.is_abstract(), "abstract"),
+            (access_flags.is_strict(), "strictfp"),
+        ];
+
+        let mut declaration = modifiers_of(&modifiers);
+        if name == "<clinit>" {
+            declaration.push_str("{}");
+        } else {
+            // The signature replaces the erased types when the method involves generics
+            let (type_parameters, mut parameters, return_type) = match &method.signature {
+                Some(signature) => (
+                    java_type_parameters(signature.type_parameters()),
+                    signature
+                        .parameters()
+                        .iter()
+                        .map(java_type_signature)
+                        .collect(),
+                    signature.result().map(java_type_signature),
+                ),
+                None => (
+                    String::new(),
+                    method
+                        .descriptor
+                        .parameters()
+                        .iter()
+                        .map(java_type)
+                        .collect::<Vec<_>>(),
+                    method.descriptor.return_type().map(java_type),
+                ),
+            };
+            if let Some(last) = parameters.last_mut().filter(|_| access_flags.is_varargs()) {
+                last.truncate(last.len() - "[]".len());
+                last.push_str("...");
+            }
+
+            if !type_parameters.is_empty() {
+                write!(declaration, "{type_parameters} ").unwrap();
+            }
+            if name == "<init>" {
+                declaration.push_str(&java_name(self.name()));
+            } else {
+                let return_type = return_type.as_deref().unwrap_or("void");
+
+                write!(declaration, "{return_type} {name}").unwrap();
+            }
+            write!(declaration, "({})", parameters.join(", ")).unwrap();
+        }
+
+        let throws = method
+            .signature
+            .as_ref()
+            .map(|signature| signature.throws())
+            .filter(|throws| !throws.is_empty());
+        let exceptions = method.attributes.iter().find_map(|attribute| {
+            if let Attribute::Exceptions { exception_indices } = attribute {
+                Some(exception_indices)
+            } else {
+                None
+            }
+        });
+        if let Some(throws) = throws {
+            let throws = throws.iter().map(java_reference_type).collect::<Vec<_>>();
+
+            write!(declaration, " throws {}", throws.join(", ")).unwrap();
+        } else if let Some(exception_indices) = exceptions {
+            write!(
+                declaration,
+                " throws {}",
+                self.exception_names(exception_indices)
+            )
+            .unwrap();
+        }
+
+        writeln!(f, "  {declaration};")?;
+        writeln!(f, "    descriptor: {descriptor}")?;
+        writeln!(
+            f,
+            "    flags: {}",
+            flags(access_flags.bits(), access_flags.names())
+        )?;
+
+        for attribute in method.attributes.iter() {
+            match attribute {
+                Attribute::Code(code) => {
+                    self.fmt_code(f, code, &method.descriptor, access_flags.is_static())?
+                }
+                attribute => self.fmt_attribute(f, attribute, 4)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fmt_code(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        code: &Code<'_>,
+        descriptor: &MethodDescriptor,
+        is_static: bool,
+    ) -> fmt::Result
+    {
+        // javap counts the arguments rather than their slots, with instance
+        // methods receiving `this` as their first argument
+        let args_size = descriptor.parameters().len() + !is_static as usize;
+
+        writeln!(f, "    Code:")?;
+        writeln!(
+            f,
+            "      stack={}, locals={}, args_size={args_size}",
+            code.max_stack(),
+            code.max_locals()
+        )?;
+
+        for instruction in code.instructions() {
+            match instruction {
+                Ok((offset, instruction)) => self.fmt_instruction(f, offset, &instruction)?,
+                Err(instruction_err) => {
+                    writeln!(f, "        error: {instruction_err}")?;
+                    break;
+                }
+            }
+        }
+
+        if !code.exception_table().is_empty() {
+            writeln!(f, "      Exception table:")?;
+            writeln!(f, "         from    to  target type")?;
+            for handler in code.exception_table() {
+                let catch_type = match handler.catch_type() {
+                    Some(catch_type) => {
+                        let catch_type = self.constant_pool.checked_index(catch_type);
+
+                        format!("Class {}", self.constant_pool.class_name_at(catch_type))
+                    }
+                    None => "any".to_string(),
+                };
+
+                writeln!(
+                    f,
+                    "{:>14}{:>6}{:>6}   {catch_type}",
+                    handler.start_pc(),
+                    handler.end_pc(),
+                    handler.handler_pc()
+                )?;
+            }
+        }
+
+        for attribute in code.attributes() {
+            self.fmt_attribute(f, attribute, 6)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_instruction(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        offset: u32,
+        instruction: &Instruction,
+    ) -> fmt::Result
+    {
+        let target = |branch: i32| offset as i64 + branch as i64;
+        let prefix = format!("{offset:>10}: ");
+
+        let (mnemonic, operands) = match instruction {
+            Instruction::Bipush(value) => (instruction.mnemonic(), value.to_string()),
+            Instruction::Sipush(value) => (instruction.mnemonic(), value.to_string()),
+
+            Instruction::Iload(index)
+            | Instruction::Lload(index)
+            | Instruction::Fload(index)
+            | Instruction::Dload(index)
+            | Instruction::Aload(index)
+            | Instruction::Istore(index)
+            | Instruction::Lstore(index)
+            | Instruction::Fstore(index)
+            | Instruction::Dstore(index)
+            | Instruction::Astore(index)
+            | Instruction::Ret(index) => (instruction.mnemonic(), index.to_string()),
+
+            Instruction::Iinc { index, value } => {
+                (instruction.mnemonic(), format!("{index}, {value}"))
+            }
+
+            Instruction::Ifeq(branch)
+            | Instruction::Ifne(branch)
+            | Instruction::Iflt(branch)
+            | Instruction::Ifge(branch)
+            | Instruction::Ifgt(branch)
+            | Instruction::Ifle(branch)
+            | Instruction::IfIcmpeq(branch)
+            | Instruction::IfIcmpne(branch)
+            | Instruction::IfIcmplt(branch)
+            | Instruction::IfIcmpge(branch)
+            | Instruction::IfIcmpgt(branch)
+            | Instruction::IfIcmple(branch)
+            | Instruction::IfAcmpeq(branch)
+            | Instruction::IfAcmpne(branch)
+            | Instruction::Goto(branch)
+            | Instruction::Jsr(branch)
+            | Instruction::Ifnull(branch)
+            | Instruction::Ifnonnull(branch) => {
+                (instruction.mnemonic(), target(*branch as i32).to_string())
+            }
+            Instruction::GotoW(branch) | Instruction::JsrW(branch) => {
+                (instruction.mnemonic(), target(*branch).to_string())
+            }
+
+            Instruction::Tableswitch {
+                default,
+                low,
+                high,
+                offsets,
+            } => {
+                writeln!(
+                    f,
+                    "{prefix}{:<13} {{ // {low} to {high}",
+                    instruction.mnemonic()
+                )?;
+                for (key, branch) in (*low..=*high).zip(offsets.iter()) {
+                    writeln!(f, "{key:>24}: {}", target(*branch))?;
+                }
+                writeln!(f, "{:>24}: {}", "default", target(*default))?;
+
+                return writeln!(f, "            }}");
+            }
+            Instruction::Lookupswitch { default, pairs } => {
+                writeln!(
+                    f,
+                    "{prefix}{:<13} {{ // {}",
+                    instruction.mnemonic(),
+                    pairs.len()
+                )?;
+                for (key, branch) in pairs.iter() {
+                    writeln!(f, "{key:>24}: {}", target(*branch))?;
+                }
+                writeln!(f, "{:>24}: {}", "default", target(*default))?;
+
+                return writeln!(f, "            }}");
+            }
+
+            Instruction::Invokeinterface { index, count } => {
+                (instruction.mnemonic(), format!("#{index},  {count}"))
+            }
+            Instruction::Invokedynamic(index) => (instruction.mnemonic(), format!("#{index},  0")),
+            Instruction::Multianewarray { index, dimensions } => {
+                (instruction.mnemonic(), format!("#{index},  {dimensions}"))
+            }
+
+            Instruction::Newarray(array_type) => {
+                (instruction.mnemonic(), format!(" {}", array_type.name()))
+            }
+
+            Instruction::Wide(wide) => {
+                let operands = match wide {
+                    Wide::Iload(index)
+                    | Wide::Lload(index)
+                    | Wide::Fload(index)
+                    | Wide::Dload(index)
+                    | Wide::Aload(index)
+                    | Wide::Istore(index)
+                    | Wide::Lstore(index)
+                    | Wide::Fstore(index)
+                    | Wide::Dstore(index)
+                    | Wide::Astore(index)
+                    | Wide::Ret(index) => index.to_string(),
+                    Wide::Iinc { index, value } => format!("{index}, {value}"),
+                };
+
+                return writeln!(
+                    f,
+                    "{prefix}{:<13} {operands}",
+                    format!("{}_w", wide.mnemonic())
+                );
+            }
+
+            instruction => match instruction.constant_index() {
+                Some(index) => (instruction.mnemonic(), format!("#{index}")),
+                None => return writeln!(f, "{prefix}{}", instruction.mnemonic()),
+            },
+        };
+
+        let line = format!("{prefix}{mnemonic:<13} {operands}");
+        match instruction
+            .constant_index()
+            .and_then(|index| self.describe_operand(instruction, index))
+        {
+            Some(description) => writeln!(f, "{}", comment(&line, 6, &description)),
+            None => writeln!(f, "{line}"),
+        }
+    }
+
+    /// Resolves the constant an instruction refers to, or
+    /// `None` if it is not of the kind the instruction expects
+    fn describe_operand(&self, instruction: &Instruction, index: u16) -> Option<String>
+    {
+        let constant_pool = &self.constant_pool;
+
+        let description = match instruction {
+            Instruction::Ldc(..) | Instruction::LdcW(..) | Instruction::Ldc2W(..) => {
+                match constant_pool.loadable(index).ok()? {
+                    Loadable::Integer(value) => format!("int {value}"),
+                    Loadable::Float(value) => format!("float {}f", java_float(value)),
+                    Loadable::Long(value) => format!("long {value}l"),
+                    Loadable::Double(value) => format!("double {}d", java_float(value)),
+                    Loadable::String(value) => format!("String {}", escape(value)),
+                    Loadable::Class(name) => format!("class {}", quote_class_name(name)),
+                    Loadable::MethodType(descriptor) => format!("MethodType {descriptor}"),
+                    Loadable::MethodHandle {
+                        reference_kind,
+                        reference,
+                    } => format!(
+                        "MethodHandle {} {}",
+                        reference_kind.name(),
+                        self.member(reference)
+                    ),
+                    Loadable::Dynamic {
+                        bootstrap_method_attr_index,
+                        name,
+                        descriptor,
+                    } => format!(
+                        "Dynamic #{bootstrap_method_attr_index}:{}:{descriptor}",
+                        quote_member_name(name)
+                    ),
+                }
+            }
+
+            Instruction::Getstatic(..)
+            | Instruction::Putstatic(..)
+            | Instruction::Getfield(..)
+            | Instruction::Putfield(..) => {
+                format!(
+                    "Field {}",
+                    self.member(constant_pool.field_ref(index).ok()?)
+                )
+            }
+
+            Instruction::Invokevirtual(..)
+            | Instruction::Invokespecial(..)
+            | Instruction::Invokestatic(..) => {
+                let kind = match constant_pool.kind_of(index).ok()? {
+                    (ConstantKind::InterfaceMethodRef, _) => "InterfaceMethod",
+                    _ => "Method",
+                };
+
+                format!(
+                    "{kind} {}",
+                    self.member(constant_pool.method_ref(index).ok()?)
+                )
+            }
+            Instruction::Invokeinterface { .. } => format!(
+                "InterfaceMethod {}",
+                self.member(constant_pool.interface_method_ref(index).ok()?)
+            ),
+            Instruction::Invokedynamic(..) => {
+                let (bootstrap_method_attr_index, name, descriptor) =
+                    constant_pool.invoke_dynamic(index).ok()?;
+
+                format!(
+                    "InvokeDynamic #{bootstrap_method_attr_index}:{}:{descriptor}",
+                    quote_member_name(name)
+                )
+            }
+
+            Instruction::New(..)
+            | Instruction::Anewarray(..)
+            | Instruction::Checkcast(..)
+            | Instruction::Instanceof(..)
+            | Instruction::Multianewarray { .. } => {
+                format!(
+                    "class {}",
+                    quote_class_name(constant_pool.class_name(index).ok()?)
+                )
+            }
+
+            _ => None?,
+        };
+
+        Some(description)
+    }
+
+    /// Formats a member reference, leaving out the class when it is this one
+    fn member(&self, member_ref: MemberRef<'_>) -> String
+    {
+        let MemberRef {
+            class_name,
+            name,
+            descriptor,
+        } = member_ref;
+        let name = quote_member_name(name);
+
+        if class_name == self.name() {
+            format!("{name}:{descriptor}")
+        } else {
+            format!("{}.{name}:{descriptor}", quote_class_name(class_name))
+        }
+    }
+
+    fn exception_names(&self, exception_indices: &[u16]) -> String
+    {
+        let constant_pool = &self.constant_pool;
+
+        exception_indices
+            .iter()
+            .map(|&index| {
+                java_name(constant_pool.class_name_at(constant_pool.checked_index(index)))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn fmt_attribute(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        attribute: &Attribute<'_>,
+        indent: usize,
+    ) -> fmt::Result
+    {
+        let constant_pool = &self.constant_pool;
+
+        match attribute {
+            Attribute::ConstantValue(value) => {
+                let value = match value {
+                    ConstantValue::Integer(value) => format!("int {value}"),
+                    ConstantValue::Float(value) => format!("float {}f", java_float(*value)),
+                    ConstantValue::Long(value) => format!("long {value}l"),
+                    ConstantValue::Double(value) => format!("double {}d", java_float(*value)),
+                    ConstantValue::String(string) => format!("String {}", escape(string)),
+                };
+
+                writeln!(f, "{:indent$}ConstantValue: {value}", "")
+            }
+
+            // Code attributes only show up directly within methods, where
+            // we need the method's descriptor to print them
+            Attribute::Code(..) => writeln!(f, "{:indent$}Code: <outside of a method>", ""),
+
+            Attribute::StackMapTable(stack_map_table) => {
+                writeln!(
+                    f,
+                    "{:indent$}StackMapTable: number_of_entries = {}",
+                    "",
+                    stack_map_table.entries().len()
+                )?;
+                for frame in stack_map_table.entries() {
+                    fmt_stack_map_frame(f, frame, indent + 2)?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::Exceptions { exception_indices } => {
+                writeln!(f, "{:indent$}Exceptions:", "")?;
+                writeln!(
+                    f,
+                    "{:indent$}  throws {}",
+                    "",
+                    self.exception_names(exception_indices)
+                )
+            }
+
+            Attribute::LineNumberTable { line_numbers } => {
+                writeln!(f, "{:indent$}LineNumberTable:", "")?;
+                for line_number in line_numbers.iter() {
+                    writeln!(
+                        f,
+                        "{:indent$}  line {}: {}",
+                        "",
+                        line_number.line_number(),
+                        line_number.start_pc()
+                    )?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::LocalVariableTable { local_variables } => {
+                writeln!(f, "{:indent$}LocalVariableTable:", "")?;
+                writeln!(f, "{:indent$}  Start  Length  Slot  Name   Signature", "")?;
+                for local_variable in local_variables.iter() {
+                    writeln!(
+                        f,
+                        "{:indent$}  {:5} {:7} {:5} {:>5}   {}",
+                        "",
+                        local_variable.start_pc(),
+                        local_variable.length(),
+                        local_variable.index(),
+                        local_variable.name(),
+                        local_variable.descriptor()
+                    )?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::LocalVariableTypeTable {
+                local_variable_types,
+            } => {
+                writeln!(f, "{:indent$}LocalVariableTypeTable:", "")?;
+                writeln!(f, "{:indent$}  Start  Length  Slot  Name   Signature", "")?;
+                for local_variable_type in local_variable_types.iter() {
+                    writeln!(
+                        f,
+                        "{:indent$}  {:5} {:7} {:5} {:>5}   {}",
+                        "",
+                        local_variable_type.start_pc(),
+                        local_variable_type.length(),
+                        local_variable_type.index(),
+                        local_variable_type.name(),
+                        local_variable_type.signature()
+                    )?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::Signature { signature_index } => {
+                let signature =
+                    constant_pool.utf8_at(constant_pool.checked_index(*signature_index));
+
+                writeln!(
+                    f,
+                    "{}",
+                    comment(
+                        &format!("{:indent$}Signature: #{signature_index}", ""),
+                        indent,
+                        signature
+                    )
+                )
+            }
+
+            Attribute::SourceFile { sourcefile_index } => {
+                writeln!(
+                    f,
+                    "{:indent$}SourceFile: \"{}\"",
+                    "",
+                    constant_pool.utf8_at(constant_pool.checked_index(*sourcefile_index))
+                )
+            }
+
+            Attribute::SourceDebugExtension { debug_extension } => {
+                writeln!(f, "{:indent$}SourceDebugExtension:", "")?;
+                for line in debug_extension.lines() {
+                    writeln!(f, "{:indent$}  {line}", "")?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::RuntimeVisibleAnnotations { annotations } => {
+                writeln!(f, "{:indent$}RuntimeVisibleAnnotations:", "")?;
+                self.fmt_annotations(f, annotations, indent + 2)
+            }
+
+            Attribute::RuntimeInvisibleAnnotations { annotations } => {
+                writeln!(f, "{:indent$}RuntimeInvisibleAnnotations:", "")?;
+                self.fmt_annotations(f, annotations, indent + 2)
+            }
+
+            Attribute::RuntimeVisibleParameterAnnotations {
+                parameter_annotations,
+            } => {
+                writeln!(f, "{:indent$}RuntimeVisibleParameterAnnotations:", "")?;
+                self.fmt_parameter_annotations(f, parameter_annotations, indent + 2)
+            }
+
+            Attribute::RuntimeInvisibleParameterAnnotations {
+                parameter_annotations,
+            } => {
+                writeln!(f, "{:indent$}RuntimeInvisibleParameterAnnotations:", "")?;
+                self.fmt_parameter_annotations(f, parameter_annotations, indent + 2)
+            }
+
+            Attribute::RuntimeVisibleTypeAnnotations { type_annotations } => {
+                writeln!(f, "{:indent$}RuntimeVisibleTypeAnnotations:", "")?;
+                self.fmt_type_annotations(f, type_annotations, indent + 2)
+            }
+
+            Attribute::RuntimeInvisibleTypeAnnotations { type_annotations } => {
+                writeln!(f, "{:indent$}RuntimeInvisibleTypeAnnotations:", "")?;
+                self.fmt_type_annotations(f, type_annotations, indent + 2)
+            }
+
+            Attribute::AnnotationDefault { default_value } => {
+                writeln!(f, "{:indent$}AnnotationDefault:", "")?;
+                writeln!(
+                    f,
+                    "{:indent$}  default_value: {}",
+                    "",
+                    self.raw_element_value(default_value)
+                )?;
+                writeln!(
+                    f,
+                    "{:indent$}    {}",
+                    "",
+                    self.resolved_element_value(default_value, indent + 4)
+                )
+            }
+
+            Attribute::BootstrapMethods { bootstrap_methods } => {
+                writeln!(f, "{:indent$}BootstrapMethods:", "")?;
+                for (index, bootstrap_method) in bootstrap_methods.iter().enumerate() {
+                    let method_handle_index = bootstrap_method.bootstrap_method_ref();
+                    let (reference_kind, reference) = constant_pool
+                        .method_handle_at(constant_pool.checked_index(method_handle_index));
+
+                    writeln!(
+                        f,
+                        "{:indent$}  {index}: #{method_handle_index} {} {}",
+                        "",
+                        reference_kind.name(),
+                        qualified_member(reference)
+                    )?;
+                    writeln!(f, "{:indent$}    Method arguments:", "")?;
+                    for &argument in bootstrap_method.bootstrap_arguments() {
+                        let Ok(loadable) = constant_pool.loadable(argument) else {
+                            continue;
+                        };
+
+                        writeln!(
+                            f,
+                            "{:indent$}      #{argument} {}",
+                            "",
+                            bootstrap_argument(loadable)
+                        )?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            Attribute::InnerClasses { classes } => {
+                writeln!(f, "{:indent$}InnerClasses:", "")?;
+                for inner_class in classes.iter() {
+                    writeln!(f, "{}", self.inner_class(inner_class, indent + 2))?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::EnclosingMethod {
+                class_index,
+                method_index,
+            } => {
+                let method = method_index.unwrap_or(0);
+
+                let mut enclosing = java_name(
+                    constant_pool.class_name_at(constant_pool.checked_index(*class_index)),
+                );
+                if let Some(method_index) = method_index {
+                    let (name, _) =
+                        constant_pool.name_and_type_at(constant_pool.checked_index(*method_index));
+
+                    write!(enclosing, ".{name}").unwrap();
+                }
+
+                writeln!(
+                    f,
+                    "{}",
+                    comment(
+                        &format!("{:indent$}EnclosingMethod: #{class_index}.#{method}", ""),
+                        indent,
+                        &enclosing
+                    )
+                )
+            }
+
+            Attribute::NestHost { host_class_index } => {
+                writeln!(
+                    f,
+                    "{:indent$}NestHost: class {}",
+                    "",
+                    quote_class_name(
+                        constant_pool.class_name_at(constant_pool.checked_index(*host_class_index))
+                    )
+                )
+            }
+
+            Attribute::NestMembers { class_indices } => {
+                writeln!(f, "{:indent$}NestMembers:", "")?;
+                self.fmt_class_names(f, class_indices, indent + 2)
+            }
+
+            Attribute::Record { components } => {
+                writeln!(f, "{:indent$}Record:", "")?;
+                for component in components.iter() {
+                    self.fmt_record_component(f, component, indent + 2)?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::PermittedSubclasses { class_indices } => {
+                writeln!(f, "{:indent$}PermittedSubclasses:", "")?;
+                self.fmt_class_names(f, class_indices, indent + 2)
+            }
+
+            Attribute::Synthetic => writeln!(f, "{:indent$}Synthetic: true", ""),
+
+            Attribute::Deprecated => writeln!(f, "{:indent$}Deprecated: true", ""),
+
+            Attribute::MethodParameters { parameters } => {
+                writeln!(f, "{:indent$}MethodParameters:", "")?;
+                writeln!(f, "{:indent$}  {:<30} Flags", "", "Name")?;
+                for parameter in parameters.iter() {
+                    let access_flags = parameter.access_flags();
+                    let flags = [
+                        (access_flags.is_final(), "final "),
+                        (access_flags.is_mandated(), "mandated "),
+                        (access_flags.is_synthetic(), "synthetic"),
+                    ]
+                    .iter()
+                    .filter(|(present, _)| *present)
+                    .map(|(_, flag)| *flag)
+                    .collect::<String>();
+
+                    writeln!(
+                        f,
+                        "{:indent$}  {:<30} {flags}",
+                        "",
+                        parameter.name().unwrap_or("<no name>")
+                    )?;
+                }
+
+                Ok(())
+            }
+
+            Attribute::Custom { name, value } => {
+                writeln!(f, "{:indent$}{name}: {value:?}", "")
+            }
+
+            Attribute::Unknown { name, info } => {
+                writeln!(
+                    f,
+                    "{:indent$}{name}: length = {:#X} (unknown attribute)",
+                    "",
+                    info.len()
+                )?;
+                for chunk in info.chunks(16) {
+                    write!(f, "{:indent$}", "")?;
+                    for byte in chunk {
+                        write!(f, " {byte:02x}")?;
+                    }
+                    writeln!(f)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes an entry of the InnerClasses attribute, such as
+    /// `public static #12= #7 of #2;` followed by what the indices resolve to
+    fn inner_class(&self, inner_class: &InnerClass, indent: usize) -> String
+    {
+        let constant_pool = &self.constant_pool;
+        let access_flags = inner_class.inner_class_access_flags();
+
+        // Interfaces are abstract anyway, so javap leaves that out
+        let modifiers = [
+            (access_flags.is_public(), "public"),
+            (access_flags.is_private(), "private"),
+            (access_flags.is_protected(), "protected"),
+            (access_flags.is_static(), "static"),
+            (
+                access_flags.is_abstract() && !access_flags.is_interface(),
+                "abstract",
+            ),
+            (access_flags.is_final(), "final"),
+        ];
+
+        let mut line = format!("{:indent$}{}", "", modifiers_of(&modifiers));
+        let mut resolved = String::new();
+
+        let class_name = |index| {
+            quote_class_name(constant_pool.class_name_at(constant_pool.checked_index(index)))
+        };
+
+        if let Some(inner_name_index) = inner_class.inner_name_index() {
+            write!(line, "#{inner_name_index}= ").unwrap();
+            write!(
+                resolved,
+                "{}=",
+                constant_pool.utf8_at(constant_pool.checked_index(inner_name_index))
+            )
+            .unwrap();
+        }
+
+        let inner_class_info_index = inner_class.inner_class_info_index();
+        write!(line, "#{inner_class_info_index}").unwrap();
+        write!(resolved, "class {}", class_name(inner_class_info_index)).unwrap();
+
+        if let Some(outer_class_info_index) = inner_class.outer_class_info_index() {
+            write!(line, " of #{outer_class_info_index}").unwrap();
+            write!(resolved, " of class {}", class_name(outer_class_info_index)).unwrap();
+        }
+        line.push(';');
+
+        comment(&line, indent, &resolved)
+    }
+
+    fn fmt_class_names(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        class_indices: &[u16],
+        indent: usize,
+    ) -> fmt::Result
+    {
+        let constant_pool = &self.constant_pool;
+
+        for &class_index in class_indices {
+            let class_name = constant_pool.class_name_at(constant_pool.checked_index(class_index));
+
+            writeln!(f, "{:indent$}{}", "", quote_class_name(class_name))?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_record_component(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        component: &RecordComponent<'_>,
+        indent: usize,
+    ) -> fmt::Result
+    {
+        let component_type = match component.signature() {
+            Some(signature) => java_reference_type(signature.field_type()),
+            None => java_type(component.descriptor()),
+        };
+
+        writeln!(f, "{:indent$}{component_type} {};", "", component.name())?;
+        writeln!(f, "{:indent$}  descriptor: {}", "", component.descriptor())?;
+        for attribute in component.attributes() {
+            self.fmt_attribute(f, attribute, indent + 2)?;
+        }
+
+        writeln!(f)
+    }
+
+    fn fmt_annotations(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        annotations: &[Annotation<'_>],
+        indent: usize,
+    ) -> fmt::Result
+    {
+        for (index, annotation) in annotations.iter().enumerate() {
+            writeln!(
+                f,
+                "{:indent$}{index}: {}",
+                "",
+                self.raw_annotation(annotation)
+            )?;
+            writeln!(
+                f,
+                "{:indent$}  {}",
+                "",
+                self.resolved_annotation(annotation, indent + 2)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_parameter_annotations(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        parameter_annotations: &[Box<[Annotation<'_>]>],
+        indent: usize,
+    ) -> fmt::Result
+    {
+        for (parameter, annotations) in parameter_annotations.iter().enumerate() {
+            writeln!(f, "{:indent$}parameter {parameter}:", "")?;
+            self.fmt_annotations(f, annotations, indent + 2)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_type_annotations(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        type_annotations: &[TypeAnnotation<'_>],
+        indent: usize,
+    ) -> fmt::Result
+    {
+        for (index, type_annotation) in type_annotations.iter().enumerate() {
+            let annotation = type_annotation.annotation();
+
+            writeln!(
+                f,
+                "{:indent$}{index}: {}: {}",
+                "",
+                self.raw_annotation(annotation),
+                type_annotation_position(type_annotation)
+            )?;
+            writeln!(
+                f,
+                "{:indent$}  {}",
+                "",
+                self.resolved_annotation(annotation, indent + 2)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes an annotation with constant pool indices, such as `#42(#105=I#106)`
+    fn raw_annotation(&self, annotation: &Annotation<'_>) -> String
+    {
+        let pairs = annotation
+            .element_value_pairs()
+            .iter()
+            .map(|pair| {
+                format!(
+                    "#{}={}",
+                    pair.element_name_index(),
+                    self.raw_element_value(pair.value())
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!("#{}({})", annotation.type_index(), pairs.join(","))
+    }
+
+    fn raw_element_value(&self, value: &ElementValue<'_>) -> String
+    {
+        let tag = value.tag();
+
+        match value {
+            ElementValue::Byte {
+                const_value_index, ..
+            }
+            | ElementValue::Char {
+                const_value_index, ..
+            }
+            | ElementValue::Double {
+                const_value_index, ..
+            }
+            | ElementValue::Float {
+                const_value_index, ..
+            }
+            | ElementValue::Int {
+                const_value_index, ..
+            }
+            | ElementValue::Long {
+                const_value_index, ..
+            }
+            | ElementValue::Short {
+                const_value_index, ..
+            }
+            | ElementValue::Boolean {
+                const_value_index, ..
+            }
+            | ElementValue::String {
+                const_value_index, ..
+            } => format!("{tag}#{const_value_index}"),
+            ElementValue::Enum {
+                type_name_index,
+                const_name_index,
+                ..
+            } => format!("{tag}#{type_name_index}.#{const_name_index}"),
+            ElementValue::Class {
+                class_info_index, ..
+            } => format!("{tag}#{class_info_index}"),
+            ElementValue::Annotation(annotation) => {
+                format!("{tag}{}", self.raw_annotation(annotation))
+            }
+            ElementValue::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.raw_element_value(value))
+                    .collect::<Vec<_>>();
+
+                format!("[{}]", values.join(","))
+            }
+        }
+    }
+
+    /// Writes an annotation with its values resolved, putting each element
+    /// on a line of its own past `indent`, where the annotation starts
+    fn resolved_annotation(&self, annotation: &Annotation<'_>, indent: usize) -> String
+    {
+        let descriptor = annotation.type_descriptor();
+        let name = FieldType::parse(descriptor).map_or_else(
+            |_| descriptor.to_string(),
+            |field_type| java_type(&field_type),
+        );
+
+        if annotation.element_value_pairs().is_empty() {
+            return name;
+        }
+
+        let mut annotation_string = format!("{name}(\n");
+        for pair in annotation.element_value_pairs() {
+            writeln!(
+                annotation_string,
+                "{:indent$}  {}={}",
+                "",
+                pair.element_name(),
+                self.resolved_element_value(pair.value(), indent + 2)
+            )
+            .unwrap();
+        }
+        write!(annotation_string, "{:indent$})", "").unwrap();
+
+        annotation_string
+    }
+
+    fn resolved_element_value(&self, value: &ElementValue<'_>, indent: usize) -> String
+    {
+        match value {
+            ElementValue::Byte { value, .. } => format!("(byte) {value}"),
+            ElementValue::Char { value, .. } => {
+                let value = char::from_u32(*value as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+
+                format!("'{}'", escape(value.encode_utf8(&mut [0; 4])))
+            }
+            ElementValue::Double { value, .. } => format!("{}d", java_float(*value)),
+            ElementValue::Float { value, .. } => format!("{}f", java_float(*value)),
+            ElementValue::Int { value, .. } => value.to_string(),
+            ElementValue::Long { value, .. } => format!("{value}l"),
+            ElementValue::Short { value, .. } => format!("(short) {value}"),
+            ElementValue::Boolean { value, .. } => value.to_string(),
+            ElementValue::String { value, .. } => format!("\"{}\"", escape(value)),
+            // javap leaves the enum type as a descriptor
+            ElementValue::Enum {
+                type_descriptor,
+                const_name,
+                ..
+            } => format!("{type_descriptor}.{const_name}"),
+            ElementValue::Class {
+                return_descriptor, ..
+            } => format!("class {return_descriptor}"),
+            ElementValue::Annotation(annotation) => {
+                format!("@{}", self.resolved_annotation(annotation, indent))
+            }
+            ElementValue::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.resolved_element_value(value, indent))
+                    .collect::<Vec<_>>();
+
+                format!("[{}]", values.join(","))
+            }
+        }
+    }
+}
+
+/// Writes a static argument of a bootstrap method, such as `()V` for a method type
+fn bootstrap_argument(loadable: Loadable<'_>) -> String
+{
+    match loadable {
+        Loadable::Integer(value) => value.to_string(),
+        Loadable::Float(value) => format!("{}f", java_float(value)),
+        Loadable::Long(value) => format!("{value}l"),
+        Loadable::Double(value) => format!("{}d", java_float(value)),
+        Loadable::String(value) => escape(value),
+        Loadable::Class(name) => quote_class_name(name),
+        Loadable::MethodType(descriptor) => descriptor.to_string(),
+        Loadable::MethodHandle {
+            reference_kind,
+            reference,
+        } => format!("{} {}", reference_kind.name(), qualified_member(reference)),
+        Loadable::Dynamic {
+            bootstrap_method_attr_index,
+            name,
+            descriptor,
+        } => format!(
+            "#{bootstrap_method_attr_index}:{}:{descriptor}",
+            quote_member_name(name)
+        ),
+    }
+}
+
+/// Describes what a type annotation is on the way javap does,
+/// such as `METHOD_FORMAL_PARAMETER, param_index=1, location=[ARRAY]`
+fn type_annotation_position(type_annotation: &TypeAnnotation<'_>) -> String
+{
+    let mut position = type_annotation.target_type().name().to_string();
+
+    match type_annotation.target_info() {
+        TargetInfo::TypeParameter {
+            type_parameter_index,
+        } => write!(position, ", param_index={type_parameter_index}"),
+        TargetInfo::Supertype { supertype_index } => {
+            write!(position, ", type_index={supertype_index}")
+        }
+        TargetInfo::TypeParameterBound {
+            type_parameter_index,
+            bound_index,
+        } => write!(
+            position,
+            ", param_index={type_parameter_index}, bound_index={bound_index}"
+        ),
+        TargetInfo::Empty => Ok(()),
+        TargetInfo::FormalParameter {
+            formal_parameter_index,
+        } => write!(position, ", param_index={formal_parameter_index}"),
+        TargetInfo::Throws { throws_type_index } => {
+            write!(position, ", type_index={throws_type_index}")
+        }
+        TargetInfo::Localvar { table } => {
+            let table = table
+                .iter()
+                .map(|target| {
+                    format!(
+                        "start_pc={}, length={}, index={}",
+                        target.start_pc(),
+                        target.length(),
+                        target.index()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            write!(position, ", {{{}}}", table.join("; "))
+        }
+        TargetInfo::Catch {
+            exception_table_index,
+        } => write!(position, ", exception_index={exception_table_index}"),
+        TargetInfo::Offset { offset } => write!(position, ", offset={offset}"),
+        TargetInfo::TypeArgument {
+            offset,
+            type_argument_index,
+        } => write!(
+            position,
+            ", offset={offset}, type_index={type_argument_index}"
+        ),
+    }
+    .unwrap();
+
+    if !type_annotation.type_path().is_empty() {
+        let location = type_annotation
+            .type_path()
+            .iter()
+            .map(|entry| match entry.type_path_kind() {
+                TypePathKind::Array => "ARRAY".to_string(),
+                TypePathKind::Nested => "INNER_TYPE".to_string(),
+                TypePathKind::Wildcard => "WILDCARD".to_string(),
+                TypePathKind::TypeArgument => {
+                    format!("TYPE_ARGUMENT({})", entry.type_argument_index())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        write!(position, ", location=[{}]", location.join(", ")).unwrap();
+    }
+
+    position
+}
+
+fn fmt_stack_map_frame(
+    f: &mut fmt::Formatter<'_>,
+    frame: &StackMapFrame<'_>,
+    indent: usize,
+) -> fmt::Result
+{
+    let name = match frame {
+        StackMapFrame::Same { .. } => "same",
+        StackMapFrame::SameExtended { .. } => "same_frame_extended",
+        StackMapFrame::SameLocals1StackItem { .. } => "same_locals_1_stack_item",
+        StackMapFrame::SameLocals1StackItemExtended { .. } => {
+            "same_locals_1_stack_item_frame_extended"
+        }
+        StackMapFrame::Chop { .. } => "chop",
+        StackMapFrame::Append { .. } => "append",
+        StackMapFrame::Full { .. } => "full_frame",
+    };
+    writeln!(
+        f,
+        "{:indent$}frame_type = {} /* {name} */",
+        "",
+        frame.frame_type()
+    )?;
+
+    // The offset delta of the short forms is part of their frame type
+    let indent = indent + 2;
+    if !matches!(
+        frame,
+        StackMapFrame::Same { .. } | StackMapFrame::SameLocals1StackItem { .. }
+    ) {
+        writeln!(f, "{:indent$}offset_delta = {}", "", frame.offset_delta())?;
+    }
+
+    match frame {
+        StackMapFrame::SameLocals1StackItem { stack, .. }
+        | StackMapFrame::SameLocals1StackItemExtended { stack, .. } => {
+            writeln!(
+                f,
+                "{:indent$}stack = {}",
+                "",
+                verification_types(std::slice::from_ref(stack))
+            )
+        }
+        StackMapFrame::Append { locals, .. } => {
+            writeln!(f, "{:indent$}locals = {}", "", verification_types(locals))
+        }
+        StackMapFrame::Full { locals, stack, .. } => {
+            writeln!(f, "{:indent$}locals = {}", "", verification_types(locals))?;
+            writeln!(f, "{:indent$}stack = {}", "", verification_types(stack))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Lists verification types the way javap does, such as `[ int, class java/lang/String ]`
+fn verification_types(types: &[VerificationType<'_>]) -> String
+{
+    if types.is_empty() {
+        return "[]".to_string();
+    }
+
+    let types = types
+        .iter()
+        .map(|verification_type| match verification_type {
+            VerificationType::Top => "top".to_string(),
+            VerificationType::Integer => "int".to_string(),
+            VerificationType::Float => "float".to_string(),
+            VerificationType::Double => "double".to_string(),
+            VerificationType::Long => "long".to_string(),
+            VerificationType::Null => "null".to_string(),
+            VerificationType::UninitializedThis => "this".to_string(),
+            VerificationType::Object(class_name) => {
+                format!("class {}", quote_class_name(class_name))
+            }
+            VerificationType::Uninitialized { offset } => format!("uninitialized {offset}"),
+        })
+        .collect::<Vec<_>>();
+
+    format!("[ {} ]", types.join(", "))
+}
+
+/// Appends `comment` to `line`, lined up the way javap does
+/// for a line indented by `indent` columns
+pub(super) fn comment(line: &str, indent: usize, comment: &str) -> String
+{
+    let column = indent + COMMENT_COLUMN;
+
+    if line.len() < column {
+        format!("{line:<column$}// {comment}")
+    } else {
+        format!("{line} // {comment}")
+    }
+}
+
+/// Escapes a string the way Java would write it within a string literal
+pub(super) fn escape(string: &str) -> String
+{
+    let mut escaped = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '"' => escaped.push_str("\\\""),
+            '\'' => escaped.push_str("\\'"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_ascii_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Formats a float the way Java's `Double.toString` does, which switches
+/// to scientific notation outside of the range 10^-3 to 10^7
+pub(super) fn java_float<F>(value: F) -> String
+where
+    F: Copy + Into<f64> + fmt::Debug + fmt::LowerExp,
+{
+    let float: f64 = value.into();
+
+    if float.is_nan() {
+        "NaN".to_string()
+    } else if float.is_infinite() {
+        if float > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else if float == 0.0 || (1e-3..1e7).contains(&float.abs()) {
+        format!("{value:?}")
+    } else {
+        let scientific = format!("{value:e}");
+        let (mantissa, exponent) = scientific.split_once('e').unwrap();
+
+        if mantissa.contains('.') {
+            format!("{mantissa}E{exponent}")
+        } else {
+            format!("{mantissa}.0E{exponent}")
+        }
+    }
+}
+
+/// Quotes array class names, which javap does to set them apart from descriptors
+pub(super) fn quote_class_name(name: &str) -> String
+{
+    if name.starts_with('[') {
+        format!("\"{name}\"")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Formats a member reference along with its class, such as `java/lang/Object."<init>":()V`
+pub(super) fn qualified_member(member_ref: MemberRef<'_>) -> String
+{
+    let MemberRef {
+        class_name,
+        name,
+        descriptor,
+    } = member_ref;
+
+    format!(
+        "{}.{}:{descriptor}",
+        quote_class_name(class_name),
+        quote_member_name(name)
+    )
+}
+
+/// Quotes the special `<init>` and `<clinit>` method names
+pub(super) fn quote_member_name(name: &str) -> String
+{
+    if name.starts_with('<') {
+        format!("\"{name}\"")
+    } else {
+        name.to_string()
+    }
+}
+
+fn flags<'a>(bits: u16, names: impl Iterator<Item = &'a str>) -> String
+{
+    let names = names.collect::<Vec<_>>();
+
+    if names.is_empty() {
+        format!("({bits:#06x})")
+    } else {
+        format!("({bits:#06x}) {}", names.join(", "))
+    }
+}
+
+fn modifiers_of(modifiers: &[(bool, &str)]) -> String
+{
+    modifiers
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, modifier)| format!("{modifier} "))
+        .collect()
+}
+
+/// Turns an internal name such as `java/lang/String` into `java.lang.String`
+fn java_name(name: &str) -> String
+{
+    name.replace('/', ".")
+}
+
+/// Turns a field type such as `[Ljava/lang/String;` into `java.lang.String[]`
+fn java_type(field_type: &FieldType) -> String
+{
+    match field_type {
+        FieldType::Base(base_type) => base_type.name().to_string(),
+        FieldType::Object(class_name) => java_name(class_name),
+        FieldType::Array {
+            dimensions,
+            element,
+        } => java_type(element) + &"[]".repeat(*dimensions as usize),
+    }
+}
+
+/// Turns type parameters into their Java form, such as `<T extends java.lang.Object>`
+fn java_type_parameters(type_parameters: &[TypeParameter]) -> String
+{
+    if type_parameters.is_empty() {
+        return String::new();
+    }
+
+    let type_parameters = type_parameters
+        .iter()
+        .map(|type_parameter| {
+            let bounds = type_parameter
+                .class_bound()
+                .into_iter()
+                .chain(type_parameter.interface_bounds())
+                .map(java_reference_type)
+                .collect::<Vec<_>>();
+
+            format!("{} extends {}", type_parameter.name(), bounds.join(" & "))
+        })
+        .collect::<Vec<_>>();
+
+    format!("<{}>", type_parameters.join(", "))
+}
+
+fn java_type_signature(java_type: &JavaTypeSignature) -> String
+{
+    match java_type {
+        JavaTypeSignature::Base(base_type) => base_type.name().to_string(),
+        JavaTypeSignature::Reference(reference) => java_reference_type(reference),
+    }
+}
+
+fn java_reference_type(reference: &ReferenceTypeSignature) -> String
+{
+    match reference {
+        ReferenceTypeSignature::Class(class) => java_class_type(class),
+        ReferenceTypeSignature::TypeVariable(name) => name.to_string(),
+        ReferenceTypeSignature::Array {
+            dimensions,
+            element,
+        } => java_type_signature(element) + &"[]".repeat(*dimensions as usize),
+    }
+}
+
+/// Turns a class type into its Java form, such as `java.util.Map<K, V>.Entry<K, V>`
+fn java_class_type(class: &ClassTypeSignature) -> String
+{
+    let mut java_type = java_name(class.package());
+    if !java_type.is_empty() {
+        java_type.push('.');
+    }
+
+    for (i, class) in class.classes().iter().enumerate() {
+        if i != 0 {
+            java_type.push('.');
+        }
+        java_type.push_str(class.name());
+
+        if !class.type_arguments().is_empty() {
+            let type_arguments = class
+                .type_arguments()
+                .iter()
+                .map(|type_argument| match type_argument {
+                    TypeArgument::Any => "?".to_string(),
+                    TypeArgument::Exact(bound) => java_reference_type(bound),
+                    TypeArgument::Extends(bound) => {
+                        format!("? extends {}", java_reference_type(bound))
+                    }
+                    TypeArgument::Super(bound) => format!("? super {}", java_reference_type(bound)),
+                })
+                .collect::<Vec<_>>();
+
+            write!(java_type, "<{}>", type_arguments.join(", ")).unwrap();
+        }
+    }
+
+    java_type
+}
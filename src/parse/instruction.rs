@@ -0,0 +1,1051 @@
+use std::{fmt, result};
+
+use crate::parse::cursor::Cursor;
+
+use self::error::{Error, Result};
+
+/// A single JVM instruction, with its operands as they
+/// appear in the code array
+///
+/// Operands referring to the constant pool are kept as their
+/// original indices, see [`Instruction::constant_index`], while
+/// branch operands are relative to the instruction's own offset
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction
+{
+    Nop,
+    AconstNull,
+    IconstM1,
+    Iconst0,
+    Iconst1,
+    Iconst2,
+    Iconst3,
+    Iconst4,
+    Iconst5,
+    Lconst0,
+    Lconst1,
+    Fconst0,
+    Fconst1,
+    Fconst2,
+    Dconst0,
+    Dconst1,
+    Bipush(i8),
+    Sipush(i16),
+    Ldc(u8),
+    LdcW(u16),
+    Ldc2W(u16),
+    Iload(u8),
+    Lload(u8),
+    Fload(u8),
+    Dload(u8),
+    Aload(u8),
+    Iload0,
+    Iload1,
+    Iload2,
+    Iload3,
+    Lload0,
+    Lload1,
+    Lload2,
+    Lload3,
+    Fload0,
+    Fload1,
+    Fload2,
+    Fload3,
+    Dload0,
+    Dload1,
+    Dload2,
+    Dload3,
+    Aload0,
+    Aload1,
+    Aload2,
+    Aload3,
+    Iaload,
+    Laload,
+    Faload,
+    Daload,
+    Aaload,
+    Baload,
+    Caload,
+    Saload,
+    Istore(u8),
+    Lstore(u8),
+    Fstore(u8),
+    Dstore(u8),
+    Astore(u8),
+    Istore0,
+    Istore1,
+    Istore2,
+    Istore3,
+    Lstore0,
+    Lstore1,
+    Lstore2,
+    Lstore3,
+    Fstore0,
+    Fstore1,
+    Fstore2,
+    Fstore3,
+    Dstore0,
+    Dstore1,
+    Dstore2,
+    Dstore3,
+    Astore0,
+    Astore1,
+    Astore2,
+    Astore3,
+    Iastore,
+    Lastore,
+    Fastore,
+    Dastore,
+    Aastore,
+    Bastore,
+    Castore,
+    Sastore,
+    Pop,
+    Pop2,
+    Dup,
+    DupX1,
+    DupX2,
+    Dup2,
+    Dup2X1,
+    Dup2X2,
+    Swap,
+    Iadd,
+    Ladd,
+    Fadd,
+    Dadd,
+    Isub,
+    Lsub,
+    Fsub,
+    Dsub,
+    Imul,
+    Lmul,
+    Fmul,
+    Dmul,
+    Idiv,
+    Ldiv,
+    Fdiv,
+    Ddiv,
+    Irem,
+    Lrem,
+    Frem,
+    Drem,
+    Ineg,
+    Lneg,
+    Fneg,
+    Dneg,
+    Ishl,
+    Lshl,
+    Ishr,
+    Lshr,
+    Iushr,
+    Lushr,
+    Iand,
+    Land,
+    Ior,
+    Lor,
+    Ixor,
+    Lxor,
+    Iinc
+    {
+        index: u8,
+        value: i8,
+    },
+    I2l,
+    I2f,
+    I2d,
+    L2i,
+    L2f,
+    L2d,
+    F2i,
+    F2l,
+    F2d,
+    D2i,
+    D2l,
+    D2f,
+    I2b,
+    I2c,
+    I2s,
+    Lcmp,
+    Fcmpl,
+    Fcmpg,
+    Dcmpl,
+    Dcmpg,
+    Ifeq(i16),
+    Ifne(i16),
+    Iflt(i16),
+    Ifge(i16),
+    Ifgt(i16),
+    Ifle(i16),
+    IfIcmpeq(i16),
+    IfIcmpne(i16),
+    IfIcmplt(i16),
+    IfIcmpge(i16),
+    IfIcmpgt(i16),
+    IfIcmple(i16),
+    IfAcmpeq(i16),
+    IfAcmpne(i16),
+    Goto(i16),
+    Jsr(i16),
+    Ret(u8),
+    Tableswitch
+    {
+        default: i32,
+        low: i32,
+        high: i32,
+        offsets: Box<[i32]>,
+    },
+    Lookupswitch
+    {
+        default: i32,
+        pairs: Box<[(i32, i32)]>,
+    },
+    Ireturn,
+    Lreturn,
+    Freturn,
+    Dreturn,
+    Areturn,
+    Return,
+    Getstatic(u16),
+    Putstatic(u16),
+    Getfield(u16),
+    Putfield(u16),
+    Invokevirtual(u16),
+    Invokespecial(u16),
+    Invokestatic(u16),
+    Invokeinterface
+    {
+        index: u16,
+        count: u8,
+    },
+    Invokedynamic(u16),
+    New(u16),
+    Newarray(ArrayType),
+    Anewarray(u16),
+    Arraylength,
+    Athrow,
+    Checkcast(u16),
+    Instanceof(u16),
+    Monitorenter,
+    Monitorexit,
+    Wide(Wide),
+    Multianewarray
+    {
+        index: u16,
+        dimensions: u8,
+    },
+    Ifnull(i16),
+    Ifnonnull(i16),
+    GotoW(i32),
+    JsrW(i32),
+}
+
+impl Instruction
+{
+    fn decode(cursor: &mut Cursor, offset: u32) -> Result<Self>
+    {
+        let opcode = cursor.read_integer::<u8>()?;
+
+        let instruction = match opcode {
+            0x00 => Instruction::Nop,
+            0x01 => Instruction::AconstNull,
+            0x02 => Instruction::IconstM1,
+            0x03 => Instruction::Iconst0,
+            0x04 => Instruction::Iconst1,
+            0x05 => Instruction::Iconst2,
+            0x06 => Instruction::Iconst3,
+            0x07 => Instruction::Iconst4,
+            0x08 => Instruction::Iconst5,
+            0x09 => Instruction::Lconst0,
+            0x0a => Instruction::Lconst1,
+            0x0b => Instruction::Fconst0,
+            0x0c => Instruction::Fconst1,
+            0x0d => Instruction::Fconst2,
+            0x0e => Instruction::Dconst0,
+            0x0f => Instruction::Dconst1,
+            0x10 => Instruction::Bipush(cursor.read_integer::<i8>()?),
+            0x11 => Instruction::Sipush(cursor.read_integer::<i16>()?),
+            0x12 => Instruction::Ldc(cursor.read_integer::<u8>()?),
+            0x13 => Instruction::LdcW(cursor.read_integer::<u16>()?),
+            0x14 => Instruction::Ldc2W(cursor.read_integer::<u16>()?),
+            0x15 => Instruction::Iload(cursor.read_integer::<u8>()?),
+            0x16 => Instruction::Lload(cursor.read_integer::<u8>()?),
+            0x17 => Instruction::Fload(cursor.read_integer::<u8>()?),
+            0x18 => Instruction::Dload(cursor.read_integer::<u8>()?),
+            0x19 => Instruction::Aload(cursor.read_integer::<u8>()?),
+            0x1a => Instruction::Iload0,
+            0x1b => Instruction::Iload1,
+            0x1c => Instruction::Iload2,
+            0x1d => Instruction::Iload3,
+            0x1e => Instruction::Lload0,
+            0x1f => Instruction::Lload1,
+            0x20 => Instruction::Lload2,
+            0x21 => Instruction::Lload3,
+            0x22 => Instruction::Fload0,
+            0x23 => Instruction::Fload1,
+            0x24 => Instruction::Fload2,
+            0x25 => Instruction::Fload3,
+            0x26 => Instruction::Dload0,
+            0x27 => Instruction::Dload1,
+            0x28 => Instruction::Dload2,
+            0x29 => Instruction::Dload3,
+            0x2a => Instruction::Aload0,
+            0x2b => Instruction::Aload1,
+            0x2c => Instruction::Aload2,
+            0x2d => Instruction::Aload3,
+            0x2e => Instruction::Iaload,
+            0x2f => Instruction::Laload,
+            0x30 => Instruction::Faload,
+            0x31 => Instruction::Daload,
+            0x32 => Instruction::Aaload,
+            0x33 => Instruction::Baload,
+            0x34 => Instruction::Caload,
+            0x35 => Instruction::Saload,
+            0x36 => Instruction::Istore(cursor.read_integer::<u8>()?),
+            0x37 => Instruction::Lstore(cursor.read_integer::<u8>()?),
+            0x38 => Instruction::Fstore(cursor.read_integer::<u8>()?),
+            0x39 => Instruction::Dstore(cursor.read_integer::<u8>()?),
+            0x3a => Instruction::Astore(cursor.read_integer::<u8>()?),
+            0x3b => Instruction::Istore0,
+            0x3c => Instruction::Istore1,
+            0x3d => Instruction::Istore2,
+            0x3e => Instruction::Istore3,
+            0x3f => Instruction::Lstore0,
+            0x40 => Instruction::Lstore1,
+            0x41 => Instruction::Lstore2,
+            0x42 => Instruction::Lstore3,
+            0x43 => Instruction::Fstore0,
+            0x44 => Instruction::Fstore1,
+            0x45 => Instruction::Fstore2,
+            0x46 => Instruction::Fstore3,
+            0x47 => Instruction::Dstore0,
+            0x48 => Instruction::Dstore1,
+            0x49 => Instruction::Dstore2,
+            0x4a => Instruction::Dstore3,
+            0x4b => Instruction::Astore0,
+            0x4c => Instruction::Astore1,
+            0x4d => Instruction::Astore2,
+            0x4e => Instruction::Astore3,
+            0x4f => Instruction::Iastore,
+            0x50 => Instruction::Lastore,
+            0x51 => Instruction::Fastore,
+            0x52 => Instruction::Dastore,
+            0x53 => Instruction::Aastore,
+            0x54 => Instruction::Bastore,
+            0x55 => Instruction::Castore,
+            0x56 => Instruction::Sastore,
+            0x57 => Instruction::Pop,
+            0x58 => Instruction::Pop2,
+            0x59 => Instruction::Dup,
+            0x5a => Instruction::DupX1,
+            0x5b => Instruction::DupX2,
+            0x5c => Instruction::Dup2,
+            0x5d => Instruction::Dup2X1,
+            0x5e => Instruction::Dup2X2,
+            0x5f => Instruction::Swap,
+            0x60 => Instruction::Iadd,
+            0x61 => Instruction::Ladd,
+            0x62 => Instruction::Fadd,
+            0x63 => Instruction::Dadd,
+            0x64 => Instruction::Isub,
+            0x65 => Instruction::Lsub,
+            0x66 => Instruction::Fsub,
+            0x67 => Instruction::Dsub,
+            0x68 => Instruction::Imul,
+            0x69 => Instruction::Lmul,
+            0x6a => Instruction::Fmul,
+            0x6b => Instruction::Dmul,
+            0x6c => Instruction::Idiv,
+            0x6d => Instruction::Ldiv,
+            0x6e => Instruction::Fdiv,
+            0x6f => Instruction::Ddiv,
+            0x70 => Instruction::Irem,
+            0x71 => Instruction::Lrem,
+            0x72 => Instruction::Frem,
+            0x73 => Instruction::Drem,
+            0x74 => Instruction::Ineg,
+            0x75 => Instruction::Lneg,
+            0x76 => Instruction::Fneg,
+            0x77 => Instruction::Dneg,
+            0x78 => Instruction::Ishl,
+            0x79 => Instruction::Lshl,
+            0x7a => Instruction::Ishr,
+            0x7b => Instruction::Lshr,
+            0x7c => Instruction::Iushr,
+            0x7d => Instruction::Lushr,
+            0x7e => Instruction::Iand,
+            0x7f => Instruction::Land,
+            0x80 => Instruction::Ior,
+            0x81 => Instruction::Lor,
+            0x82 => Instruction::Ixor,
+            0x83 => Instruction::Lxor,
+            0x84 => {
+                let index = cursor.read_integer::<u8>()?;
+                let value = cursor.read_integer::<i8>()?;
+
+                Instruction::Iinc { index, value }
+            }
+            0x85 => Instruction::I2l,
+            0x86 => Instruction::I2f,
+            0x87 => Instruction::I2d,
+            0x88 => Instruction::L2i,
+            0x89 => Instruction::L2f,
+            0x8a => Instruction::L2d,
+            0x8b => Instruction::F2i,
+            0x8c => Instruction::F2l,
+            0x8d => Instruction::F2d,
+            0x8e => Instruction::D2i,
+            0x8f => Instruction::D2l,
+            0x90 => Instruction::D2f,
+            0x91 => Instruction::I2b,
+            0x92 => Instruction::I2c,
+            0x93 => Instruction::I2s,
+            0x94 => Instruction::Lcmp,
+            0x95 => Instruction::Fcmpl,
+            0x96 => Instruction::Fcmpg,
+            0x97 => Instruction::Dcmpl,
+            0x98 => Instruction::Dcmpg,
+            0x99 => Instruction::Ifeq(cursor.read_integer::<i16>()?),
+            0x9a => Instruction::Ifne(cursor.read_integer::<i16>()?),
+            0x9b => Instruction::Iflt(cursor.read_integer::<i16>()?),
+            0x9c => Instruction::Ifge(cursor.read_integer::<i16>()?),
+            0x9d => Instruction::Ifgt(cursor.read_integer::<i16>()?),
+            0x9e => Instruction::Ifle(cursor.read_integer::<i16>()?),
+            0x9f => Instruction::IfIcmpeq(cursor.read_integer::<i16>()?),
+            0xa0 => Instruction::IfIcmpne(cursor.read_integer::<i16>()?),
+            0xa1 => Instruction::IfIcmplt(cursor.read_integer::<i16>()?),
+            0xa2 => Instruction::IfIcmpge(cursor.read_integer::<i16>()?),
+            0xa3 => Instruction::IfIcmpgt(cursor.read_integer::<i16>()?),
+            0xa4 => Instruction::IfIcmple(cursor.read_integer::<i16>()?),
+            0xa5 => Instruction::IfAcmpeq(cursor.read_integer::<i16>()?),
+            0xa6 => Instruction::IfAcmpne(cursor.read_integer::<i16>()?),
+            0xa7 => Instruction::Goto(cursor.read_integer::<i16>()?),
+            0xa8 => Instruction::Jsr(cursor.read_integer::<i16>()?),
+            0xa9 => Instruction::Ret(cursor.read_integer::<u8>()?),
+            0xaa => {
+                skip_padding(cursor, offset)?;
+
+                let default = cursor.read_integer::<i32>()?;
+                let low = cursor.read_integer::<i32>()?;
+                let high = cursor.read_integer::<i32>()?;
+                if low > high {
+                    Err(Error::InvalidTableswitch { offset, low, high })?
+                }
+
+                let offsets = (low..=high)
+                    .map(|_| Ok(cursor.read_integer::<i32>()?))
+                    .collect::<Result<_>>()?;
+
+                Instruction::Tableswitch {
+                    default,
+                    low,
+                    high,
+                    offsets,
+                }
+            }
+            0xab => {
+                skip_padding(cursor, offset)?;
+
+                let default = cursor.read_integer::<i32>()?;
+                let npairs = cursor.read_integer::<i32>()?;
+                if npairs < 0 {
+                    Err(Error::InvalidLookupswitch { offset })?
+                }
+
+                let pairs = (0..npairs)
+                    .map(|_| {
+                        let key = cursor.read_integer::<i32>()?;
+                        let jump = cursor.read_integer::<i32>()?;
+
+                        Ok((key, jump))
+                    })
+                    .collect::<Result<Box<[_]>>>()?;
+                // The keys must be sorted so that the lookup can be a binary search
+                if pairs.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+                    Err(Error::InvalidLookupswitch { offset })?
+                }
+
+                Instruction::Lookupswitch { default, pairs }
+            }
+            0xac => Instruction::Ireturn,
+            0xad => Instruction::Lreturn,
+            0xae => Instruction::Freturn,
+            0xaf => Instruction::Dreturn,
+            0xb0 => Instruction::Areturn,
+            0xb1 => Instruction::Return,
+            0xb2 => Instruction::Getstatic(cursor.read_integer::<u16>()?),
+            0xb3 => Instruction::Putstatic(cursor.read_integer::<u16>()?),
+            0xb4 => Instruction::Getfield(cursor.read_integer::<u16>()?),
+            0xb5 => Instruction::Putfield(cursor.read_integer::<u16>()?),
+            0xb6 => Instruction::Invokevirtual(cursor.read_integer::<u16>()?),
+            0xb7 => Instruction::Invokespecial(cursor.read_integer::<u16>()?),
+            0xb8 => Instruction::Invokestatic(cursor.read_integer::<u16>()?),
+            0xb9 => {
+                let index = cursor.read_integer::<u16>()?;
+                let count = cursor.read_integer::<u8>()?;
+                let zero = cursor.read_integer::<u8>()?;
+                if count == 0 || zero != 0 {
+                    Err(Error::InvalidOperands { offset, opcode })?
+                }
+
+                Instruction::Invokeinterface { index, count }
+            }
+            0xba => {
+                let index = cursor.read_integer::<u16>()?;
+                let zero = cursor.read_integer::<u16>()?;
+                if zero != 0 {
+                    Err(Error::InvalidOperands { offset, opcode })?
+                }
+
+                Instruction::Invokedynamic(index)
+            }
+            0xbb => Instruction::New(cursor.read_integer::<u16>()?),
+            0xbc => {
+                let atype = cursor.read_integer::<u8>()?;
+                let array_type = ArrayType::try_from(atype)
+                    .map_err(|_| Error::InvalidOperands { offset, opcode })?;
+
+                Instruction::Newarray(array_type)
+            }
+            0xbd => Instruction::Anewarray(cursor.read_integer::<u16>()?),
+            0xbe => Instruction::Arraylength,
+            0xbf => Instruction::Athrow,
+            0xc0 => Instruction::Checkcast(cursor.read_integer::<u16>()?),
+            0xc1 => Instruction::Instanceof(cursor.read_integer::<u16>()?),
+            0xc2 => Instruction::Monitorenter,
+            0xc3 => Instruction::Monitorexit,
+            0xc4 => Instruction::Wide(Wide::decode(cursor, offset)?),
+            0xc5 => {
+                let index = cursor.read_integer::<u16>()?;
+                let dimensions = cursor.read_integer::<u8>()?;
+                if dimensions == 0 {
+                    Err(Error::InvalidOperands { offset, opcode })?
+                }
+
+                Instruction::Multianewarray { index, dimensions }
+            }
+            0xc6 => Instruction::Ifnull(cursor.read_integer::<i16>()?),
+            0xc7 => Instruction::Ifnonnull(cursor.read_integer::<i16>()?),
+            0xc8 => Instruction::GotoW(cursor.read_integer::<i32>()?),
+            0xc9 => Instruction::JsrW(cursor.read_integer::<i32>()?),
+
+            opcode => Err(Error::InvalidOpcode { offset, opcode })?,
+        };
+
+        Ok(instruction)
+    }
+
+    pub fn mnemonic(&self) -> &'static str
+    {
+        match self {
+            Instruction::Nop => "nop",
+            Instruction::AconstNull => "aconst_null",
+            Instruction::IconstM1 => "iconst_m1",
+            Instruction::Iconst0 => "iconst_0",
+            Instruction::Iconst1 => "iconst_1",
+            Instruction::Iconst2 => "iconst_2",
+            Instruction::Iconst3 => "iconst_3",
+            Instruction::Iconst4 => "iconst_4",
+            Instruction::Iconst5 => "iconst_5",
+            Instruction::Lconst0 => "lconst_0",
+            Instruction::Lconst1 => "lconst_1",
+            Instruction::Fconst0 => "fconst_0",
+            Instruction::Fconst1 => "fconst_1",
+            Instruction::Fconst2 => "fconst_2",
+            Instruction::Dconst0 => "dconst_0",
+            Instruction::Dconst1 => "dconst_1",
+            Instruction::Bipush(..) => "bipush",
+            Instruction::Sipush(..) => "sipush",
+            Instruction::Ldc(..) => "ldc",
+            Instruction::LdcW(..) => "ldc_w",
+            Instruction::Ldc2W(..) => "ldc2_w",
+            Instruction::Iload(..) => "iload",
+            Instruction::Lload(..) => "lload",
+            Instruction::Fload(..) => "fload",
+            Instruction::Dload(..) => "dload",
+            Instruction::Aload(..) => "aload",
+            Instruction::Iload0 => "iload_0",
+            Instruction::Iload1 => "iload_1",
+            Instruction::Iload2 => "iload_2",
+            Instruction::Iload3 => "iload_3",
+            Instruction::Lload0 => "lload_0",
+            Instruction::Lload1 => "lload_1",
+            Instruction::Lload2 => "lload_2",
+            Instruction::Lload3 => "lload_3",
+            Instruction::Fload0 => "fload_0",
+            Instruction::Fload1 => "fload_1",
+            Instruction::Fload2 => "fload_2",
+            Instruction::Fload3 => "fload_3",
+            Instruction::Dload0 => "dload_0",
+            Instruction::Dload1 => "dload_1",
+            Instruction::Dload2 => "dload_2",
+            Instruction::Dload3 => "dload_3",
+            Instruction::Aload0 => "aload_0",
+            Instruction::Aload1 => "aload_1",
+            Instruction::Aload2 => "aload_2",
+            Instruction::Aload3 => "aload_3",
+            Instruction::Iaload => "iaload",
+            Instruction::Laload => "laload",
+            Instruction::Faload => "faload",
+            Instruction::Daload => "daload",
+            Instruction::Aaload => "aaload",
+            Instruction::Baload => "baload",
+            Instruction::Caload => "caload",
+            Instruction::Saload => "saload",
+            Instruction::Istore(..) => "istore",
+            Instruction::Lstore(..) => "lstore",
+            Instruction::Fstore(..) => "fstore",
+            Instruction::Dstore(..) => "dstore",
+            Instruction::Astore(..) => "astore",
+            Instruction::Istore0 => "istore_0",
+            Instruction::Istore1 => "istore_1",
+            Instruction::Istore2 => "istore_2",
+            Instruction::Istore3 => "istore_3",
+            Instruction::Lstore0 => "lstore_0",
+            Instruction::Lstore1 => "lstore_1",
+            Instruction::Lstore2 => "lstore_2",
+            Instruction::Lstore3 => "lstore_3",
+            Instruction::Fstore0 => "fstore_0",
+            Instruction::Fstore1 => "fstore_1",
+            Instruction::Fstore2 => "fstore_2",
+            Instruction::Fstore3 => "fstore_3",
+            Instruction::Dstore0 => "dstore_0",
+            Instruction::Dstore1 => "dstore_1",
+            Instruction::Dstore2 => "dstore_2",
+            Instruction::Dstore3 => "dstore_3",
+            Instruction::Astore0 => "astore_0",
+            Instruction::Astore1 => "astore_1",
+            Instruction::Astore2 => "astore_2",
+            Instruction::Astore3 => "astore_3",
+            Instruction::Iastore => "iastore",
+            Instruction::Lastore => "lastore",
+            Instruction::Fastore => "fastore",
+            Instruction::Dastore => "dastore",
+            Instruction::Aastore => "aastore",
+            Instruction::Bastore => "bastore",
+            Instruction::Castore => "castore",
+            Instruction::Sastore => "sastore",
+            Instruction::Pop => "pop",
+            Instruction::Pop2 => "pop2",
+            Instruction::Dup => "dup",
+            Instruction::DupX1 => "dup_x1",
+            Instruction::DupX2 => "dup_x2",
+            Instruction::Dup2 => "dup2",
+            Instruction::Dup2X1 => "dup2_x1",
+            Instruction::Dup2X2 => "dup2_x2",
+            Instruction::Swap => "swap",
+            Instruction::Iadd => "iadd",
+            Instruction::Ladd => "ladd",
+            Instruction::Fadd => "fadd",
+            Instruction::Dadd => "dadd",
+            Instruction::Isub => "isub",
+            Instruction::Lsub => "lsub",
+            Instruction::Fsub => "fsub",
+            Instruction::Dsub => "dsub",
+            Instruction::Imul => "imul",
+            Instruction::Lmul => "lmul",
+            Instruction::Fmul => "fmul",
+            Instruction::Dmul => "dmul",
+            Instruction::Idiv => "idiv",
+            Instruction::Ldiv => "ldiv",
+            Instruction::Fdiv => "fdiv",
+            Instruction::Ddiv => "ddiv",
+            Instruction::Irem => "irem",
+            Instruction::Lrem => "lrem",
+            Instruction::Frem => "frem",
+            Instruction::Drem => "drem",
+            Instruction::Ineg => "ineg",
+            Instruction::Lneg => "lneg",
+            Instruction::Fneg => "fneg",
+            Instruction::Dneg => "dneg",
+            Instruction::Ishl => "ishl",
+            Instruction::Lshl => "lshl",
+            Instruction::Ishr => "ishr",
+            Instruction::Lshr => "lshr",
+            Instruction::Iushr => "iushr",
+            Instruction::Lushr => "lushr",
+            Instruction::Iand => "iand",
+            Instruction::Land => "land",
+            Instruction::Ior => "ior",
+            Instruction::Lor => "lor",
+            Instruction::Ixor => "ixor",
+            Instruction::Lxor => "lxor",
+            Instruction::Iinc { .. } => "iinc",
+            Instruction::I2l => "i2l",
+            Instruction::I2f => "i2f",
+            Instruction::I2d => "i2d",
+            Instruction::L2i => "l2i",
+            Instruction::L2f => "l2f",
+            Instruction::L2d => "l2d",
+            Instruction::F2i => "f2i",
+            Instruction::F2l => "f2l",
+            Instruction::F2d => "f2d",
+            Instruction::D2i => "d2i",
+            Instruction::D2l => "d2l",
+            Instruction::D2f => "d2f",
+            Instruction::I2b => "i2b",
+            Instruction::I2c => "i2c",
+            Instruction::I2s => "i2s",
+            Instruction::Lcmp => "lcmp",
+            Instruction::Fcmpl => "fcmpl",
+            Instruction::Fcmpg => "fcmpg",
+            Instruction::Dcmpl => "dcmpl",
+            Instruction::Dcmpg => "dcmpg",
+            Instruction::Ifeq(..) => "ifeq",
+            Instruction::Ifne(..) => "ifne",
+            Instruction::Iflt(..) => "iflt",
+            Instruction::Ifge(..) => "ifge",
+            Instruction::Ifgt(..) => "ifgt",
+            Instruction::Ifle(..) => "ifle",
+            Instruction::IfIcmpeq(..) => "if_icmpeq",
+            Instruction::IfIcmpne(..) => "if_icmpne",
+            Instruction::IfIcmplt(..) => "if_icmplt",
+            Instruction::IfIcmpge(..) => "if_icmpge",
+            Instruction::IfIcmpgt(..) => "if_icmpgt",
+            Instruction::IfIcmple(..) => "if_icmple",
+            Instruction::IfAcmpeq(..) => "if_acmpeq",
+            Instruction::IfAcmpne(..) => "if_acmpne",
+            Instruction::Goto(..) => "goto",
+            Instruction::Jsr(..) => "jsr",
+            Instruction::Ret(..) => "ret",
+            Instruction::Tableswitch { .. } => "tableswitch",
+            Instruction::Lookupswitch { .. } => "lookupswitch",
+            Instruction::Ireturn => "ireturn",
+            Instruction::Lreturn => "lreturn",
+            Instruction::Freturn => "freturn",
+            Instruction::Dreturn => "dreturn",
+            Instruction::Areturn => "areturn",
+            Instruction::Return => "return",
+            Instruction::Getstatic(..) => "getstatic",
+            Instruction::Putstatic(..) => "putstatic",
+            Instruction::Getfield(..) => "getfield",
+            Instruction::Putfield(..) => "putfield",
+            Instruction::Invokevirtual(..) => "invokevirtual",
+            Instruction::Invokespecial(..) => "invokespecial",
+            Instruction::Invokestatic(..) => "invokestatic",
+            Instruction::Invokeinterface { .. } => "invokeinterface",
+            Instruction::Invokedynamic(..) => "invokedynamic",
+            Instruction::New(..) => "new",
+            Instruction::Newarray(..) => "newarray",
+            Instruction::Anewarray(..) => "anewarray",
+            Instruction::Arraylength => "arraylength",
+            Instruction::Athrow => "athrow",
+            Instruction::Checkcast(..) => "checkcast",
+            Instruction::Instanceof(..) => "instanceof",
+            Instruction::Monitorenter => "monitorenter",
+            Instruction::Monitorexit => "monitorexit",
+            Instruction::Wide(..) => "wide",
+            Instruction::Multianewarray { .. } => "multianewarray",
+            Instruction::Ifnull(..) => "ifnull",
+            Instruction::Ifnonnull(..) => "ifnonnull",
+            Instruction::GotoW(..) => "goto_w",
+            Instruction::JsrW(..) => "jsr_w",
+        }
+    }
+
+    /// The constant pool index this instruction refers to, if any
+    pub fn constant_index(&self) -> Option<u16>
+    {
+        match self {
+            Instruction::Ldc(index) => Some(*index as u16),
+            Instruction::LdcW(index)
+            | Instruction::Ldc2W(index)
+            | Instruction::Getstatic(index)
+            | Instruction::Putstatic(index)
+            | Instruction::Getfield(index)
+            | Instruction::Putfield(index)
+            | Instruction::Invokevirtual(index)
+            | Instruction::Invokespecial(index)
+            | Instruction::Invokestatic(index)
+            | Instruction::Invokeinterface { index, .. }
+            | Instruction::Invokedynamic(index)
+            | Instruction::New(index)
+            | Instruction::Anewarray(index)
+            | Instruction::Checkcast(index)
+            | Instruction::Instanceof(index)
+            | Instruction::Multianewarray { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayType
+{
+    Boolean,
+    Char,
+    Float,
+    Double,
+    Byte,
+    Short,
+    Int,
+    Long,
+}
+
+impl ArrayType
+{
+    pub fn name(&self) -> &'static str
+    {
+        match self {
+            ArrayType::Boolean => "boolean",
+            ArrayType::Char => "char",
+            ArrayType::Float => "float",
+            ArrayType::Double => "double",
+            ArrayType::Byte => "byte",
+            ArrayType::Short => "short",
+            ArrayType::Int => "int",
+            ArrayType::Long => "long",
+        }
+    }
+}
+
+impl TryFrom<u8> for ArrayType
+{
+    type Error = u8;
+
+    fn try_from(atype: u8) -> result::Result<Self, u8>
+    {
+        match atype {
+            4 => Ok(ArrayType::Boolean),
+            5 => Ok(ArrayType::Char),
+            6 => Ok(ArrayType::Float),
+            7 => Ok(ArrayType::Double),
+            8 => Ok(ArrayType::Byte),
+            9 => Ok(ArrayType::Short),
+            10 => Ok(ArrayType::Int),
+            11 => Ok(ArrayType::Long),
+            atype => Err(atype),
+        }
+    }
+}
+
+/// An instruction modified by a preceding `wide`, which
+/// widens its local variable index to 16 bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wide
+{
+    Iload(u16),
+    Lload(u16),
+    Fload(u16),
+    Dload(u16),
+    Aload(u16),
+    Istore(u16),
+    Lstore(u16),
+    Fstore(u16),
+    Dstore(u16),
+    Astore(u16),
+    Ret(u16),
+    Iinc
+    {
+        index: u16,
+        value: i16,
+    },
+}
+
+impl Wide
+{
+    fn decode(cursor: &mut Cursor, offset: u32) -> Result<Self>
+    {
+        let opcode = cursor.read_integer::<u8>()?;
+
+        let wide = match opcode {
+            0x84 => {
+                let index = cursor.read_integer::<u16>()?;
+                let value = cursor.read_integer::<i16>()?;
+
+                return Ok(Wide::Iinc { index, value });
+            }
+
+            0x15 => Wide::Iload,
+            0x16 => Wide::Lload,
+            0x17 => Wide::Fload,
+            0x18 => Wide::Dload,
+            0x19 => Wide::Aload,
+            0x36 => Wide::Istore,
+            0x37 => Wide::Lstore,
+            0x38 => Wide::Fstore,
+            0x39 => Wide::Dstore,
+            0x3a => Wide::Astore,
+            0xa9 => Wide::Ret,
+
+            opcode => Err(Error::InvalidWideOpcode { offset, opcode })?,
+        };
+
+        Ok(wide(cursor.read_integer::<u16>()?))
+    }
+
+    pub fn mnemonic(&self) -> &'static str
+    {
+        match self {
+            Wide::Iload(..) => "iload",
+            Wide::Lload(..) => "lload",
+            Wide::Fload(..) => "fload",
+            Wide::Dload(..) => "dload",
+            Wide::Aload(..) => "aload",
+            Wide::Istore(..) => "istore",
+            Wide::Lstore(..) => "lstore",
+            Wide::Fstore(..) => "fstore",
+            Wide::Dstore(..) => "dstore",
+            Wide::Astore(..) => "astore",
+            Wide::Ret(..) => "ret",
+            Wide::Iinc { .. } => "iinc",
+        }
+    }
+}
+
+/// Skips the padding that aligns the operands of `tableswitch`
+/// and `lookupswitch` to a multiple of four from the start of the code
+fn skip_padding(cursor: &mut Cursor, offset: u32) -> Result<()>
+{
+    let padding = (3 - offset % 4) as usize;
+    cursor.read_bytes(padding)?;
+
+    Ok(())
+}
+
+/// Decodes a code array into its instructions, alongside their offsets
+pub struct Instructions<'a>
+{
+    code: &'a [u8],
+    cursor: Cursor<'a>,
+    failed: bool,
+}
+
+impl<'a> Instructions<'a>
+{
+    pub fn new(code: &'a [u8]) -> Self
+    {
+        let cursor = Cursor::new(code);
+
+        Instructions {
+            code,
+            cursor,
+            failed: false,
+        }
+    }
+}
+
+impl Iterator for Instructions<'_>
+{
+    type Item = Result<(u32, Instruction)>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.failed || self.cursor.is_empty() {
+            return None;
+        }
+
+        let offset = (self.code.len() - self.cursor.len()) as u32;
+        let instruction = Instruction::decode(&mut self.cursor, offset);
+
+        // Once decoding fails we cannot tell where the next instruction starts
+        self.failed = instruction.is_err();
+
+        Some(instruction.map(|instruction| (offset, instruction)))
+    }
+}
+
+impl fmt::Debug for Instructions<'_>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("Instructions")
+            .field("offset", &(self.code.len() - self.cursor.len()))
+            .finish()
+    }
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    use crate::parse::cursor;
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        Cursor(cursor::Error),
+        InvalidOpcode
+        {
+            offset: u32,
+            opcode: u8,
+        },
+        InvalidWideOpcode
+        {
+            offset: u32,
+            opcode: u8,
+        },
+        InvalidOperands
+        {
+            offset: u32,
+            opcode: u8,
+        },
+        InvalidTableswitch
+        {
+            offset: u32,
+            low: i32,
+            high: i32,
+        },
+        InvalidLookupswitch
+        {
+            offset: u32,
+        },
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::Cursor(cursor_err) => write!(f, "{cursor_err}"),
+                Error::InvalidOpcode { offset, opcode } => {
+                    write!(f, "invalid opcode {opcode:#04x} at offset {offset}")
+                }
+                Error::InvalidWideOpcode { offset, opcode } => {
+                    write!(
+                        f,
+                        "opcode {opcode:#04x} cannot be widened at offset {offset}"
+                    )
+                }
+                Error::InvalidOperands { offset, opcode } => {
+                    write!(
+                        f,
+                        "invalid operands for opcode {opcode:#04x} at offset {offset}"
+                    )
+                }
+                Error::InvalidTableswitch { offset, low, high } => {
+                    write!(
+                        f,
+                        "tableswitch at offset {offset} has its low {low} above its high {high}"
+                    )
+                }
+                Error::InvalidLookupswitch { offset } => {
+                    write!(
+                        f,
+                        "lookupswitch at offset {offset} has unsorted or negative pairs"
+                    )
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            match self {
+                Error::Cursor(cursor_err) => Some(cursor_err),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<cursor::Error> for Error
+    {
+        fn from(cursor_err: cursor::Error) -> Self
+        {
+            Error::Cursor(cursor_err)
+        }
+    }
+}
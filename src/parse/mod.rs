@@ -1,30 +1,144 @@
-use std::fmt;
-
-mod cursor;
+use std::{borrow::Cow, fmt};
+
+pub mod access;
+pub mod attribute;
+pub mod constant_pool;
+pub mod context;
+pub mod cursor;
+pub mod descriptor;
+mod disassemble;
+pub mod instruction;
+pub mod mutf8;
 pub mod raw;
-
+pub mod signature;
+pub mod version;
+pub mod writer;
+
+use access::{ClassAccess, FieldAccess, InnerClassAccess, MethodAccess};
+use attribute::{
+    parse_signature, Annotation, Attribute, AttributeDecoder, BootstrapMethod, Code, ConstantValue,
+    ElementValue, Frame, LocalVariable, MethodParameter, RecordComponent, TypeAnnotation,
+};
+use constant_pool::{error::ConstantKind, Loadable, MemberRef, ReferenceKind};
+use context::{Located, Segment};
+use descriptor::{FieldType, MethodDescriptor};
 use raw::ClassFile;
+use signature::{ClassSignature, FieldSignature, MethodSignature};
+use version::{SupportedVersions, Version};
 
+/// A parsed class, borrowing its strings and code from the class file bytes
 #[derive(Debug)]
-pub struct Class
+pub struct Class<'a>
 {
     magic: Magic,
     version: Version,
 
-    constant_pool: constant_pool::ConstantPool,
+    constant_pool: constant_pool::ConstantPool<'a>,
+
+    access_flags: ClassAccess,
+    this_class: usize,
+    super_class: Option<usize>,
+
+    interfaces: Box<[usize]>,
+    fields: Box<[Field<'a>]>,
+    methods: Box<[Method<'a>]>,
+    attributes: Box<[Attribute<'a>]>,
+
+    signature: Option<ClassSignature>,
+
+    /// The class file the class was parsed from, kept for writing it again
+    raw: ClassFile<'a>,
 }
 
-impl Class
+impl<'a> Class<'a>
 {
-    pub(crate) fn parse_class_file(class_file: ClassFile) -> error::Result<Self>
+    pub(crate) fn parse_class_file(
+        class_file: ClassFile<'a>,
+        decoders: &[&dyn AttributeDecoder],
+        supported_versions: &SupportedVersions,
+    ) -> error::Result<Self>
     {
+        // Reading the class file checked the magic number already, and the version as well,
+        // but against the versions it was read with, which need not be these
         let magic = Magic(class_file.magic);
 
-        let version = Version(class_file.major, class_file.minor);
+        let version = Version::new(class_file.major, class_file.minor);
+        supported_versions
+            .check(version)
+            .map_err(locate(4, "minor_version"))?;
+
+        // The items following the constant pool are all two bytes long
+        let offset = class_file.access_flags_offset;
+
+        // Which constants the constant pool may hold depends on whether the class is a module
+        let access_flags =
+            ClassAccess::new(class_file.access_flags).map_err(locate(offset, "access_flags"))?;
 
         let constant_pool = constant_pool::ConstantPool::new(
             &class_file.constant_pool,
-            class_file.constant_pool_count as usize,
+            &class_file.constant_offsets,
+            class_file.major,
+            access_flags.is_module(),
+        )?;
+        let this_class = constant_pool
+            .class_index(class_file.this_class)
+            .map_err(locate(offset + 2, "this_class"))?;
+        // Only java/lang/Object has no superclass, in which case the index is 0
+        let super_class = match class_file.super_class {
+            0 => None,
+            super_class => Some(
+                constant_pool
+                    .class_index(super_class)
+                    .map_err(locate(offset + 4, "super_class"))?,
+            ),
+        };
+
+        let interfaces = class_file
+            .interfaces
+            .iter()
+            .enumerate()
+            .map(|(index, interface)| {
+                constant_pool.class_index(*interface).map_err(|err| {
+                    Located::new(err.into(), offset + 8 + 2 * index)
+                        .within(Segment::Interface(index))
+                })
+            })
+            .collect::<error::Result<_>>()?;
+
+        let fields = class_file
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                Field::parse_field_info(field, access_flags, &constant_pool, decoders)
+                    .map_err(|err| err.within(Segment::Field(index)))
+            })
+            .collect::<error::Result<_>>()?;
+
+        let methods = class_file
+            .methods
+            .iter()
+            .enumerate()
+            .map(|(index, method)| {
+                Method::parse_method_info(method, access_flags, &version, &constant_pool, decoders)
+                    .map_err(|err| err.within(Segment::Method(index)))
+            })
+            .collect::<error::Result<_>>()?;
+
+        let attributes =
+            Attribute::parse_attribute_infos(&class_file.attributes, &constant_pool, decoders)?;
+        let signature = parse_signature(
+            &attributes,
+            &class_file.attributes,
+            &constant_pool,
+            ClassSignature::parse,
+            invalid_signature,
+        )?;
+
+        let bootstrap_methods = bootstrap_methods(&attributes);
+        constant_pool.check_bootstrap_method_attr_indices(
+            bootstrap_methods.len(),
+            &class_file.constant_offsets,
         )?;
 
         Ok(Class {
@@ -32,474 +146,887 @@ impl Class
             version,
 
             constant_pool,
+
+            access_flags,
+            this_class,
+            super_class,
+
+            interfaces,
+            fields,
+            methods,
+            attributes,
+
+            signature,
+
+            raw: class_file,
         })
     }
-}
 
-struct Magic(u32);
+    pub fn version(&self) -> Version
+    {
+        self.version
+    }
 
-impl fmt::Debug for Magic
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    /// The binary name of the class, such as `java/lang/String`
+    pub fn name(&self) -> &str
     {
-        write!(f, "{:x?}", self.0)
+        self.constant_pool.class_name_at(self.this_class)
     }
-}
 
-struct Version(u16, u16);
+    /// The binary name of the superclass, which only `java/lang/Object` lacks
+    pub fn super_name(&self) -> Option<&str>
+    {
+        self.super_class
+            .map(|super_class| self.constant_pool.class_name_at(super_class))
+    }
 
-impl fmt::Debug for Version
-{
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    /// The binary names of the interfaces the class implements, or
+    /// those an interface extends, in the order they are declared
+    pub fn interfaces(&self) -> impl ExactSizeIterator<Item = &str>
     {
-        write!(f, "{}.{}", self.0, self.1)
+        self.interfaces
+            .iter()
+            .map(|interface| self.constant_pool.class_name_at(*interface))
     }
-}
 
-mod constant_pool
-{
-    use crate::raw;
+    pub fn fields(&self) -> &[Field<'a>]
+    {
+        &self.fields
+    }
 
-    fn normalise_index(index: &u16) -> usize
+    /// Finds the field with the given name and descriptor, such as `I` for an `int`
+    pub fn field(&self, name: &str, descriptor: &str) -> Option<&Field<'a>>
     {
-        (*index) as usize - 1
+        self.fields.iter().find(|field| {
+            field.name() == name && self.constant_pool.utf8_at(field.descriptor_index) == descriptor
+        })
     }
 
-    #[derive(Debug)]
-    pub(super) struct ConstantPool
+    pub fn methods(&self) -> &[Method<'a>]
     {
-        classes: Box<[Class]>,
-        field_refs: Box<[FieldRef]>,
-        method_refs: Box<[MethodRef]>,
-        strings: Box<[constant::String]>,
-        name_and_types: Box<[NameAndType]>,
-        utf8s: Box<[Utf8]>,
+        &self.methods
     }
 
-    impl ConstantPool
+    /// Finds the method with the given name and descriptor, such
+    /// as `main` and `([Ljava/lang/String;)V` for the main method
+    pub fn method(&self, name: &str, descriptor: &str) -> Option<&Method<'a>>
     {
-        pub(super) fn new(
-            constant_pool: &[raw::Constant],
-            constant_pool_count: usize,
-        ) -> error::Result<Self>
-        {
-            let mut utf8_index_keeper = IndexKeeper::init(constant_pool_count);
-            let utf8s = constant_pool
-                .iter()
-                .enumerate()
-                .filter_map(|(original_index, constant)| {
-                    if let raw::Constant::Utf8 { bytes, .. } = constant {
-                        utf8_index_keeper.keep(original_index);
-
-                        Some(bytes)
-                    } else {
-                        None
-                    }
-                })
-                .cloned()
-                .map(|bytes| {
-                    let bytes = String::from_utf8(bytes.into_vec())
-                        .map(String::into_boxed_str)
-                        .map_err(error::Error::from)?;
+        self.methods.iter().find(|method| {
+            method.name() == name
+                && self.constant_pool.utf8_at(method.descriptor_index) == descriptor
+        })
+    }
 
-                    Ok(Utf8 { bytes })
-                })
-                .collect::<error::Result<_>>()?;
-
-            let mut class_index_keeper = IndexKeeper::init(constant_pool_count);
-            let classes = constant_pool
-                .iter()
-                .enumerate()
-                .filter_map(|(original_index, constant)| {
-                    if let raw::Constant::Class { name_index } = constant {
-                        class_index_keeper.keep(original_index);
-
-                        Some(normalise_index(name_index))
-                    } else {
-                        None
-                    }
-                })
-                .map(|name_index| {
-                    if !(1..constant_pool_count).contains(&name_index) {
-                        Err(error::Error::OutOfRangeIndex(name_index))?
-                    }
-
-                    // This will never fail as we have checked
-                    // that our index is within bounds
-                    let name = constant_pool.get(name_index).unwrap();
-                    if !matches!(name, raw::Constant::Utf8 { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::Utf8,
-                            actual: name.into(),
-                        })?
-                    }
-
-                    let name_index = utf8_index_keeper.fetch(name_index);
-
-                    Ok(Class { name_index })
-                })
-                .collect::<error::Result<_>>()?;
-
-            let strings = constant_pool
-                .iter()
-                .filter_map(|constant| {
-                    if let raw::Constant::String { string_index } = constant {
-                        Some(normalise_index(string_index))
-                    } else {
-                        None
-                    }
-                })
-                .map(|string_index| {
-                    if !(1..constant_pool_count).contains(&string_index) {
-                        Err(error::Error::OutOfRangeIndex(string_index))?
-                    }
-
-                    // This will never fail as we have checked
-                    // that our index is within bounds
-                    let string = constant_pool.get(string_index).unwrap();
-                    if !matches!(string, raw::Constant::Utf8 { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::Utf8,
-                            actual: string.into(),
-                        })?
-                    }
-
-                    let string_index = utf8_index_keeper.fetch(string_index);
-
-                    Ok(constant::String { string_index })
-                })
-                .collect::<error::Result<_>>()?;
-
-            let mut name_and_type_index_keeper = IndexKeeper::init(constant_pool_count);
-            let name_and_types = constant_pool
-                .iter()
-                .enumerate()
-                .filter_map(|(original_index, constant)| {
-                    if let raw::Constant::NameAndType {
-                        name_index,
-                        descriptor_index,
-                    } = constant
-                    {
-                        name_and_type_index_keeper.keep(original_index);
-
-                        Some((
-                            normalise_index(name_index),
-                            normalise_index(descriptor_index),
-                        ))
-                    } else {
-                        None
-                    }
-                })
-                .map(|(name_index, descriptor_index)| {
-                    let bounds = 1..constant_pool_count - 1;
-                    if !bounds.contains(&name_index) {
-                        Err(error::Error::OutOfRangeIndex(name_index))?
-                    }
-                    if !bounds.contains(&descriptor_index) {
-                        Err(error::Error::OutOfRangeIndex(descriptor_index))?
-                    }
-
-                    // These will never fail as we have checked
-                    // that our indices are within bounds
-                    let name = constant_pool.get(name_index).unwrap();
-                    let descriptor = constant_pool.get(descriptor_index).unwrap();
-
-                    if !matches!(name, raw::Constant::Utf8 { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::Utf8,
-                            actual: name.into(),
-                        })?
-                    }
-                    if !matches!(descriptor, raw::Constant::Utf8 { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::Utf8,
-                            actual: descriptor.into(),
-                        })?
-                    }
-
-                    let name_index = utf8_index_keeper.fetch(name_index);
-                    let descriptor_index = utf8_index_keeper.fetch(descriptor_index);
-
-                    Ok(NameAndType {
-                        name_index,
-                        descriptor_index,
-                    })
-                })
-                .collect::<error::Result<_>>()?;
-
-            let field_refs = constant_pool
-                .iter()
-                .filter_map(|constant| {
-                    if let raw::Constant::FieldRef {
-                        class_index,
-                        name_and_type_index,
-                    } = constant
-                    {
-                        Some((
-                            normalise_index(class_index),
-                            normalise_index(name_and_type_index),
-                        ))
-                    } else {
-                        None
-                    }
-                })
-                .map(|(class_index, name_and_type_index)| {
-                    let bounds = 1..constant_pool_count - 1;
-                    if !bounds.contains(&class_index) {
-                        Err(error::Error::OutOfRangeIndex(class_index))?
-                    }
-                    if !bounds.contains(&name_and_type_index) {
-                        Err(error::Error::OutOfRangeIndex(name_and_type_index))?
-                    }
-
-                    // These will never fail as we have checked
-                    // that our indices are within bounds
-                    let class = constant_pool.get(class_index).unwrap();
-                    let name_and_type = constant_pool.get(name_and_type_index).unwrap();
-
-                    if !matches!(class, raw::Constant::Class { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::Class,
-                            actual: class.into(),
-                        })?
-                    }
-                    if !matches!(name_and_type, raw::Constant::NameAndType { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::NameAndType,
-                            actual: name_and_type.into(),
-                        })?
-                    }
-
-                    let class_index = class_index_keeper.fetch(class_index);
-                    let name_and_type_index = name_and_type_index_keeper.fetch(name_and_type_index);
-
-                    Ok(FieldRef {
-                        class_index,
-                        name_and_type_index,
-                    })
-                })
-                .collect::<error::Result<_>>()?;
-
-            let method_refs = constant_pool
-                .iter()
-                .filter_map(|constant| {
-                    if let raw::Constant::MethodRef {
-                        class_index,
-                        name_and_type_index,
-                    } = constant
-                    {
-                        Some((
-                            normalise_index(class_index),
-                            normalise_index(name_and_type_index),
-                        ))
-                    } else {
-                        None
-                    }
-                })
-                .map(|(class_index, name_and_type_index)| {
-                    let bounds = 1..constant_pool_count - 1;
-                    if !bounds.contains(&class_index) {
-                        Err(error::Error::OutOfRangeIndex(class_index))?
-                    }
-                    if !bounds.contains(&name_and_type_index) {
-                        Err(error::Error::OutOfRangeIndex(name_and_type_index))?
-                    }
-
-                    // These will never fail as we have checked
-                    // that our indices are within bounds
-                    let class = constant_pool.get(class_index).unwrap();
-                    let name_and_type = constant_pool.get(name_and_type_index).unwrap();
-
-                    if !matches!(class, raw::Constant::Class { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::Class,
-                            actual: class.into(),
-                        })?
-                    }
-                    if !matches!(name_and_type, raw::Constant::NameAndType { .. }) {
-                        Err(error::Error::UnexpectedConstantKind {
-                            expected: error::ConstantKind::NameAndType,
-                            actual: name_and_type.into(),
-                        })?
-                    }
-
-                    let class_index = class_index_keeper.fetch(class_index);
-                    let name_and_type_index = name_and_type_index_keeper.fetch(name_and_type_index);
-
-                    Ok(MethodRef {
-                        class_index,
-                        name_and_type_index,
-                    })
+    /// The types of the locals and the stack at each offset a method's stack map table gives,
+    /// or `None` if the method has no code or its code has no stack map table
+    pub fn stack_map_frames(
+        &self,
+        method: &Method<'a>,
+    ) -> Option<Result<Box<[Frame<'a>]>, attribute::error::Error>>
+    {
+        let stack_map_table = method.code()?.stack_map_table()?;
+        let initial = Frame::initial(
+            self.constant_pool.class_name_cow_at(self.this_class),
+            method.name(),
+            method.descriptor(),
+            method.access_flags().is_static(),
+        );
+
+        Some(stack_map_table.expand(&initial))
+    }
+
+    pub fn access_flags(&self) -> ClassAccess
+    {
+        self.access_flags
+    }
+
+    pub fn constant_pool(&self) -> &constant_pool::ConstantPool<'a>
+    {
+        &self.constant_pool
+    }
+
+    pub fn attributes(&self) -> &[Attribute<'a>]
+    {
+        &self.attributes
+    }
+
+    /// The name of the source file the class was compiled from, without any directories
+    pub fn source_file(&self) -> Option<&str>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::SourceFile { sourcefile_index } = attribute {
+                Some(
+                    self.constant_pool
+                        .utf8_at(self.constant_pool.checked_index(*sourcefile_index)),
+                )
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The entries of the BootstrapMethods attribute, which
+    /// InvokeDynamic and Dynamic constants refer to by index
+    pub fn bootstrap_methods(&self) -> &[BootstrapMethod]
+    {
+        bootstrap_methods(&self.attributes)
+    }
+
+    /// Links the InvokeDynamic or Dynamic constant at `index` to its bootstrap
+    /// method, resolving the method handle and the static arguments
+    pub fn call_site(&self, index: u16) -> constant_pool::error::Result<CallSite<'_>>
+    {
+        let (bootstrap_method_attr_index, name, descriptor) =
+            match self.constant_pool.kind_of(index)? {
+                (ConstantKind::Dynamic, _) => self.constant_pool.dynamic(index)?,
+                (ConstantKind::InvokeDynamic, _) => self.constant_pool.invoke_dynamic(index)?,
+                (actual, _) => Err(constant_pool::error::Error::UnexpectedConstantKind {
+                    expected: ConstantKind::InvokeDynamic,
+                    actual,
+                })?,
+            };
+
+        // Parsing made sure the index is within the BootstrapMethods attribute
+        // and that the bootstrap method refers to loadable constants
+        let bootstrap_method = &self.bootstrap_methods()[bootstrap_method_attr_index];
+        let (reference_kind, method) = self.constant_pool.method_handle_at(
+            self.constant_pool
+                .checked_index(bootstrap_method.bootstrap_method_ref()),
+        );
+        let arguments = bootstrap_method
+            .bootstrap_arguments()
+            .iter()
+            .map(|&argument| self.constant_pool.loadable(argument))
+            .collect::<constant_pool::error::Result<_>>()?;
+
+        Ok(CallSite {
+            name,
+            descriptor,
+            bootstrap_method_attr_index,
+            reference_kind,
+            method,
+            arguments,
+        })
+    }
+
+    /// The classes that are not members of a package which the class refers to or
+    /// declares, with the classes they are members of and their simple names
+    pub fn inner_classes(&self) -> impl ExactSizeIterator<Item = InnerClassRef<'_>>
+    {
+        let classes = self
+            .attributes
+            .iter()
+            .find_map(|attribute| {
+                if let Attribute::InnerClasses { classes } = attribute {
+                    Some(&**classes)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        let constant_pool = &self.constant_pool;
+        let class_name_at = |index| constant_pool.class_name_at(constant_pool.checked_index(index));
+
+        classes.iter().map(move |inner_class| InnerClassRef {
+            class_name: class_name_at(inner_class.inner_class_info_index()),
+            outer_class_name: inner_class.outer_class_info_index().map(class_name_at),
+            simple_name: inner_class
+                .inner_name_index()
+                .map(|index| constant_pool.utf8_at(constant_pool.checked_index(index))),
+            access_flags: inner_class.inner_class_access_flags(),
+        })
+    }
+
+    /// The binary name of the class the class is declared in, which is the class it
+    /// is a member of or the one enclosing it if it is a local or anonymous class
+    pub fn outer_class(&self) -> Option<&str>
+    {
+        let name = self.name();
+
+        self.inner_classes()
+            .find(|inner_class| inner_class.class_name == name)
+            .and_then(|inner_class| inner_class.outer_class_name)
+            .or_else(|| self.enclosing_class())
+    }
+
+    /// The class a local or anonymous class is declared in
+    fn enclosing_class(&self) -> Option<&str>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::EnclosingMethod { class_index, .. } = attribute {
+                Some(
+                    self.constant_pool
+                        .class_name_at(self.constant_pool.checked_index(*class_index)),
+                )
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The method a local or anonymous class is declared in, which those
+    /// declared in initializers or the bodies of fields lack
+    pub fn enclosing_method(&self) -> Option<MemberRef<'_>>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::EnclosingMethod {
+                class_index,
+                method_index: Some(method_index),
+            } = attribute
+            {
+                let (name, descriptor) = self
+                    .constant_pool
+                    .name_and_type_at(self.constant_pool.checked_index(*method_index));
+
+                Some(MemberRef {
+                    class_name: self
+                        .constant_pool
+                        .class_name_at(self.constant_pool.checked_index(*class_index)),
+                    name,
+                    descriptor,
                 })
-                .collect::<error::Result<_>>()?;
-
-            Ok(ConstantPool {
-                classes,
-                field_refs,
-                method_refs,
-                strings,
-                name_and_types,
-                utf8s,
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The binary name of the class at the top of the nest the class is a member of,
+    /// which nested classes compiled for Java 11 and later have
+    pub fn nest_host(&self) -> Option<&str>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::NestHost { host_class_index } = attribute {
+                Some(
+                    self.constant_pool
+                        .class_name_at(self.constant_pool.checked_index(*host_class_index)),
+                )
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The binary names of the other members of the nest the class is the host of
+    pub fn nest_members(&self) -> impl ExactSizeIterator<Item = &str>
+    {
+        let class_indices = self
+            .attributes
+            .iter()
+            .find_map(|attribute| {
+                if let Attribute::NestMembers { class_indices } = attribute {
+                    Some(&**class_indices)
+                } else {
+                    None
+                }
             })
-        }
+            .unwrap_or_default();
+
+        class_indices.iter().map(|class_index| {
+            self.constant_pool
+                .class_name_at(self.constant_pool.checked_index(*class_index))
+        })
     }
 
-    #[derive(Debug)]
-    pub(super) struct Class
+    pub fn is_record(&self) -> bool
     {
-        pub(super) name_index: usize,
+        self.attributes
+            .iter()
+            .any(|attribute| matches!(attribute, Attribute::Record { .. }))
     }
 
-    #[derive(Debug)]
-    pub(super) struct FieldRef
+    /// The components of a record class in the order they are declared,
+    /// which is none for classes other than records
+    pub fn record_components(&self) -> &[RecordComponent<'a>]
     {
-        pub(super) class_index: usize,
-        pub(super) name_and_type_index: usize,
+        self.attributes
+            .iter()
+            .find_map(|attribute| {
+                if let Attribute::Record { components } = attribute {
+                    Some(&**components)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default()
     }
 
-    #[derive(Debug)]
-    pub(super) struct MethodRef
+    /// Whether only the permitted subclasses may extend or implement the class
+    pub fn is_sealed(&self) -> bool
     {
-        pub(super) class_index: usize,
-        pub(super) name_and_type_index: usize,
+        self.attributes
+            .iter()
+            .any(|attribute| matches!(attribute, Attribute::PermittedSubclasses { .. }))
     }
 
-    pub(super) mod constant
+    /// The binary names of the classes that may directly extend
+    /// or implement a sealed class, which is none for the others
+    pub fn permitted_subclasses(&self) -> impl ExactSizeIterator<Item = &str>
     {
-        #[derive(Debug)]
-        pub(super) struct String
-        {
-            pub(super) string_index: usize,
-        }
+        let class_indices = self
+            .attributes
+            .iter()
+            .find_map(|attribute| {
+                if let Attribute::PermittedSubclasses { class_indices } = attribute {
+                    Some(&**class_indices)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        class_indices.iter().map(|class_index| {
+            self.constant_pool
+                .class_name_at(self.constant_pool.checked_index(*class_index))
+        })
     }
 
-    #[derive(Debug)]
-    pub(super) struct NameAndType
+    /// Whether the class has the Deprecated attribute or the `@Deprecated` annotation
+    pub fn is_deprecated(&self) -> bool
     {
-        pub(super) name_index: usize,
-        pub(super) descriptor_index: usize,
+        is_deprecated(&self.attributes)
     }
 
-    #[derive(Debug)]
-    pub(super) struct Utf8
+    /// Whether the class does not appear in the source code, through either
+    /// its access flags or the Synthetic attribute older compilers use
+    pub fn is_synthetic(&self) -> bool
     {
-        pub(super) bytes: Box<str>,
+        self.access_flags.is_synthetic() || is_synthetic(&self.attributes)
     }
 
-    #[derive(Debug)]
-    struct IndexKeeper
+    pub fn source_debug_extension(&self) -> Option<&str>
     {
-        indices: Vec<usize>,
-        count: usize,
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::SourceDebugExtension { debug_extension } = attribute {
+                Some(debug_extension.as_ref())
+            } else {
+                None
+            }
+        })
     }
 
-    impl IndexKeeper
+    /// The annotations on the class, the runtime visible ones followed by the invisible ones
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation<'a>>
     {
-        fn init(len: usize) -> Self
-        {
-            let indices = vec![0; len];
-            let count = 0;
+        annotations(&self.attributes)
+    }
 
-            IndexKeeper { indices, count }
-        }
+    /// The annotation of the given type, such as `Ljavax/persistence/Entity;`, if the class has it
+    pub fn annotation(&self, type_descriptor: &str) -> Option<&Annotation<'a>>
+    {
+        self.annotations()
+            .find(|annotation| annotation.type_descriptor() == type_descriptor)
+    }
 
-        fn keep(&mut self, original_index: usize)
-        {
-            self.indices[original_index] = self.count;
-            self.count += 1;
-        }
+    /// The annotations on types used in the declaration of the class, the
+    /// runtime visible ones followed by the invisible ones
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation<'a>>
+    {
+        type_annotations(&self.attributes)
+    }
 
-        fn fetch(&self, original_index: usize) -> usize
-        {
-            self.indices[original_index]
-        }
+    /// The generic signature of the class, if it has any type parameters
+    /// or extends or implements a parameterized type
+    pub fn signature(&self) -> Option<&ClassSignature>
+    {
+        self.signature.as_ref()
     }
 
-    pub mod error
+    /// The class file the class was parsed from
+    pub fn raw(&self) -> &ClassFile<'a>
     {
-        use std::{error, fmt, result, str, string};
+        &self.raw
+    }
 
-        use crate::raw;
+    /// Gives up the parsed class for the class file it was parsed from, such as to patch it
+    pub fn into_raw(self) -> ClassFile<'a>
+    {
+        self.raw
+    }
 
-        pub(super) type Result<T> = result::Result<T, Error>;
+    /// Writes the class file the class was parsed from, reproducing its bytes
+    pub fn to_bytes(&self) -> writer::Result<Vec<u8>>
+    {
+        self.raw.to_bytes()
+    }
+}
 
-        #[derive(Debug)]
-        pub enum ConstantKind
-        {
-            Class,
-            FieldRef,
-            MethodRef,
-            String,
-            NameAndType,
-            Utf8,
-        }
+/// An invokedynamic call site or a dynamically-computed constant,
+/// linked to the bootstrap method that produces it
+#[derive(Debug, Clone)]
+pub struct CallSite<'a>
+{
+    pub name: &'a str,
+    /// The method descriptor of a call site, or the field descriptor of a constant
+    pub descriptor: &'a str,
+    /// The index of the bootstrap method within the BootstrapMethods attribute
+    pub bootstrap_method_attr_index: usize,
+    /// How the method handle of the bootstrap method calls it, usually `REF_invokeStatic`
+    pub reference_kind: ReferenceKind,
+    pub method: MemberRef<'a>,
+    /// The static arguments the bootstrap method is called with
+    pub arguments: Box<[Loadable<'a>]>,
+}
 
-        impl From<&raw::Constant> for ConstantKind
-        {
-            fn from(value: &raw::Constant) -> Self
-            {
-                match value {
-                    raw::Constant::Class { .. } => ConstantKind::Class,
-                    raw::Constant::FieldRef { .. } => ConstantKind::FieldRef,
-                    raw::Constant::MethodRef { .. } => ConstantKind::MethodRef,
-                    raw::Constant::String { .. } => ConstantKind::String,
-                    raw::Constant::NameAndType { .. } => ConstantKind::NameAndType,
-                    raw::Constant::Utf8 { .. } => ConstantKind::Utf8,
-                }
+/// An entry of the InnerClasses attribute with its constants resolved
+#[derive(Debug, Clone, Copy)]
+pub struct InnerClassRef<'a>
+{
+    pub class_name: &'a str,
+    /// The class the class is a member of, which local and anonymous classes lack
+    pub outer_class_name: Option<&'a str>,
+    /// The name of the class in the source code, which anonymous classes lack
+    pub simple_name: Option<&'a str>,
+    pub access_flags: InnerClassAccess,
+}
+
+#[derive(Debug)]
+pub struct Field<'a>
+{
+    access_flags: FieldAccess,
+    name_index: usize,
+    name: Cow<'a, str>,
+    descriptor_index: usize,
+    descriptor: FieldType,
+
+    attributes: Box<[Attribute<'a>]>,
+
+    signature: Option<FieldSignature>,
+}
+
+impl<'a> Field<'a>
+{
+    fn parse_field_info(
+        field_info: &raw::FieldInfo<'a>,
+        class_access: ClassAccess,
+        constant_pool: &constant_pool::ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> error::Result<Self>
+    {
+        let offset = field_info.offset;
+
+        let access_flags = FieldAccess::new(field_info.access_flags, class_access)
+            .map_err(locate(offset, "access_flags"))?;
+        let name_index = constant_pool
+            .utf8_index(field_info.name_index)
+            .map_err(locate(offset + 2, "name_index"))?;
+        let descriptor_index = constant_pool
+            .utf8_index(field_info.descriptor_index)
+            .map_err(locate(offset + 4, "descriptor_index"))?;
+        let descriptor = constant_pool
+            .field_descriptor_at(descriptor_index)
+            .map_err(locate(offset + 4, "descriptor_index"))?;
+
+        let attributes =
+            Attribute::parse_attribute_infos(&field_info.attributes, constant_pool, decoders)?;
+        let signature = parse_signature(
+            &attributes,
+            &field_info.attributes,
+            constant_pool,
+            FieldSignature::parse,
+            invalid_signature,
+        )?;
+
+        Ok(Field {
+            access_flags,
+            name_index,
+            name: constant_pool.utf8_cow_at(name_index),
+            descriptor_index,
+            descriptor,
+
+            attributes,
+
+            signature,
+        })
+    }
+
+    pub fn access_flags(&self) -> FieldAccess
+    {
+        self.access_flags
+    }
+
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    pub fn descriptor(&self) -> &FieldType
+    {
+        &self.descriptor
+    }
+
+    pub fn attributes(&self) -> &[Attribute<'a>]
+    {
+        &self.attributes
+    }
+
+    /// The generic type of the field, if it involves type variables or parameterized types
+    pub fn signature(&self) -> Option<&FieldSignature>
+    {
+        self.signature.as_ref()
+    }
+
+    /// The annotations on the field, the runtime visible ones followed by the invisible ones
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation<'a>>
+    {
+        annotations(&self.attributes)
+    }
+
+    /// The annotation of the given type, such as `Ljavax/persistence/Id;`, if the field has it
+    pub fn annotation(&self, type_descriptor: &str) -> Option<&Annotation<'a>>
+    {
+        self.annotations()
+            .find(|annotation| annotation.type_descriptor() == type_descriptor)
+    }
+
+    /// The annotations on types used in the type of the field, the
+    /// runtime visible ones followed by the invisible ones
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation<'a>>
+    {
+        type_annotations(&self.attributes)
+    }
+
+    /// Whether the field has the Deprecated attribute or the `@Deprecated` annotation
+    pub fn is_deprecated(&self) -> bool
+    {
+        is_deprecated(&self.attributes)
+    }
+
+    /// Whether the field does not appear in the source code, through either
+    /// its access flags or the Synthetic attribute older compilers use
+    pub fn is_synthetic(&self) -> bool
+    {
+        self.access_flags.is_synthetic() || is_synthetic(&self.attributes)
+    }
+
+    /// The value a static field is initialized to before the class initializer runs
+    pub fn constant_value(&self) -> Option<&ConstantValue<'a>>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::ConstantValue(value) = attribute {
+                Some(value)
+            } else {
+                None
             }
-        }
+        })
+    }
+}
 
-        #[derive(Debug)]
-        pub enum Error
-        {
-            OutOfRangeIndex(usize),
-            UnexpectedConstantKind
-            {
-                expected: ConstantKind,
-                actual: ConstantKind,
-            },
-            Utf8(str::Utf8Error),
-        }
+#[derive(Debug)]
+pub struct Method<'a>
+{
+    access_flags: MethodAccess,
+    name_index: usize,
+    name: Cow<'a, str>,
+    descriptor_index: usize,
+    descriptor: MethodDescriptor,
 
-        impl fmt::Display for Error
-        {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    attributes: Box<[Attribute<'a>]>,
+
+    signature: Option<MethodSignature>,
+}
+
+impl<'a> Method<'a>
+{
+    fn parse_method_info(
+        method_info: &raw::MethodInfo<'a>,
+        class_access: ClassAccess,
+        version: &Version,
+        constant_pool: &constant_pool::ConstantPool<'a>,
+        decoders: &[&dyn AttributeDecoder],
+    ) -> error::Result<Self>
+    {
+        let offset = method_info.offset;
+
+        let name_index = constant_pool
+            .utf8_index(method_info.name_index)
+            .map_err(locate(offset + 2, "name_index"))?;
+        let access_flags = MethodAccess::new(
+            method_info.access_flags,
+            class_access,
+            constant_pool.utf8_at(name_index),
+            version.major(),
+        )
+        .map_err(locate(offset, "access_flags"))?;
+        let descriptor_index = constant_pool
+            .utf8_index(method_info.descriptor_index)
+            .map_err(locate(offset + 4, "descriptor_index"))?;
+        let descriptor = constant_pool
+            .method_descriptor_at(descriptor_index)
+            .map_err(locate(offset + 4, "descriptor_index"))?;
+
+        let attributes =
+            Attribute::parse_attribute_infos(&method_info.attributes, constant_pool, decoders)?;
+        let signature = parse_signature(
+            &attributes,
+            &method_info.attributes,
+            constant_pool,
+            MethodSignature::parse,
+            invalid_signature,
+        )?;
+
+        Ok(Method {
+            access_flags,
+            name_index,
+            name: constant_pool.utf8_cow_at(name_index),
+            descriptor_index,
+            descriptor,
+
+            attributes,
+
+            signature,
+        })
+    }
+
+    pub fn access_flags(&self) -> MethodAccess
+    {
+        self.access_flags
+    }
+
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    pub fn descriptor(&self) -> &MethodDescriptor
+    {
+        &self.descriptor
+    }
+
+    /// The method's body, which only abstract and native methods lack
+    pub fn code(&self) -> Option<&Code<'a>>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::Code(code) = attribute {
+                Some(code)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn attributes(&self) -> &[Attribute<'a>]
+    {
+        &self.attributes
+    }
+
+    /// The line of source code the instruction at `pc` was compiled from,
+    /// if the method has code with line numbers that cover it
+    pub fn line_for_pc(&self, pc: u16) -> Option<u16>
+    {
+        self.code()?.line_for_pc(pc)
+    }
+
+    /// The local variables that have a value at `pc`, which
+    /// is none for methods without a LocalVariableTable
+    pub fn locals_at(&self, pc: u16) -> impl Iterator<Item = &LocalVariable<'a>>
+    {
+        self.code()
+            .into_iter()
+            .flat_map(move |code| code.locals_at(pc))
+    }
+
+    /// The annotations on the method, the runtime visible ones followed by the invisible ones
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation<'a>>
+    {
+        annotations(&self.attributes)
+    }
+
+    /// The annotation of the given type, such as `Ljava/lang/Deprecated;`, if the method has it
+    pub fn annotation(&self, type_descriptor: &str) -> Option<&Annotation<'a>>
+    {
+        self.annotations()
+            .find(|annotation| annotation.type_descriptor() == type_descriptor)
+    }
+
+    /// The annotations on types used in the signature of the method, the
+    /// runtime visible ones followed by the invisible ones
+    pub fn type_annotations(&self) -> impl Iterator<Item = &TypeAnnotation<'a>>
+    {
+        type_annotations(&self.attributes)
+    }
+
+    /// The annotations on each parameter, the runtime visible ones followed by the invisible ones
+    ///
+    /// Compilers may leave out parameters the source code does not declare, such as
+    /// those of the constructors of inner classes, so the indices may be off
+    pub fn parameter_annotations(&self) -> Box<[Vec<&Annotation<'a>>]>
+    {
+        let mut parameters: Vec<Vec<_>> = Vec::new();
+
+        for attribute in self.attributes.iter() {
+            if let Attribute::RuntimeVisibleParameterAnnotations {
+                parameter_annotations,
+            }
+            | Attribute::RuntimeInvisibleParameterAnnotations {
+                parameter_annotations,
+            } = attribute
             {
-                match self {
-                    Error::OutOfRangeIndex(index) => {
-                        write!(f, "out of range constant pool index {index}")
-                    }
-                    Error::UnexpectedConstantKind { expected, actual } => {
-                        write!(
-                            f,
-                            "expected {} constant, but instead got a {} constant",
-                            format!("{expected:?}").to_ascii_lowercase(),
-                            format!("{actual:?}").to_ascii_lowercase()
-                        )
-                    }
-                    Error::Utf8(utf8_err) => write!(f, "{utf8_err}"),
+                if parameters.len() < parameter_annotations.len() {
+                    parameters.resize_with(parameter_annotations.len(), Vec::new);
+                }
+                for (parameter, annotations) in
+                    parameters.iter_mut().zip(parameter_annotations.iter())
+                {
+                    parameter.extend(annotations.iter());
                 }
             }
         }
 
-        impl error::Error for Error
-        {
-            fn source(&self) -> Option<&(dyn error::Error + 'static)>
-            {
-                match self {
-                    Error::Utf8(utf8_err) => Some(utf8_err),
-                    _ => None,
+        parameters.into()
+    }
+
+    /// The names and flags of the parameters, which compilers only
+    /// give when asked to, such as by `javac -parameters`
+    pub fn parameters(&self) -> &[MethodParameter<'a>]
+    {
+        self.attributes
+            .iter()
+            .find_map(|attribute| {
+                if let Attribute::MethodParameters { parameters } = attribute {
+                    Some(&**parameters)
+                } else {
+                    None
                 }
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether the method has the Deprecated attribute or the `@Deprecated` annotation
+    pub fn is_deprecated(&self) -> bool
+    {
+        is_deprecated(&self.attributes)
+    }
+
+    /// Whether the method does not appear in the source code, through either
+    /// its access flags or the Synthetic attribute older compilers use
+    pub fn is_synthetic(&self) -> bool
+    {
+        self.access_flags.is_synthetic() || is_synthetic(&self.attributes)
+    }
+
+    /// The value an element of an annotation interface takes when an annotation leaves it out
+    pub fn annotation_default(&self) -> Option<&ElementValue<'a>>
+    {
+        self.attributes.iter().find_map(|attribute| {
+            if let Attribute::AnnotationDefault { default_value } = attribute {
+                Some(default_value)
+            } else {
+                None
             }
-        }
+        })
+    }
 
-        impl From<string::FromUtf8Error> for Error
-        {
-            fn from(utf8_err: string::FromUtf8Error) -> Self
-            {
-                Error::Utf8(utf8_err.utf8_error())
+    /// The generic signature of the method, if it is generic or
+    /// its parameters, return type or exceptions involve generic types
+    pub fn signature(&self) -> Option<&MethodSignature>
+    {
+        self.signature.as_ref()
+    }
+}
+
+fn bootstrap_methods<'b>(attributes: &'b [Attribute<'_>]) -> &'b [BootstrapMethod]
+{
+    attributes
+        .iter()
+        .find_map(|attribute| {
+            if let Attribute::BootstrapMethods { bootstrap_methods } = attribute {
+                Some(&**bootstrap_methods)
+            } else {
+                None
             }
+        })
+        .unwrap_or_default()
+}
+
+fn is_deprecated(attributes: &[Attribute<'_>]) -> bool
+{
+    attributes
+        .iter()
+        .any(|attribute| matches!(attribute, Attribute::Deprecated))
+        || annotations(attributes)
+            .any(|annotation| annotation.type_descriptor() == "Ljava/lang/Deprecated;")
+}
+
+fn is_synthetic(attributes: &[Attribute<'_>]) -> bool
+{
+    attributes
+        .iter()
+        .any(|attribute| matches!(attribute, Attribute::Synthetic))
+}
+
+/// The annotations of the RuntimeVisibleAnnotations and RuntimeInvisibleAnnotations attributes
+fn annotations<'b, 'a>(attributes: &'b [Attribute<'a>])
+    -> impl Iterator<Item = &'b Annotation<'a>>
+{
+    let visible = attributes.iter().filter_map(|attribute| {
+        if let Attribute::RuntimeVisibleAnnotations { annotations } = attribute {
+            Some(annotations.iter())
+        } else {
+            None
+        }
+    });
+    let invisible = attributes.iter().filter_map(|attribute| {
+        if let Attribute::RuntimeInvisibleAnnotations { annotations } = attribute {
+            Some(annotations.iter())
+        } else {
+            None
+        }
+    });
+
+    visible.chain(invisible).flatten()
+}
+
+/// The type annotations of the Runtime(In)VisibleTypeAnnotations attributes
+fn type_annotations<'b, 'a>(
+    attributes: &'b [Attribute<'a>],
+) -> impl Iterator<Item = &'b TypeAnnotation<'a>>
+{
+    let visible = attributes.iter().filter_map(|attribute| {
+        if let Attribute::RuntimeVisibleTypeAnnotations { type_annotations } = attribute {
+            Some(type_annotations.iter())
+        } else {
+            None
         }
+    });
+    let invisible = attributes.iter().filter_map(|attribute| {
+        if let Attribute::RuntimeInvisibleTypeAnnotations { type_annotations } = attribute {
+            Some(type_annotations.iter())
+        } else {
+            None
+        }
+    });
+
+    visible.chain(invisible).flatten()
+}
+
+/// Attaches the offset and name of the item an error was found in
+fn locate<E>(offset: usize, item: &'static str) -> impl FnOnce(E) -> Located<error::Error>
+where
+    E: Into<error::Error>,
+{
+    move |err| Located::new(err.into(), offset).within(Segment::Item(item))
+}
+
+/// The error of a Signature attribute whose signature `parse` rejected
+fn invalid_signature(signature: Box<str>, source: descriptor::error::Error) -> error::Error
+{
+    error::Error::InvalidSignature { signature, source }
+}
+
+struct Magic(u32);
+
+impl Magic
+{
+    pub(super) const CLASS_FILE: Magic = Magic(0xcafebabe);
+}
+
+impl fmt::Debug for Magic
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{:x?}", self.0)
     }
 }
 
@@ -507,14 +1034,22 @@ pub mod error
 {
     use std::{error, fmt, result};
 
-    use crate::parse::constant_pool;
+    use crate::parse::{access, attribute, constant_pool, context::Located, descriptor, version};
 
-    pub type Result<T> = result::Result<T, Error>;
+    pub type Result<T> = result::Result<T, Located<Error>>;
 
     #[derive(Debug)]
     pub enum Error
     {
+        Version(version::error::Error),
         ConstantPool(constant_pool::error::Error),
+        Access(access::error::Error),
+        Attribute(attribute::error::Error),
+        InvalidSignature
+        {
+            signature: Box<str>,
+            source: descriptor::error::Error,
+        },
     }
 
     impl fmt::Display for Error
@@ -522,9 +1057,15 @@ pub mod error
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
         {
             match self {
+                Error::Version(version_err) => write!(f, "{version_err}"),
                 Error::ConstantPool(constant_pool_err) => {
                     write!(f, "{constant_pool_err}")
                 }
+                Error::Access(access_err) => write!(f, "{access_err}"),
+                Error::Attribute(attribute_err) => write!(f, "{attribute_err}"),
+                Error::InvalidSignature { signature, source } => {
+                    write!(f, "invalid signature {signature:?}: {source}")
+                }
             }
         }
     }
@@ -534,13 +1075,23 @@ pub mod error
         fn source(&self) -> Option<&(dyn error::Error + 'static)>
         {
             match self {
+                Error::Version(version_err) => Some(version_err),
                 Error::ConstantPool(constant_pool_err) => Some(constant_pool_err),
-                #[allow(unreachable_patterns)]
-                _ => None,
+                Error::Access(access_err) => Some(access_err),
+                Error::Attribute(attribute_err) => Some(attribute_err),
+                Error::InvalidSignature { source, .. } => Some(source),
             }
         }
     }
 
+    impl From<version::error::Error> for Error
+    {
+        fn from(version_err: version::error::Error) -> Self
+        {
+            Error::Version(version_err)
+        }
+    }
+
     impl From<constant_pool::error::Error> for Error
     {
         fn from(constant_pool_err: constant_pool::error::Error) -> Self
@@ -548,4 +1099,28 @@ pub mod error
             Error::ConstantPool(constant_pool_err)
         }
     }
+
+    impl From<access::error::Error> for Error
+    {
+        fn from(access_err: access::error::Error) -> Self
+        {
+            Error::Access(access_err)
+        }
+    }
+
+    impl From<Located<constant_pool::error::Error>> for Located<Error>
+    {
+        fn from(constant_pool_err: Located<constant_pool::error::Error>) -> Self
+        {
+            constant_pool_err.map(Error::ConstantPool)
+        }
+    }
+
+    impl From<Located<attribute::error::Error>> for Located<Error>
+    {
+        fn from(attribute_err: Located<attribute::error::Error>) -> Self
+        {
+            attribute_err.map(Error::Attribute)
+        }
+    }
 }
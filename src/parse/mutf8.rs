@@ -0,0 +1,197 @@
+//! The modified UTF-8 encoding used by `CONSTANT_Utf8` entries
+//!
+//! It differs from standard UTF-8 in two ways: NUL is encoded with two
+//! bytes as `C0 80`, so that strings never contain a zero byte, and
+//! supplementary characters are encoded as a surrogate pair of three byte
+//! sequences rather than as a single four byte sequence
+//!
+//! Like Java strings, the encoded strings may also contain unpaired surrogates,
+//! which have no place in a `str`, so those strings are only decoded exactly
+//! by [`decode_utf16`]
+
+use std::{borrow::Cow, str};
+
+use self::error::{Error, Result};
+
+/// Decodes modified UTF-8 bytes into a string, borrowing them
+/// when they are valid standard UTF-8 as well, which is the common case
+pub fn decode(bytes: &[u8]) -> Result<Cow<'_, str>>
+{
+    // Standard UTF-8 differs by allowing zero bytes and four byte sequences,
+    // while rejecting the two byte NUL and the surrogates that need decoding
+    if !bytes.iter().any(|byte| *byte == 0 || *byte >= 0xf0) {
+        if let Ok(string) = str::from_utf8(bytes) {
+            return Ok(Cow::Borrowed(string));
+        }
+    }
+
+    decode_owned(bytes).map(Cow::Owned)
+}
+
+fn decode_owned(bytes: &[u8]) -> Result<String>
+{
+    let units = decode_units(bytes)?;
+
+    // Supplementary characters are made of a high and a low surrogate
+    let mut string = String::with_capacity(units.len());
+    let mut rest = units.iter();
+    while let Some(&(unit, position)) = rest.next() {
+        let c = match unit {
+            0xd800..=0xdbff => match rest.as_slice().first() {
+                Some(&(low @ 0xdc00..=0xdfff, _)) => {
+                    rest.next();
+
+                    let high = u32::from(unit - 0xd800) << 10;
+                    char::from_u32(0x10000 + high + u32::from(low - 0xdc00))
+                }
+                _ => None,
+            },
+            unit => char::from_u32(unit.into()),
+        };
+
+        string.push(c.ok_or(Error::UnpairedSurrogate { position })?);
+    }
+
+    Ok(string)
+}
+
+/// Decodes modified UTF-8 bytes into the UTF-16 code units of a Java string,
+/// which unlike [`decode`] accepts unpaired surrogates
+pub fn decode_utf16(bytes: &[u8]) -> Result<Box<[u16]>>
+{
+    let units = decode_units(bytes)?;
+
+    Ok(units.into_iter().map(|(unit, _)| unit).collect())
+}
+
+/// Decodes the UTF-16 code units along with the positions of their sequences
+fn decode_units(bytes: &[u8]) -> Result<Vec<(u16, usize)>>
+{
+    let mut units = Vec::with_capacity(bytes.len());
+
+    let mut position = 0;
+    while position < bytes.len() {
+        let continuation = |offset: usize| {
+            let byte = *bytes
+                .get(position + offset)
+                .ok_or(Error::Truncated { position })?;
+            if byte & 0xc0 != 0x80 {
+                Err(Error::InvalidByte {
+                    byte,
+                    position: position + offset,
+                })?
+            }
+
+            Ok((byte & 0x3f) as u16)
+        };
+
+        let byte = bytes[position];
+        let (unit, length) = match byte {
+            0x01..=0x7f => (byte as u16, 1),
+            0xc0..=0xdf => (((byte & 0x1f) as u16) << 6 | continuation(1)?, 2),
+            0xe0..=0xef => (
+                ((byte & 0x0f) as u16) << 12 | continuation(1)? << 6 | continuation(2)?,
+                3,
+            ),
+            // Zero bytes never appear, and neither do the
+            // lead bytes of four byte sequences
+            byte => Err(Error::InvalidByte { byte, position })?,
+        };
+
+        units.push((unit, position));
+        position += length;
+    }
+
+    Ok(units)
+}
+
+/// Encodes a string as modified UTF-8
+pub fn encode(string: &str) -> Box<[u8]>
+{
+    encode_units(string.encode_utf16(), string.len())
+}
+
+/// Encodes the UTF-16 code units of a Java string as
+/// modified UTF-8, unpaired surrogates included
+pub fn encode_utf16(units: &[u16]) -> Box<[u8]>
+{
+    encode_units(units.iter().copied(), units.len())
+}
+
+fn encode_units(units: impl Iterator<Item = u16>, capacity: usize) -> Box<[u8]>
+{
+    let mut bytes = Vec::with_capacity(capacity);
+
+    for unit in units {
+        match unit {
+            0x0001..=0x007f => bytes.push(unit as u8),
+            0x0000 | 0x0080..=0x07ff => {
+                bytes.push(0xc0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+            0x0800..=0xffff => {
+                bytes.push(0xe0 | (unit >> 12) as u8);
+                bytes.push(0x80 | (unit >> 6 & 0x3f) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+        }
+    }
+
+    bytes.into()
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        InvalidByte
+        {
+            byte: u8, position: usize
+        },
+        Truncated
+        {
+            position: usize
+        },
+        UnpairedSurrogate
+        {
+            position: usize
+        },
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::InvalidByte { byte, position } => {
+                    write!(
+                        f,
+                        "invalid modified utf-8 byte {byte:#04x} at position {position}"
+                    )
+                }
+                Error::Truncated { position } => {
+                    write!(
+                        f,
+                        "truncated modified utf-8 sequence at position {position}"
+                    )
+                }
+                Error::UnpairedSurrogate { position } => {
+                    write!(f, "unpaired surrogate at position {position}")
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            None
+        }
+    }
+}
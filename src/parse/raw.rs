@@ -1,156 +1,536 @@
-use crate::parse::cursor::Cursor;
+use std::mem;
+
+use crate::parse::{
+    context::{Located, Segment},
+    cursor::Cursor,
+    version::{SupportedVersions, Version},
+    writer::{self, Writer},
+    Magic,
+};
 
 use self::error::{Error, Result};
 
+/// A class file as laid out in its bytes, borrowing the variable length parts from them
+///
+/// The counts and lengths preceding each table are not kept, but written from the
+/// sizes of the tables, so that methods and attributes can be patched before the
+/// class file is written again with [`ClassFile::to_bytes`]
 #[derive(Debug)]
-pub struct ClassFile
+pub struct ClassFile<'a>
 {
     pub(super) magic: u32,
 
     pub(super) minor: u16,
     pub(super) major: u16,
 
-    pub(super) constant_pool_count: u16,
-    pub(super) constant_pool: Box<[Constant]>,
+    pub(super) constant_pool: Box<[Constant<'a>]>,
+    /// Where each constant starts, at its tag
+    pub(super) constant_offsets: Box<[usize]>,
+
+    /// Where the items following the constant pool start,
+    /// from which the offsets of the next few items follow
+    pub(super) access_flags_offset: usize,
+    pub(super) access_flags: u16,
+    pub(super) this_class: u16,
+    pub(super) super_class: u16,
+
+    pub(super) interfaces: Box<[u16]>,
+
+    pub(super) fields: Box<[FieldInfo<'a>]>,
+
+    pub(super) methods: Box<[MethodInfo<'a>]>,
+
+    pub(super) attributes: Box<[AttributeInfo<'a>]>,
 }
 
-impl ClassFile
+impl<'a> ClassFile<'a>
 {
-    pub(crate) fn parse_bytes(bytes: &[u8]) -> Result<Self>
+    pub(crate) fn parse_bytes(bytes: &'a [u8]) -> Result<Self>
+    {
+        ClassFile::parse_bytes_with_versions(bytes, &SupportedVersions::default())
+    }
+
+    /// Parses a class file of one of the supported versions, checking the magic
+    /// number and the version before anything else, so that other files and
+    /// class files of unsupported versions are rejected without reading them
+    pub(crate) fn parse_bytes_with_versions(
+        bytes: &'a [u8],
+        supported_versions: &SupportedVersions,
+    ) -> Result<Self>
     {
         let mut cursor = Cursor::new(bytes);
 
         let magic = cursor.read_integer::<u32>()?;
+        if magic != Magic::CLASS_FILE.0 {
+            Err(Located::new(Error::InvalidMagic { magic }, 0).within(Segment::Item("magic")))?
+        }
 
         let minor = cursor.read_integer::<u16>()?;
         let major = cursor.read_integer::<u16>()?;
+        supported_versions
+            .check(Version::new(major, minor))
+            .map_err(|err| {
+                Located::new(Error::Version(err), 4).within(Segment::Item("minor_version"))
+            })?;
 
-        let (constant_pool_count, constant_pool) = {
+        let (constant_pool, constant_offsets) = {
             let count = cursor.read_integer::<u16>()?;
 
-            let mut pool = Vec::with_capacity(count as usize - 1);
-            for _ in 0..count - 1 {
-                let tag = cursor.read_integer::<u8>()?;
+            // The constant pool is indexed from 1 to count - 1
+            let len = (count as usize).saturating_sub(1);
 
-                let constant = match tag {
-                    7 => {
-                        let name_index = cursor.read_integer::<u16>()?;
+            let mut pool = Vec::with_capacity(len);
+            let mut offsets = Vec::with_capacity(len);
+            while pool.len() < len {
+                let offset = cursor.position();
+                let index = pool.len() + 1;
 
-                        Constant::Class { name_index }
-                    }
+                let constant = Constant::parse(&mut cursor)
+                    .map_err(|err| err.within(Segment::Constant(index)))?;
 
-                    9 => {
-                        let class_index = cursor.read_integer::<u16>()?;
-                        let name_and_type_index = cursor.read_integer::<u16>()?;
+                // Long and double constants take up two entries in the
+                // constant pool, the second of which must never be referenced
+                let is_wide = matches!(constant, Constant::Long { .. } | Constant::Double { .. });
 
-                        Constant::FieldRef {
-                            class_index,
-                            name_and_type_index,
-                        }
+                pool.push(constant);
+                offsets.push(offset);
+
+                if is_wide {
+                    if pool.len() == len {
+                        Err(
+                            Located::new(Error::WideConstantOverflow(len as u16), offset)
+                                .within(Segment::Constant(index)),
+                        )?
                     }
 
-                    10 => {
-                        let class_index = cursor.read_integer::<u16>()?;
-                        let name_and_type_index = cursor.read_integer::<u16>()?;
+                    pool.push(Constant::Unusable);
+                    offsets.push(offset);
+                }
+            }
 
-                        Constant::MethodRef {
-                            class_index,
-                            name_and_type_index,
-                        }
-                    }
+            (pool.into_boxed_slice(), offsets.into_boxed_slice())
+        };
 
-                    11 => {
-                        dbg!(&pool);
-                        todo!("InterfaceMethodRef")
-                    }
+        let access_flags_offset = cursor.position();
+        let access_flags = cursor.read_integer::<u16>()?;
+        let this_class = cursor.read_integer::<u16>()?;
+        let super_class = cursor.read_integer::<u16>()?;
 
-                    8 => {
-                        let string_index = cursor.read_integer::<u16>()?;
+        let interfaces = {
+            let count = cursor.read_integer::<u16>()?;
 
-                        Constant::String { string_index }
-                    }
+            (0..count)
+                .map(|index| {
+                    cursor.read_integer::<u16>().map_err(|err| {
+                        Located::from(err).within(Segment::Interface(index as usize))
+                    })
+                })
+                .collect::<Result<_>>()?
+        };
 
-                    3 => {
-                        dbg!(&pool);
-                        todo!("Integer")
-                    }
+        let fields = {
+            let count = cursor.read_integer::<u16>()?;
 
-                    4 => {
-                        dbg!(&pool);
-                        todo!("Float")
-                    }
+            (0..count)
+                .map(|index| {
+                    FieldInfo::parse(&mut cursor)
+                        .map_err(|err| err.within(Segment::Field(index as usize)))
+                })
+                .collect::<Result<_>>()?
+        };
 
-                    5 => {
-                        dbg!(&pool);
-                        todo!("Long")
-                    }
+        let methods = {
+            let count = cursor.read_integer::<u16>()?;
 
-                    6 => {
-                        dbg!(&pool);
-                        todo!("Double")
-                    }
+            (0..count)
+                .map(|index| {
+                    MethodInfo::parse(&mut cursor)
+                        .map_err(|err| err.within(Segment::Method(index as usize)))
+                })
+                .collect::<Result<_>>()?
+        };
 
-                    12 => {
-                        let name_index = cursor.read_integer::<u16>()?;
-                        let descriptor_index = cursor.read_integer::<u16>()?;
+        let attributes = AttributeInfo::parse_many(&mut cursor)?;
 
-                        Constant::NameAndType {
-                            name_index,
-                            descriptor_index,
-                        }
-                    }
+        if !cursor.is_empty() {
+            Err(Located::new(
+                Error::TrailingBytes(cursor.len()),
+                cursor.position(),
+            ))?
+        }
 
-                    1 => {
-                        let length = cursor.read_integer::<u16>()?;
-                        let bytes = cursor.read_bytes(length as usize)?;
+        Ok(Self {
+            magic,
 
-                        Constant::Utf8 { length, bytes }
-                    }
+            minor,
+            major,
 
-                    15 => {
-                        dbg!(&pool);
-                        todo!("MethodHandle")
-                    }
+            constant_pool,
+            constant_offsets,
 
-                    16 => {
-                        dbg!(&pool);
-                        todo!("MethodType")
-                    }
+            access_flags_offset,
+            access_flags,
+            this_class,
+            super_class,
 
-                    18 => {
-                        dbg!(&pool);
-                        todo!("InvokeDynamic")
-                    }
+            interfaces,
 
-                    tag => Err(Error::UnexpectedConstantTag(tag))?,
-                };
+            fields,
 
-                pool.push(constant);
-            }
+            methods,
 
-            let pool = pool.into_boxed_slice();
+            attributes,
+        })
+    }
 
-            (count, pool)
-        };
+    /// Writes the class file to bytes, which are the bytes
+    /// it was parsed from for as long as it is left unchanged
+    pub fn to_bytes(&self) -> writer::Result<Vec<u8>>
+    {
+        let mut writer = Writer::new();
+        self.write(&mut writer)?;
 
-        Ok(Self {
-            magic,
+        Ok(writer.into_bytes())
+    }
 
-            minor,
-            major,
+    fn write(&self, writer: &mut Writer) -> writer::Result<()>
+    {
+        writer.write_integer(self.magic);
 
-            constant_pool_count,
-            constant_pool,
+        writer.write_integer(self.minor);
+        writer.write_integer(self.major);
+
+        // The count includes the unused index 0
+        writer.write_length::<u16>("constant pool", self.constant_pool.len() + 1)?;
+        for constant in self.constant_pool.iter() {
+            constant.write(writer)?;
+        }
+
+        writer.write_integer(self.access_flags);
+        writer.write_integer(self.this_class);
+        writer.write_integer(self.super_class);
+
+        writer.write_length::<u16>("interface table", self.interfaces.len())?;
+        for interface in self.interfaces.iter() {
+            writer.write_integer(*interface);
+        }
+
+        writer.write_length::<u16>("field table", self.fields.len())?;
+        for field in self.fields.iter() {
+            field.write(writer)?;
+        }
+
+        writer.write_length::<u16>("method table", self.methods.len())?;
+        for method in self.methods.iter() {
+            method.write(writer)?;
+        }
+
+        AttributeInfo::write_many(&self.attributes, writer)
+    }
+
+    /// The constants of the constant pool, where the constant at index `n` is the
+    /// `n - 1`th, and the second entries of long and double constants are unusable
+    pub fn constant_pool(&self) -> &[Constant<'a>]
+    {
+        &self.constant_pool
+    }
+
+    /// Replaces the constant pool, such as with one extended by a builder, whose
+    /// constants, not having been parsed, have their errors reported at offset 0
+    pub fn set_constant_pool(&mut self, constant_pool: Box<[Constant<'a>]>)
+    {
+        self.constant_offsets = vec![0; constant_pool.len()].into_boxed_slice();
+        self.constant_pool = constant_pool;
+    }
+
+    /// The bytes of the Utf8 constant at `index`, if there is one
+    pub fn utf8(&self, index: u16) -> Option<&'a [u8]>
+    {
+        match self.constant_pool.get((index as usize).checked_sub(1)?) {
+            Some(Constant::Utf8 { bytes }) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn fields(&self) -> &[FieldInfo<'a>]
+    {
+        &self.fields
+    }
+
+    pub fn fields_mut(&mut self) -> &mut [FieldInfo<'a>]
+    {
+        &mut self.fields
+    }
+
+    pub fn methods(&self) -> &[MethodInfo<'a>]
+    {
+        &self.methods
+    }
+
+    pub fn methods_mut(&mut self) -> &mut [MethodInfo<'a>]
+    {
+        &mut self.methods
+    }
+
+    pub fn attributes(&self) -> &[AttributeInfo<'a>]
+    {
+        &self.attributes
+    }
+
+    pub fn attributes_mut(&mut self) -> &mut [AttributeInfo<'a>]
+    {
+        &mut self.attributes
+    }
+
+    pub fn push_attribute(&mut self, attribute: AttributeInfo<'a>)
+    {
+        push_attribute(&mut self.attributes, attribute)
+    }
+}
+
+#[derive(Debug)]
+pub struct FieldInfo<'a>
+{
+    pub(super) offset: usize,
+
+    pub(super) access_flags: u16,
+    pub(super) name_index: u16,
+    pub(super) descriptor_index: u16,
+
+    pub(super) attributes: Box<[AttributeInfo<'a>]>,
+}
+
+impl<'a> FieldInfo<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>) -> Result<Self>
+    {
+        let offset = cursor.position();
+        let access_flags = cursor.read_integer::<u16>()?;
+        let name_index = cursor.read_integer::<u16>()?;
+        let descriptor_index = cursor.read_integer::<u16>()?;
+
+        let attributes = AttributeInfo::parse_many(cursor)?;
+
+        Ok(FieldInfo {
+            offset,
+
+            access_flags,
+            name_index,
+            descriptor_index,
+
+            attributes,
+        })
+    }
+    fn write(&self, writer: &mut Writer) -> writer::Result<()>
+    {
+        writer.write_integer(self.access_flags);
+        writer.write_integer(self.name_index);
+        writer.write_integer(self.descriptor_index);
+
+        AttributeInfo::write_many(&self.attributes, writer)
+    }
+
+    pub fn access_flags(&self) -> u16
+    {
+        self.access_flags
+    }
+
+    pub fn name_index(&self) -> u16
+    {
+        self.name_index
+    }
+
+    pub fn descriptor_index(&self) -> u16
+    {
+        self.descriptor_index
+    }
+
+    pub fn attributes(&self) -> &[AttributeInfo<'a>]
+    {
+        &self.attributes
+    }
+
+    pub fn attributes_mut(&mut self) -> &mut [AttributeInfo<'a>]
+    {
+        &mut self.attributes
+    }
+
+    pub fn push_attribute(&mut self, attribute: AttributeInfo<'a>)
+    {
+        push_attribute(&mut self.attributes, attribute)
+    }
+}
+
+#[derive(Debug)]
+pub struct MethodInfo<'a>
+{
+    pub(super) offset: usize,
+
+    pub(super) access_flags: u16,
+    pub(super) name_index: u16,
+    pub(super) descriptor_index: u16,
+
+    pub(super) attributes: Box<[AttributeInfo<'a>]>,
+}
+
+impl<'a> MethodInfo<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>) -> Result<Self>
+    {
+        let offset = cursor.position();
+        let access_flags = cursor.read_integer::<u16>()?;
+        let name_index = cursor.read_integer::<u16>()?;
+        let descriptor_index = cursor.read_integer::<u16>()?;
+
+        let attributes = AttributeInfo::parse_many(cursor)?;
+
+        Ok(MethodInfo {
+            offset,
+
+            access_flags,
+            name_index,
+            descriptor_index,
+
+            attributes,
         })
     }
+    fn write(&self, writer: &mut Writer) -> writer::Result<()>
+    {
+        writer.write_integer(self.access_flags);
+        writer.write_integer(self.name_index);
+        writer.write_integer(self.descriptor_index);
+
+        AttributeInfo::write_many(&self.attributes, writer)
+    }
+
+    pub fn access_flags(&self) -> u16
+    {
+        self.access_flags
+    }
+
+    pub fn name_index(&self) -> u16
+    {
+        self.name_index
+    }
+
+    pub fn descriptor_index(&self) -> u16
+    {
+        self.descriptor_index
+    }
+
+    pub fn attributes(&self) -> &[AttributeInfo<'a>]
+    {
+        &self.attributes
+    }
+
+    pub fn attributes_mut(&mut self) -> &mut [AttributeInfo<'a>]
+    {
+        &mut self.attributes
+    }
+
+    pub fn push_attribute(&mut self, attribute: AttributeInfo<'a>)
+    {
+        push_attribute(&mut self.attributes, attribute)
+    }
 }
 
 #[derive(Debug)]
-pub(super) enum Constant
+pub struct AttributeInfo<'a>
+{
+    pub(super) offset: usize,
+
+    pub(super) attribute_name_index: u16,
+    pub(super) info: &'a [u8],
+}
+
+impl<'a> AttributeInfo<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>) -> Result<Self>
+    {
+        let offset = cursor.position();
+        let attribute_name_index = cursor.read_integer::<u16>()?;
+        let length = cursor.read_integer::<u32>()?;
+        let info = cursor.read_bytes(length as usize)?;
+
+        Ok(AttributeInfo {
+            offset,
+
+            attribute_name_index,
+            info,
+        })
+    }
+
+    pub(crate) fn parse_many(cursor: &mut Cursor<'a>) -> Result<Box<[Self]>>
+    {
+        let count = cursor.read_integer::<u16>()?;
+
+        (0..count)
+            .map(|index| {
+                AttributeInfo::parse(cursor).map_err(|err| {
+                    err.within(Segment::Attribute {
+                        index: index as usize,
+                        name: None,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    fn write_many(attributes: &[Self], writer: &mut Writer) -> writer::Result<()>
+    {
+        writer.write_length::<u16>("attribute table", attributes.len())?;
+        for attribute in attributes {
+            writer.write_integer(attribute.attribute_name_index);
+            writer.write_length::<u32>("attribute", attribute.info.len())?;
+            writer.write_bytes(attribute.info);
+        }
+
+        Ok(())
+    }
+
+    /// Creates an attribute to be added to a class file, which, not having
+    /// been parsed from one, has its errors reported at offset 0
+    pub fn new(attribute_name_index: u16, info: &'a [u8]) -> Self
+    {
+        AttributeInfo {
+            offset: 0,
+
+            attribute_name_index,
+            info,
+        }
+    }
+
+    pub fn attribute_name_index(&self) -> u16
+    {
+        self.attribute_name_index
+    }
+
+    pub fn info(&self) -> &'a [u8]
+    {
+        self.info
+    }
+
+    /// Replaces the contents of the attribute, such as to patch the body of a method
+    pub fn set_info(&mut self, info: &'a [u8])
+    {
+        self.info = info;
+    }
+}
+
+fn push_attribute<'a>(attributes: &mut Box<[AttributeInfo<'a>]>, attribute: AttributeInfo<'a>)
+{
+    let mut pushed = mem::take(attributes).into_vec();
+    pushed.push(attribute);
+
+    *attributes = pushed.into_boxed_slice();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Constant<'a>
 {
     Class
     {
-        name_index: u16
+        name_index: u16,
     },
 
     FieldRef
@@ -164,15 +544,40 @@ pub(super) enum Constant
         class_index: u16,
         name_and_type_index: u16,
     },
-    // InterfaceMethodRef
+
+    InterfaceMethodRef
+    {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+
     String
     {
-        string_index: u16
+        string_index: u16,
+    },
+
+    Integer
+    {
+        bytes: u32,
     },
-    // Integer
-    // Float
-    // Long
-    // Double
+
+    Float
+    {
+        bytes: u32,
+    },
+
+    Long
+    {
+        high_bytes: u32,
+        low_bytes: u32,
+    },
+
+    Double
+    {
+        high_bytes: u32,
+        low_bytes: u32,
+    },
+
     NameAndType
     {
         name_index: u16,
@@ -181,26 +586,353 @@ pub(super) enum Constant
 
     Utf8
     {
-        length: u16, bytes: Box<[u8]>
+        bytes: &'a [u8],
+    },
+
+    MethodHandle
+    {
+        reference_kind: u8,
+        reference_index: u16,
+    },
+
+    MethodType
+    {
+        descriptor_index: u16,
+    },
+
+    /// A dynamically-computed constant, the value its bootstrap method returns
+    Dynamic
+    {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
     },
-    // MethodHandle
-    // MethodType
-    // InvokeDynamic
+
+    InvokeDynamic
+    {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
+
+    /// A module, only found in the `module-info` class declaring or requiring it
+    Module
+    {
+        name_index: u16,
+    },
+
+    /// A package exported or opened by a module
+    Package
+    {
+        name_index: u16,
+    },
+
+    Unusable,
+}
+
+impl<'a> Constant<'a>
+{
+    fn parse(cursor: &mut Cursor<'a>) -> Result<Self>
+    {
+        let tag = cursor.read_integer::<u8>()?;
+
+        let constant = match tag {
+            7 => {
+                let name_index = cursor.read_integer::<u16>()?;
+
+                Constant::Class { name_index }
+            }
+
+            9 => {
+                let class_index = cursor.read_integer::<u16>()?;
+                let name_and_type_index = cursor.read_integer::<u16>()?;
+
+                Constant::FieldRef {
+                    class_index,
+                    name_and_type_index,
+                }
+            }
+
+            10 => {
+                let class_index = cursor.read_integer::<u16>()?;
+                let name_and_type_index = cursor.read_integer::<u16>()?;
+
+                Constant::MethodRef {
+                    class_index,
+                    name_and_type_index,
+                }
+            }
+
+            11 => {
+                let class_index = cursor.read_integer::<u16>()?;
+                let name_and_type_index = cursor.read_integer::<u16>()?;
+
+                Constant::InterfaceMethodRef {
+                    class_index,
+                    name_and_type_index,
+                }
+            }
+
+            8 => {
+                let string_index = cursor.read_integer::<u16>()?;
+
+                Constant::String { string_index }
+            }
+
+            3 => {
+                let bytes = cursor.read_integer::<u32>()?;
+
+                Constant::Integer { bytes }
+            }
+
+            4 => {
+                let bytes = cursor.read_integer::<u32>()?;
+
+                Constant::Float { bytes }
+            }
+
+            5 => {
+                let high_bytes = cursor.read_integer::<u32>()?;
+                let low_bytes = cursor.read_integer::<u32>()?;
+
+                Constant::Long {
+                    high_bytes,
+                    low_bytes,
+                }
+            }
+
+            6 => {
+                let high_bytes = cursor.read_integer::<u32>()?;
+                let low_bytes = cursor.read_integer::<u32>()?;
+
+                Constant::Double {
+                    high_bytes,
+                    low_bytes,
+                }
+            }
+
+            12 => {
+                let name_index = cursor.read_integer::<u16>()?;
+                let descriptor_index = cursor.read_integer::<u16>()?;
+
+                Constant::NameAndType {
+                    name_index,
+                    descriptor_index,
+                }
+            }
+
+            1 => {
+                let length = cursor.read_integer::<u16>()?;
+                let bytes = cursor.read_bytes(length as usize)?;
+
+                Constant::Utf8 { bytes }
+            }
+
+            15 => {
+                let reference_kind = cursor.read_integer::<u8>()?;
+                let reference_index = cursor.read_integer::<u16>()?;
+
+                Constant::MethodHandle {
+                    reference_kind,
+                    reference_index,
+                }
+            }
+
+            16 => {
+                let descriptor_index = cursor.read_integer::<u16>()?;
+
+                Constant::MethodType { descriptor_index }
+            }
+
+            17 => {
+                let bootstrap_method_attr_index = cursor.read_integer::<u16>()?;
+                let name_and_type_index = cursor.read_integer::<u16>()?;
+
+                Constant::Dynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                }
+            }
+
+            18 => {
+                let bootstrap_method_attr_index = cursor.read_integer::<u16>()?;
+                let name_and_type_index = cursor.read_integer::<u16>()?;
+
+                Constant::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                }
+            }
+
+            19 => {
+                let name_index = cursor.read_integer::<u16>()?;
+
+                Constant::Module { name_index }
+            }
+
+            20 => {
+                let name_index = cursor.read_integer::<u16>()?;
+
+                Constant::Package { name_index }
+            }
+
+            tag => Err(cursor.locate(Error::UnexpectedConstantTag(tag)))?,
+        };
+
+        Ok(constant)
+    }
+
+    fn write(&self, writer: &mut Writer) -> writer::Result<()>
+    {
+        match *self {
+            Constant::Class { name_index } => {
+                writer.write_integer::<u8>(7);
+                writer.write_integer(name_index);
+            }
+
+            Constant::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                writer.write_integer::<u8>(9);
+                writer.write_integer(class_index);
+                writer.write_integer(name_and_type_index);
+            }
+
+            Constant::MethodRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                writer.write_integer::<u8>(10);
+                writer.write_integer(class_index);
+                writer.write_integer(name_and_type_index);
+            }
+
+            Constant::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                writer.write_integer::<u8>(11);
+                writer.write_integer(class_index);
+                writer.write_integer(name_and_type_index);
+            }
+
+            Constant::String { string_index } => {
+                writer.write_integer::<u8>(8);
+                writer.write_integer(string_index);
+            }
+
+            Constant::Integer { bytes } => {
+                writer.write_integer::<u8>(3);
+                writer.write_integer(bytes);
+            }
+
+            Constant::Float { bytes } => {
+                writer.write_integer::<u8>(4);
+                writer.write_integer(bytes);
+            }
+
+            Constant::Long {
+                high_bytes,
+                low_bytes,
+            } => {
+                writer.write_integer::<u8>(5);
+                writer.write_integer(high_bytes);
+                writer.write_integer(low_bytes);
+            }
+
+            Constant::Double {
+                high_bytes,
+                low_bytes,
+            } => {
+                writer.write_integer::<u8>(6);
+                writer.write_integer(high_bytes);
+                writer.write_integer(low_bytes);
+            }
+
+            Constant::NameAndType {
+                name_index,
+                descriptor_index,
+            } => {
+                writer.write_integer::<u8>(12);
+                writer.write_integer(name_index);
+                writer.write_integer(descriptor_index);
+            }
+
+            Constant::Utf8 { bytes } => {
+                writer.write_integer::<u8>(1);
+                writer.write_length::<u16>("utf8 constant", bytes.len())?;
+                writer.write_bytes(bytes);
+            }
+
+            Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => {
+                writer.write_integer::<u8>(15);
+                writer.write_integer(reference_kind);
+                writer.write_integer(reference_index);
+            }
+
+            Constant::MethodType { descriptor_index } => {
+                writer.write_integer::<u8>(16);
+                writer.write_integer(descriptor_index);
+            }
+
+            Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                writer.write_integer::<u8>(17);
+                writer.write_integer(bootstrap_method_attr_index);
+                writer.write_integer(name_and_type_index);
+            }
+
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                writer.write_integer::<u8>(18);
+                writer.write_integer(bootstrap_method_attr_index);
+                writer.write_integer(name_and_type_index);
+            }
+
+            Constant::Module { name_index } => {
+                writer.write_integer::<u8>(19);
+                writer.write_integer(name_index);
+            }
+
+            Constant::Package { name_index } => {
+                writer.write_integer::<u8>(20);
+                writer.write_integer(name_index);
+            }
+
+            // Only takes up the index following a long or double constant
+            Constant::Unusable => {}
+        }
+
+        Ok(())
+    }
 }
 
 pub mod error
 {
     use std::{error, fmt, result};
 
-    use crate::parse::cursor;
+    use crate::parse::{context::Located, cursor, version};
 
-    pub type Result<T> = result::Result<T, Error>;
+    pub type Result<T> = result::Result<T, Located<Error>>;
 
     #[derive(Debug)]
     pub enum Error
     {
         Cursor(cursor::Error),
+        InvalidMagic
+        {
+            magic: u32,
+        },
+        Version(version::error::Error),
         UnexpectedConstantTag(u8),
+        WideConstantOverflow(u16),
+        TrailingBytes(usize),
     }
 
     impl fmt::Display for Error
@@ -209,9 +941,22 @@ pub mod error
         {
             match self {
                 Error::Cursor(cursor_err) => write!(f, "{cursor_err}"),
+                Error::InvalidMagic { magic } => {
+                    write!(f, "invalid magic number {magic:#010x}, expected 0xcafebabe")
+                }
+                Error::Version(version_err) => write!(f, "{version_err}"),
                 Error::UnexpectedConstantTag(tag) => {
                     write!(f, "unexpected constant tag {tag}")
                 }
+                Error::WideConstantOverflow(index) => {
+                    write!(
+                        f,
+                        "long or double constant at index {index} overflows the constant pool"
+                    )
+                }
+                Error::TrailingBytes(count) => {
+                    write!(f, "found {count} trailing bytes after the class file")
+                }
             }
         }
     }
@@ -222,16 +967,19 @@ pub mod error
         {
             match self {
                 Error::Cursor(cursor_err) => Some(cursor_err),
+                Error::Version(version_err) => Some(version_err),
                 _ => None,
             }
         }
     }
 
-    impl From<cursor::Error> for Error
+    impl From<cursor::Error> for Located<Error>
     {
         fn from(cursor_err: cursor::Error) -> Self
         {
-            Error::Cursor(cursor_err)
+            let offset = cursor_err.offset();
+
+            Located::new(Error::Cursor(cursor_err), offset)
         }
     }
 }
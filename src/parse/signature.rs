@@ -0,0 +1,619 @@
+//! Generic signatures, as found in Signature attributes
+//!
+//! Signatures extend the grammar of descriptors with type parameters, type
+//! arguments and type variables, so they share its reader and errors
+
+use std::fmt;
+
+use super::descriptor::{
+    error::{Error, Result},
+    BaseType, Reader,
+};
+
+/// The generic signature of a class, such as
+/// `<T:Ljava/lang/Object;>Ljava/util/AbstractList<TT;>;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassSignature
+{
+    type_parameters: Box<[TypeParameter]>,
+    superclass: ClassTypeSignature,
+    superinterfaces: Box<[ClassTypeSignature]>,
+}
+
+impl ClassSignature
+{
+    pub fn parse(signature: &str) -> Result<Self>
+    {
+        let mut reader = Reader::new(signature);
+
+        let type_parameters = type_parameters(&mut reader)?;
+        let superclass = class_type_signature(&mut reader)?;
+        let mut superinterfaces = Vec::new();
+        while reader.peek().is_some() {
+            superinterfaces.push(class_type_signature(&mut reader)?);
+        }
+
+        Ok(ClassSignature {
+            type_parameters,
+            superclass,
+            superinterfaces: superinterfaces.into(),
+        })
+    }
+
+    pub fn type_parameters(&self) -> &[TypeParameter]
+    {
+        &self.type_parameters
+    }
+
+    pub fn superclass(&self) -> &ClassTypeSignature
+    {
+        &self.superclass
+    }
+
+    pub fn superinterfaces(&self) -> &[ClassTypeSignature]
+    {
+        &self.superinterfaces
+    }
+}
+
+impl fmt::Display for ClassSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        fmt_type_parameters(f, &self.type_parameters)?;
+        write!(f, "{}", self.superclass)?;
+        for superinterface in self.superinterfaces.iter() {
+            write!(f, "{superinterface}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The generic signature of a method, such as
+/// `<E:Ljava/lang/Exception;>(Ljava/util/List<*>;)V^TE;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MethodSignature
+{
+    type_parameters: Box<[TypeParameter]>,
+    parameters: Box<[JavaTypeSignature]>,
+    result: Option<JavaTypeSignature>,
+    throws: Box<[ReferenceTypeSignature]>,
+}
+
+impl MethodSignature
+{
+    pub fn parse(signature: &str) -> Result<Self>
+    {
+        let mut reader = Reader::new(signature);
+
+        let type_parameters = type_parameters(&mut reader)?;
+
+        reader.expect('(')?;
+        let mut parameters = Vec::new();
+        while reader.peek() != Some(')') {
+            parameters.push(java_type_signature(&mut reader)?);
+        }
+        reader.expect(')')?;
+
+        let result = match reader.peek() {
+            Some('V') => {
+                reader.next()?;
+
+                None
+            }
+            _ => Some(java_type_signature(&mut reader)?),
+        };
+
+        // Only class types and type variables can be thrown
+        let mut throws = Vec::new();
+        while reader.peek() == Some('^') {
+            reader.next()?;
+
+            let position = reader.position();
+            match reader.peek() {
+                Some('L' | 'T') => throws.push(reference_type_signature(&mut reader)?),
+                Some(character) => Err(Error::UnexpectedCharacter {
+                    character,
+                    position,
+                })?,
+                None => Err(Error::UnexpectedEnd)?,
+            }
+        }
+        reader.finish()?;
+
+        Ok(MethodSignature {
+            type_parameters,
+            parameters: parameters.into(),
+            result,
+            throws: throws.into(),
+        })
+    }
+
+    pub fn type_parameters(&self) -> &[TypeParameter]
+    {
+        &self.type_parameters
+    }
+
+    pub fn parameters(&self) -> &[JavaTypeSignature]
+    {
+        &self.parameters
+    }
+
+    /// The type the method returns, or `None` for `void`
+    pub fn result(&self) -> Option<&JavaTypeSignature>
+    {
+        self.result.as_ref()
+    }
+
+    /// The exceptions the method throws, which are either class types or type
+    /// variables, and which compilers only record when one of them is a type variable
+    pub fn throws(&self) -> &[ReferenceTypeSignature]
+    {
+        &self.throws
+    }
+}
+
+impl fmt::Display for MethodSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        fmt_type_parameters(f, &self.type_parameters)?;
+
+        write!(f, "(")?;
+        for parameter in self.parameters.iter() {
+            write!(f, "{parameter}")?;
+        }
+        write!(f, ")")?;
+
+        match &self.result {
+            Some(result) => write!(f, "{result}")?,
+            None => write!(f, "V")?,
+        }
+
+        for throws in self.throws.iter() {
+            write!(f, "^{throws}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The generic signature of a field, record component or local variable,
+/// such as `Ljava/util/List<Ljava/lang/String;>;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldSignature
+{
+    field_type: ReferenceTypeSignature,
+}
+
+impl FieldSignature
+{
+    pub fn parse(signature: &str) -> Result<Self>
+    {
+        let mut reader = Reader::new(signature);
+        let field_type = reference_type_signature(&mut reader)?;
+        reader.finish()?;
+
+        Ok(FieldSignature { field_type })
+    }
+
+    pub fn field_type(&self) -> &ReferenceTypeSignature
+    {
+        &self.field_type
+    }
+}
+
+impl fmt::Display for FieldSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.field_type)
+    }
+}
+
+/// A type parameter, such as the `U extends Number & Comparable<U>`
+/// of a declaration, written as `U:Ljava/lang/Number;:Ljava/lang/Comparable<TU;>;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeParameter
+{
+    name: Box<str>,
+    class_bound: Option<ReferenceTypeSignature>,
+    interface_bounds: Box<[ReferenceTypeSignature]>,
+}
+
+impl TypeParameter
+{
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    /// The bound that comes first, which is missing
+    /// when the parameter is only bound by interfaces
+    pub fn class_bound(&self) -> Option<&ReferenceTypeSignature>
+    {
+        self.class_bound.as_ref()
+    }
+
+    pub fn interface_bounds(&self) -> &[ReferenceTypeSignature]
+    {
+        &self.interface_bounds
+    }
+}
+
+impl fmt::Display for TypeParameter
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}:", self.name)?;
+        if let Some(class_bound) = &self.class_bound {
+            write!(f, "{class_bound}")?;
+        }
+        for interface_bound in self.interface_bounds.iter() {
+            write!(f, ":{interface_bound}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Any type, including primitives
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum JavaTypeSignature
+{
+    Base(BaseType),
+    Reference(ReferenceTypeSignature),
+}
+
+impl fmt::Display for JavaTypeSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            JavaTypeSignature::Base(base_type) => write!(f, "{}", base_type.descriptor()),
+            JavaTypeSignature::Reference(reference) => write!(f, "{reference}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ReferenceTypeSignature
+{
+    Class(ClassTypeSignature),
+    TypeVariable(Box<str>),
+    Array
+    {
+        dimensions: u8,
+        /// The type of the innermost elements, which is never an array itself
+        element: Box<JavaTypeSignature>,
+    },
+}
+
+impl fmt::Display for ReferenceTypeSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            ReferenceTypeSignature::Class(class) => write!(f, "{class}"),
+            ReferenceTypeSignature::TypeVariable(name) => write!(f, "T{name};"),
+            ReferenceTypeSignature::Array {
+                dimensions,
+                element,
+            } => write!(f, "{}{element}", "[".repeat(*dimensions as usize)),
+        }
+    }
+}
+
+/// A possibly parameterized class type, such as `Ljava/util/Map<TK;TV;>.Entry<TK;TV;>;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClassTypeSignature
+{
+    package: Box<str>,
+    classes: Box<[SimpleClassTypeSignature]>,
+}
+
+impl ClassTypeSignature
+{
+    /// The package of the class in internal form, such
+    /// as `java/util`, which is empty for the unnamed package
+    pub fn package(&self) -> &str
+    {
+        &self.package
+    }
+
+    /// The top level class followed by each class nested in it,
+    /// so `Map<K, V>.Entry<K, V>` is made of `Map<K, V>` and `Entry<K, V>`
+    pub fn classes(&self) -> &[SimpleClassTypeSignature]
+    {
+        &self.classes
+    }
+
+    /// The binary name of the class in internal form, such as `java/util/Map$Entry`
+    pub fn binary_name(&self) -> String
+    {
+        let mut binary_name = String::new();
+        if !self.package.is_empty() {
+            binary_name.push_str(&self.package);
+            binary_name.push('/');
+        }
+
+        for (i, class) in self.classes.iter().enumerate() {
+            if i != 0 {
+                binary_name.push('$');
+            }
+            binary_name.push_str(&class.name);
+        }
+
+        binary_name
+    }
+}
+
+impl fmt::Display for ClassTypeSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "L")?;
+        if !self.package.is_empty() {
+            write!(f, "{}/", self.package)?;
+        }
+
+        for (i, class) in self.classes.iter().enumerate() {
+            if i != 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{class}")?;
+        }
+
+        write!(f, ";")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimpleClassTypeSignature
+{
+    name: Box<str>,
+    type_arguments: Box<[TypeArgument]>,
+}
+
+impl SimpleClassTypeSignature
+{
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    pub fn type_arguments(&self) -> &[TypeArgument]
+    {
+        &self.type_arguments
+    }
+}
+
+impl fmt::Display for SimpleClassTypeSignature
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.name)?;
+
+        if !self.type_arguments.is_empty() {
+            write!(f, "<")?;
+            for type_argument in self.type_arguments.iter() {
+                write!(f, "{type_argument}")?;
+            }
+            write!(f, ">")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeArgument
+{
+    /// `?`, written as `*`
+    Any,
+    Exact(ReferenceTypeSignature),
+    /// `? extends T`, written as `+`
+    Extends(ReferenceTypeSignature),
+    /// `? super T`, written as `-`
+    Super(ReferenceTypeSignature),
+}
+
+impl fmt::Display for TypeArgument
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            TypeArgument::Any => write!(f, "*"),
+            TypeArgument::Exact(bound) => write!(f, "{bound}"),
+            TypeArgument::Extends(bound) => write!(f, "+{bound}"),
+            TypeArgument::Super(bound) => write!(f, "-{bound}"),
+        }
+    }
+}
+
+fn fmt_type_parameters(f: &mut fmt::Formatter<'_>, type_parameters: &[TypeParameter])
+    -> fmt::Result
+{
+    if !type_parameters.is_empty() {
+        write!(f, "<")?;
+        for type_parameter in type_parameters {
+            write!(f, "{type_parameter}")?;
+        }
+        write!(f, ">")?;
+    }
+
+    Ok(())
+}
+
+fn type_parameters(reader: &mut Reader) -> Result<Box<[TypeParameter]>>
+{
+    let mut type_parameters = Vec::new();
+    if reader.peek() != Some('<') {
+        return Ok(type_parameters.into());
+    }
+
+    reader.next()?;
+    // There has to be at least one type parameter between the brackets
+    loop {
+        let name = reader.identifier()?.into();
+
+        reader.expect(':')?;
+        let class_bound = match reader.peek() {
+            Some(':' | '>') => None,
+            _ => Some(reference_type_signature(reader)?),
+        };
+
+        let mut interface_bounds = Vec::new();
+        while reader.peek() == Some(':') {
+            reader.next()?;
+            interface_bounds.push(reference_type_signature(reader)?);
+        }
+
+        type_parameters.push(TypeParameter {
+            name,
+            class_bound,
+            interface_bounds: interface_bounds.into(),
+        });
+
+        if reader.peek() == Some('>') {
+            reader.next()?;
+            break;
+        }
+    }
+
+    Ok(type_parameters.into())
+}
+
+fn java_type_signature(reader: &mut Reader) -> Result<JavaTypeSignature>
+{
+    let position = reader.position();
+
+    match reader.peek() {
+        Some('L' | 'T' | '[') => Ok(JavaTypeSignature::Reference(reference_type_signature(
+            reader,
+        )?)),
+        Some(character) => match BaseType::from_descriptor(character) {
+            Some(base_type) => {
+                reader.next()?;
+
+                Ok(JavaTypeSignature::Base(base_type))
+            }
+            None => Err(Error::UnexpectedCharacter {
+                character,
+                position,
+            }),
+        },
+        None => Err(Error::UnexpectedEnd),
+    }
+}
+
+fn reference_type_signature(reader: &mut Reader) -> Result<ReferenceTypeSignature>
+{
+    let position = reader.position();
+
+    match reader.peek() {
+        Some('L') => Ok(ReferenceTypeSignature::Class(class_type_signature(reader)?)),
+        Some('T') => {
+            reader.next()?;
+            let name = reader.identifier()?.into();
+            reader.expect(';')?;
+
+            Ok(ReferenceTypeSignature::TypeVariable(name))
+        }
+        Some('[') => {
+            let mut dimensions = 0usize;
+            while reader.peek() == Some('[') {
+                reader.next()?;
+                dimensions += 1;
+            }
+            // An array type may have at most 255 dimensions
+            let dimensions =
+                u8::try_from(dimensions).map_err(|_| Error::TooManyDimensions { position })?;
+
+            Ok(ReferenceTypeSignature::Array {
+                dimensions,
+                element: Box::new(java_type_signature(reader)?),
+            })
+        }
+        Some(character) => Err(Error::UnexpectedCharacter {
+            character,
+            position,
+        }),
+        None => Err(Error::UnexpectedEnd),
+    }
+}
+
+fn class_type_signature(reader: &mut Reader) -> Result<ClassTypeSignature>
+{
+    reader.expect('L')?;
+
+    // Every identifier followed by a slash is part of the package
+    let mut package = Vec::new();
+    let mut name = reader.identifier()?;
+    while reader.peek() == Some('/') {
+        reader.next()?;
+        package.push(name);
+        name = reader.identifier()?;
+    }
+
+    let mut classes = vec![SimpleClassTypeSignature {
+        name: name.into(),
+        type_arguments: type_arguments(reader)?,
+    }];
+    while reader.peek() == Some('.') {
+        reader.next()?;
+
+        classes.push(SimpleClassTypeSignature {
+            name: reader.identifier()?.into(),
+            type_arguments: type_arguments(reader)?,
+        });
+    }
+    reader.expect(';')?;
+
+    Ok(ClassTypeSignature {
+        package: package.join("/").into(),
+        classes: classes.into(),
+    })
+}
+
+fn type_arguments(reader: &mut Reader) -> Result<Box<[TypeArgument]>>
+{
+    let mut type_arguments = Vec::new();
+    if reader.peek() != Some('<') {
+        return Ok(type_arguments.into());
+    }
+
+    reader.enter()?;
+    reader.next()?;
+    // There has to be at least one type argument between the brackets
+    loop {
+        let type_argument = match reader.peek() {
+            Some('*') => {
+                reader.next()?;
+
+                TypeArgument::Any
+            }
+            Some('+') => {
+                reader.next()?;
+
+                TypeArgument::Extends(reference_type_signature(reader)?)
+            }
+            Some('-') => {
+                reader.next()?;
+
+                TypeArgument::Super(reference_type_signature(reader)?)
+            }
+            _ => TypeArgument::Exact(reference_type_signature(reader)?),
+        };
+        type_arguments.push(type_argument);
+
+        if reader.peek() == Some('>') {
+            reader.next()?;
+            break;
+        }
+    }
+    reader.leave();
+
+    Ok(type_arguments.into())
+}
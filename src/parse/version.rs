@@ -0,0 +1,213 @@
+use std::fmt;
+
+use self::error::{Error, Result};
+
+/// The version of the class file format, given as `major.minor`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version
+{
+    major: u16,
+    minor: u16,
+}
+
+impl Version
+{
+    /// The minor version of class files that depend on the preview features of their release
+    pub const PREVIEW_MINOR: u16 = 0xffff;
+
+    /// Preview features, and with them the restriction of the
+    /// minor version to 0 and 0xFFFF, came with Java SE 12
+    const FIRST_PREVIEW_MAJOR: u16 = 56;
+
+    pub const fn new(major: u16, minor: u16) -> Self
+    {
+        Version { major, minor }
+    }
+
+    pub const fn major(&self) -> u16
+    {
+        self.major
+    }
+
+    pub const fn minor(&self) -> u16
+    {
+        self.minor
+    }
+
+    pub const fn is_preview(&self) -> bool
+    {
+        self.major >= Version::FIRST_PREVIEW_MAJOR && self.minor == Version::PREVIEW_MINOR
+    }
+
+    /// The name of the Java SE release that introduced this major version, such as
+    /// `"1.4"`, `"5.0"` or `"21"`, or `None` for versions older than 45 or newer
+    /// than the latest release this crate knows of
+    pub fn java_se_name(&self) -> Option<&'static str>
+    {
+        const NAMES: [&str; 27] = [
+            "1.1", "1.2", "1.3", "1.4", "5.0", "6", "7", "8", "9", "10", "11", "12", "13", "14",
+            "15", "16", "17", "18", "19", "20", "21", "22", "23", "24", "25", "26", "27",
+        ];
+
+        let index = self.major.checked_sub(45)?;
+        NAMES.get(usize::from(index)).copied()
+    }
+
+    /// Orders versions by major and then minor version, with a preview
+    /// minor version counting as the first version of its release
+    fn key(&self) -> (u16, u16)
+    {
+        if self.is_preview() {
+            (self.major, 0)
+        } else {
+            (self.major, self.minor)
+        }
+    }
+}
+
+impl fmt::Debug for Version
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl fmt::Display for Version
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The range of class file versions the parser accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SupportedVersions
+{
+    min: Version,
+    max: Version,
+    preview: bool,
+}
+
+impl SupportedVersions
+{
+    /// Accepts the versions from `min` through `max`, and if `preview` is set,
+    /// class files using the preview features of the release of `max`
+    pub const fn new(min: Version, max: Version, preview: bool) -> Self
+    {
+        SupportedVersions { min, max, preview }
+    }
+
+    pub const fn min(&self) -> Version
+    {
+        self.min
+    }
+
+    pub const fn max(&self) -> Version
+    {
+        self.max
+    }
+
+    pub const fn preview(&self) -> bool
+    {
+        self.preview
+    }
+
+    pub fn check(&self, version: Version) -> Result<()>
+    {
+        if version.major >= Version::FIRST_PREVIEW_MAJOR
+            && version.minor != 0
+            && version.minor != Version::PREVIEW_MINOR
+        {
+            Err(Error::InvalidMinorVersion { version })?
+        }
+
+        if version.key() < self.min.key() || version.key() > self.max.key() {
+            Err(Error::Unsupported {
+                version,
+                supported: *self,
+            })?
+        }
+
+        // Preview features are only ever supported by their own release
+        if version.is_preview() && (!self.preview || version.major != self.max.major) {
+            Err(Error::PreviewUnsupported { version })?
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SupportedVersions
+{
+    /// Java SE 1.1 through Java SE 21, without preview features
+    fn default() -> Self
+    {
+        SupportedVersions::new(Version::new(45, 0), Version::new(65, 0), false)
+    }
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    use super::{SupportedVersions, Version};
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        Unsupported
+        {
+            version: Version,
+            supported: SupportedVersions,
+        },
+        InvalidMinorVersion
+        {
+            version: Version
+        },
+        PreviewUnsupported
+        {
+            version: Version
+        },
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::Unsupported { version, supported } => {
+                    write!(
+                        f,
+                        "unsupported class file version {version}, expected {} through {}",
+                        supported.min(),
+                        supported.max()
+                    )
+                }
+                Error::InvalidMinorVersion { version } => {
+                    write!(
+                        f,
+                        "invalid class file version {version}, the minor version must be 0 or 65535"
+                    )
+                }
+                Error::PreviewUnsupported { version } => {
+                    write!(
+                        f,
+                        "class file version {version} depends on unsupported preview features"
+                    )
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            None
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! The counterpart of the cursor, appending big-endian values to a buffer
+
+pub use self::error::{Error, Result};
+use super::cursor::{Float, Integer};
+
+#[derive(Debug, Default)]
+pub struct Writer
+{
+    bytes: Vec<u8>,
+}
+
+impl Writer
+{
+    pub fn new() -> Self
+    {
+        Writer::default()
+    }
+
+    /// The offset of the next byte to be written
+    pub fn position(&self) -> usize
+    {
+        self.bytes.len()
+    }
+
+    pub fn write_integer<I>(&mut self, value: I)
+    where
+        I: Integer,
+    {
+        value.extend_be(&mut self.bytes)
+    }
+
+    pub fn write_float<F>(&mut self, value: F)
+    where
+        F: Float,
+    {
+        value.extend_be(&mut self.bytes)
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8])
+    {
+        self.bytes.extend_from_slice(bytes)
+    }
+
+    /// Writes the length or count of `item`, which
+    /// fails when it does not fit in the integer type
+    pub fn write_length<I>(&mut self, item: &'static str, length: usize) -> Result<()>
+    where
+        I: Integer + TryFrom<usize>,
+    {
+        let value = I::try_from(length).map_err(|_| Error::TooLong {
+            item,
+            length,
+            max: (1 << (8 * I::SIZE)) - 1,
+        })?;
+
+        self.write_integer(value);
+
+        Ok(())
+    }
+
+    pub fn into_bytes(self) -> Vec<u8>
+    {
+        self.bytes
+    }
+}
+
+pub mod error
+{
+    use std::{error, fmt, result};
+
+    pub type Result<T> = result::Result<T, Error>;
+
+    #[derive(Debug)]
+    pub enum Error
+    {
+        TooLong
+        {
+            item: &'static str,
+            length: usize,
+            max: usize,
+        },
+    }
+
+    impl fmt::Display for Error
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+        {
+            match self {
+                Error::TooLong { item, length, max } => {
+                    write!(f, "{item} is {length} long, more than the maximum of {max}")
+                }
+            }
+        }
+    }
+
+    impl error::Error for Error
+    {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)>
+        {
+            None
+        }
+    }
+}
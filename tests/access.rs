@@ -0,0 +1,325 @@
+//! Tests for the legality rules of class, field and method access flags
+
+mod common;
+
+use common::ClassFile;
+use rjvm::{
+    error::Error,
+    parse::{
+        self,
+        access::error::{Error as AccessError, Kind},
+    },
+};
+
+const CLASS: u16 = 0x0021;
+const INTERFACE: u16 = 0x0601;
+
+/// A class file of the given version and access flags, with a field of type int
+/// and a method returning void, each with the given name and access flags
+fn class_file(major: u16, access_flags: u16, fields: &[u16], methods: &[(u16, &str)]) -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("A");
+    class_file.set_major(major);
+    class_file.set_access_flags(access_flags);
+    class_file.set_super_class("java/lang/Object");
+
+    let field_descriptor = class_file.utf8("I");
+    for (index, &field_access) in fields.iter().enumerate() {
+        let name = class_file.utf8(format!("f{index}"));
+        class_file.add_field(field_access, name, field_descriptor, &[]);
+    }
+
+    let method_descriptor = class_file.utf8("()V");
+    for &(method_access, name) in methods {
+        let name = class_file.utf8(name);
+        class_file.add_method(method_access, name, method_descriptor, &[]);
+    }
+
+    class_file.to_bytes()
+}
+
+/// Asserts that parsing `bytes` fails on access flags, with an error that is `expected`
+fn assert_rejected(bytes: &[u8], expected: impl FnOnce(&AccessError) -> bool)
+{
+    match rjvm::parse(bytes) {
+        Err(Error::Parse(err)) => match err.error() {
+            parse::error::Error::Access(access_err) => {
+                assert!(expected(access_err), "{access_err:?}")
+            }
+            err => panic!("expected an access flag error, got {err}"),
+        },
+        Err(err) => panic!("expected an access flag error, got {err}"),
+        Ok(_) => panic!("accepted illegal access flags"),
+    }
+}
+
+#[test]
+fn legal_class_flags_are_accepted()
+{
+    let cases = [
+        (CLASS, "public class"),
+        (0x0031, "public final class"),
+        (0x0421, "public abstract class"),
+        (INTERFACE, "public interface"),
+        (0x2601, "public annotation interface"),
+        (0x4031, "public enum"),
+        (0x1000, "synthetic package-private class"),
+    ];
+
+    for (access_flags, reason) in cases {
+        let bytes = class_file(52, access_flags, &[], &[]);
+        let class = rjvm::parse(&bytes).unwrap_or_else(|err| panic!("rejected {reason}: {err}"));
+
+        assert_eq!(class.access_flags().bits(), access_flags);
+    }
+}
+
+#[test]
+fn illegal_class_flags_are_rejected()
+{
+    assert_rejected(&class_file(52, 0x0201, &[], &[]), |err| {
+        matches!(
+            err,
+            AccessError::MissingFlag {
+                kind: Kind::Class,
+                flag: "ACC_ABSTRACT",
+                required_by: "ACC_INTERFACE",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, 0x0431, &[], &[]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Class,
+                first: "ACC_FINAL",
+                second: "ACC_ABSTRACT",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, 0x0621, &[], &[]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Class,
+                first: "ACC_INTERFACE",
+                second: "ACC_SUPER",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, 0x2421, &[], &[]), |err| {
+        matches!(
+            err,
+            AccessError::MissingFlag {
+                kind: Kind::Class,
+                flag: "ACC_INTERFACE",
+                required_by: "ACC_ANNOTATION",
+            }
+        )
+    });
+    // Modules have no other flags
+    assert_rejected(&class_file(53, 0x8001, &[], &[]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Class,
+                first: "ACC_MODULE",
+                second: "ACC_PUBLIC",
+            }
+        )
+    });
+}
+
+#[test]
+fn legal_field_flags_are_accepted()
+{
+    let cases = [
+        (CLASS, 0x0000, "package-private field"),
+        (CLASS, 0x0002, "private field"),
+        (CLASS, 0x0048, "static volatile field"),
+        (CLASS, 0x0019, "public static final field"),
+        (INTERFACE, 0x0019, "interface constant"),
+        (INTERFACE, 0x1019, "synthetic interface constant"),
+    ];
+
+    for (class_access, field_access, reason) in cases {
+        let bytes = class_file(52, class_access, &[field_access], &[]);
+        let class = rjvm::parse(&bytes).unwrap_or_else(|err| panic!("rejected {reason}: {err}"));
+
+        assert_eq!(class.fields()[0].access_flags().bits(), field_access);
+    }
+}
+
+#[test]
+fn illegal_field_flags_are_rejected()
+{
+    assert_rejected(&class_file(52, CLASS, &[0x0003], &[]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Field,
+                first: "ACC_PUBLIC",
+                second: "ACC_PRIVATE",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, CLASS, &[0x0050], &[]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Field,
+                first: "ACC_FINAL",
+                second: "ACC_VOLATILE",
+            }
+        )
+    });
+    // Interface fields are constants
+    assert_rejected(&class_file(52, INTERFACE, &[0x0009], &[]), |err| {
+        matches!(
+            err,
+            AccessError::MissingFlag {
+                kind: Kind::Field,
+                flag: "ACC_FINAL",
+                required_by: "ACC_INTERFACE",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, INTERFACE, &[0x0099], &[]), |err| {
+        matches!(
+            err,
+            AccessError::IllegalInterfaceMemberFlag {
+                kind: Kind::Field,
+                flag: "ACC_TRANSIENT",
+            }
+        )
+    });
+}
+
+#[test]
+fn legal_method_flags_are_accepted()
+{
+    let cases = [
+        (52, CLASS, 0x0001, "<init>", "public constructor"),
+        (52, CLASS, 0x0082, "<init>", "private varargs constructor"),
+        (52, CLASS, 0x0008, "<clinit>", "class initialiser"),
+        // Before version 51.0 the flags of class initialisers are ignored
+        (
+            50,
+            CLASS,
+            0x0000,
+            "<clinit>",
+            "non-static class initialiser",
+        ),
+        (52, CLASS, 0x0121, "m", "public synchronized native method"),
+        (52, CLASS, 0x0401, "m", "public abstract method"),
+        (52, INTERFACE, 0x0401, "m", "abstract interface method"),
+        (
+            51,
+            INTERFACE,
+            0x0401,
+            "m",
+            "abstract interface method before Java 8",
+        ),
+        (52, INTERFACE, 0x0001, "m", "default method"),
+        (52, INTERFACE, 0x0009, "m", "static interface method"),
+        (52, INTERFACE, 0x0002, "m", "private interface method"),
+        // ACC_STRICT lost its meaning with version 61.0
+        (61, CLASS, 0x0c01, "m", "strict abstract method"),
+    ];
+
+    for (major, class_access, method_access, name, reason) in cases {
+        let bytes = class_file(major, class_access, &[], &[(method_access, name)]);
+        let class = rjvm::parse(&bytes).unwrap_or_else(|err| panic!("rejected {reason}: {err}"));
+
+        assert_eq!(class.methods()[0].access_flags().bits(), method_access);
+    }
+}
+
+#[test]
+fn illegal_method_flags_are_rejected()
+{
+    // Interface methods had to be public and abstract before Java 8
+    assert_rejected(&class_file(51, INTERFACE, &[], &[(0x0001, "m")]), |err| {
+        matches!(
+            err,
+            AccessError::MissingFlag {
+                kind: Kind::Method,
+                flag: "ACC_ABSTRACT",
+                required_by: "ACC_INTERFACE",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, INTERFACE, &[], &[(0x0000, "m")]), |err| {
+        matches!(
+            err,
+            AccessError::MissingFlag {
+                kind: Kind::Method,
+                flag: "ACC_PUBLIC",
+                required_by: "ACC_INTERFACE",
+            }
+        )
+    });
+    for (method_access, flag) in [
+        (0x0011, "ACC_FINAL"),
+        (0x0021, "ACC_SYNCHRONIZED"),
+        (0x0101, "ACC_NATIVE"),
+    ] {
+        let bytes = class_file(52, INTERFACE, &[], &[(method_access, "m")]);
+        assert_rejected(&bytes, |err| {
+            matches!(
+                err,
+                AccessError::IllegalInterfaceMemberFlag {
+                    kind: Kind::Method,
+                    flag: illegal,
+                } if *illegal == flag
+            )
+        });
+    }
+    assert_rejected(&class_file(52, INTERFACE, &[], &[(0x0005, "m")]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Method,
+                first: "ACC_PUBLIC",
+                second: "ACC_PROTECTED",
+            }
+        )
+    });
+
+    assert_rejected(&class_file(52, CLASS, &[], &[(0x0409, "m")]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Method,
+                first: "ACC_ABSTRACT",
+                second: "ACC_STATIC",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, CLASS, &[], &[(0x0c01, "m")]), |err| {
+        matches!(
+            err,
+            AccessError::ConflictingFlags {
+                kind: Kind::Method,
+                first: "ACC_ABSTRACT",
+                second: "ACC_STRICT",
+            }
+        )
+    });
+    assert_rejected(&class_file(52, CLASS, &[], &[(0x0009, "<init>")]), |err| {
+        matches!(err, AccessError::IllegalConstructorFlag("ACC_STATIC"))
+    });
+    assert_rejected(
+        &class_file(51, CLASS, &[], &[(0x0000, "<clinit>")]),
+        |err| {
+            matches!(
+                err,
+                AccessError::MissingFlag {
+                    kind: Kind::Method,
+                    flag: "ACC_STATIC",
+                    required_by: "<clinit>",
+                }
+            )
+        },
+    );
+}
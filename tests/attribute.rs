@@ -0,0 +1,358 @@
+//! Tests for decoding attributes, on class files built up from their bytes
+
+mod common;
+
+use common::ClassFile;
+use rjvm::{
+    error::Error,
+    parse::{
+        self,
+        attribute::{self, Attribute, AttributeDecoder, CustomAttribute, ElementValue},
+        constant_pool::ConstantPool,
+        cursor::Cursor,
+    },
+};
+
+/// The name of a vendor attribute that the JVM specification does not define
+const VENDOR: &str = "com.example.Vendor";
+
+/// A class file named `A` with a vendor attribute
+fn vendor_class_file() -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("A");
+    let vendor = class_file.utf8(VENDOR);
+    class_file.add_attribute(vendor, &[0x12, 0x34]);
+
+    class_file.to_bytes()
+}
+
+#[derive(Debug, PartialEq)]
+struct Vendor(u16);
+
+struct VendorDecoder;
+
+impl AttributeDecoder for VendorDecoder
+{
+    fn name(&self) -> &str
+    {
+        VENDOR
+    }
+
+    fn decode(
+        &self,
+        cursor: &mut Cursor<'_>,
+        _constant_pool: &ConstantPool<'_>,
+    ) -> Result<Box<dyn CustomAttribute>, Box<dyn std::error::Error + Send + Sync>>
+    {
+        Ok(Box::new(Vendor(cursor.read_integer::<u16>()?)))
+    }
+}
+
+#[test]
+fn attributes_without_a_decoder_keep_their_name()
+{
+    let bytes = vendor_class_file();
+    let class = rjvm::parse(&bytes).unwrap();
+
+    match class.attributes() {
+        [Attribute::Unknown { name, info }] => {
+            assert_eq!(name, VENDOR);
+            assert_eq!(*info, &[0x12, 0x34]);
+        }
+        attributes => panic!("expected an unknown attribute, got {attributes:?}"),
+    }
+}
+
+#[test]
+fn attributes_with_a_decoder_keep_their_name()
+{
+    let bytes = vendor_class_file();
+    let class = rjvm::parse_with(&bytes, &[&VendorDecoder]).unwrap();
+
+    match class.attributes() {
+        [Attribute::Custom { name, value }] => {
+            assert_eq!(name, VENDOR);
+            assert_eq!(value.downcast_ref::<Vendor>(), Some(&Vendor(0x1234)));
+        }
+        attributes => panic!("expected a custom attribute, got {attributes:?}"),
+    }
+}
+
+#[test]
+fn decoders_do_not_replace_attributes_of_the_specification()
+{
+    struct SourceFileDecoder;
+
+    impl AttributeDecoder for SourceFileDecoder
+    {
+        fn name(&self) -> &str
+        {
+            "SourceFile"
+        }
+
+        fn decode(
+            &self,
+            _cursor: &mut Cursor<'_>,
+            _constant_pool: &ConstantPool<'_>,
+        ) -> Result<Box<dyn CustomAttribute>, Box<dyn std::error::Error + Send + Sync>>
+        {
+            panic!("decoded an attribute of the specification")
+        }
+    }
+
+    let mut class_file = ClassFile::new("A");
+    let source_file = class_file.utf8("SourceFile");
+    let file_name = class_file.utf8("A.java");
+    class_file.add_attribute(source_file, &file_name.to_be_bytes());
+    let bytes = class_file.to_bytes();
+    let class = rjvm::parse_with(&bytes, &[&SourceFileDecoder, &VendorDecoder]).unwrap();
+
+    assert!(matches!(
+        class.attributes(),
+        [Attribute::SourceFile {
+            sourcefile_index: 4
+        }]
+    ));
+}
+
+/// A class file named `A` with the constants of the annotations `@Marker(value = (byte) 0x1ff,
+/// kind = FIELD, types = {int[].class, true}, name = "hello", nested = @Nested)` and
+/// `@Deprecated`, and the names of the attributes holding them at #3 and #16
+fn annotated_class_file() -> ClassFile
+{
+    let mut class_file = ClassFile::new("A");
+    class_file.utf8("RuntimeVisibleAnnotations");
+    class_file.utf8("Lcom/example/Marker;");
+    class_file.utf8("value");
+    class_file.constant(3, &0x1ffi32.to_be_bytes());
+    for constant in [
+        "kind",
+        "Ljava/lang/annotation/ElementType;",
+        "FIELD",
+        "types",
+        "[I",
+        "name",
+        "hello",
+        "nested",
+        "Lcom/example/Nested;",
+        "RuntimeInvisibleAnnotations",
+        "Ljava/lang/Deprecated;",
+    ] {
+        class_file.utf8(constant);
+    }
+
+    class_file
+}
+
+#[test]
+fn annotations_are_decoded()
+{
+    let marker = [
+        0, 1, 0, 4, 0, 5, // one @#4 with five elements
+        0, 5, b'B', 0, 6, // value = B#6
+        0, 7, b'e', 0, 8, 0, 9, // kind = e#8.#9
+        0, 10, b'[', 0, 2, b'c', 0, 11, b'Z', 0, 6, // types = [c#11, Z#6]
+        0, 12, b's', 0, 13, // name = s#13
+        0, 14, b'@', 0, 15, 0, 0, // nested = @#15()
+    ];
+    let deprecated = [0, 1, 0, 17, 0, 0];
+    let mut class_file = annotated_class_file();
+    class_file.add_attribute(16, &deprecated);
+    class_file.add_attribute(3, &marker);
+    let bytes = class_file.to_bytes();
+    let class = rjvm::parse(&bytes).unwrap();
+
+    // The visible annotations come first, wherever their attribute is
+    let annotations = class.annotations().collect::<Vec<_>>();
+    assert_eq!(annotations.len(), 2);
+    assert_eq!(annotations[1].type_descriptor(), "Ljava/lang/Deprecated;");
+
+    let annotation = class.annotation("Lcom/example/Marker;").unwrap();
+    // Indices are those of the class file
+    assert_eq!(annotation.type_index(), 4);
+    let pairs = annotation.element_value_pairs();
+    assert_eq!(
+        pairs
+            .iter()
+            .map(|pair| (pair.element_name_index(), pair.element_name()))
+            .collect::<Vec<_>>(),
+        [
+            (5, "value"),
+            (7, "kind"),
+            (10, "types"),
+            (12, "name"),
+            (14, "nested"),
+        ]
+    );
+
+    // Bytes are narrowed from the int constant
+    assert!(matches!(
+        annotation.element("value"),
+        Some(ElementValue::Byte {
+            const_value_index: 6,
+            value: -1,
+        })
+    ));
+    assert!(matches!(
+        annotation.element("kind"),
+        Some(ElementValue::Enum {
+            type_name_index: 8,
+            type_descriptor,
+            const_name_index: 9,
+            const_name,
+        }) if type_descriptor == "Ljava/lang/annotation/ElementType;" && const_name == "FIELD"
+    ));
+    assert!(matches!(
+        annotation.element("types"),
+        Some(ElementValue::Array(values)) if matches!(
+            &values[..],
+            [
+                ElementValue::Class {
+                    class_info_index: 11,
+                    return_descriptor,
+                },
+                ElementValue::Boolean {
+                    const_value_index: 6,
+                    value: true,
+                },
+            ] if return_descriptor == "[I"
+        )
+    ));
+    assert!(matches!(
+        annotation.element("name"),
+        Some(ElementValue::String {
+            const_value_index: 13,
+            value,
+        }) if value == "hello"
+    ));
+    assert!(matches!(
+        annotation.element("nested"),
+        Some(ElementValue::Annotation(nested))
+            if nested.type_index() == 15 && nested.element_value_pairs().is_empty()
+    ));
+    assert!(annotation.element("missing").is_none());
+
+    // javap shows the indices of the class file along with what they resolve to
+    let disassembled = class.to_string();
+    assert!(
+        disassembled.contains("0: #4(#5=B#6,#7=e#8.#9,#10=[c#11,Z#6],#12=s#13,#14=@#15())"),
+        "{disassembled}"
+    );
+}
+
+#[test]
+fn invalid_element_values_are_rejected()
+{
+    // An element value with an unknown tag
+    let mut class_file = annotated_class_file();
+    class_file.add_attribute(3, &[0, 1, 0, 4, 0, 1, 0, 5, b'X', 0, 6]);
+    let bytes = class_file.to_bytes();
+    match rjvm::parse(&bytes) {
+        Err(Error::Parse(err)) => assert!(matches!(
+            err.error(),
+            parse::error::Error::Attribute(attribute::error::Error::InvalidElementValueTag(b'X'))
+        )),
+        Err(err) => panic!("expected an invalid tag, got {err}"),
+        Ok(_) => panic!("accepted an element value with an unknown tag"),
+    }
+
+    // An int element value given by a Utf8 constant
+    let mut class_file = annotated_class_file();
+    class_file.add_attribute(3, &[0, 1, 0, 4, 0, 1, 0, 5, b'I', 0, 5]);
+    let bytes = class_file.to_bytes();
+    assert!(rjvm::parse(&bytes).is_err());
+}
+
+#[test]
+fn deeply_nested_element_values_are_rejected()
+{
+    // @Marker(value = {{...{0x1ff}...}}) with the int in `depth` arrays
+    let nested = |depth: usize| {
+        let mut info = vec![0, 1, 0, 4, 0, 1, 0, 5];
+        for _ in 0..depth {
+            info.extend_from_slice(&[b'[', 0, 1]);
+        }
+        info.extend_from_slice(&[b'I', 0, 6]);
+
+        let mut class_file = annotated_class_file();
+        class_file.add_attribute(3, &info);
+
+        class_file.to_bytes()
+    };
+
+    // As deep as they may go, they are still disassembled
+    let bytes = nested(64);
+    let class = rjvm::parse(&bytes).unwrap();
+    assert!(class.to_string().contains("[[[I#6]]]"));
+    // Nesting is bounded before it can run out of stack
+    for depth in [65, 100_000] {
+        match rjvm::parse(&nested(depth)) {
+            Err(Error::Parse(err)) => assert!(matches!(
+                err.error(),
+                parse::error::Error::Attribute(attribute::error::Error::NestingTooDeep)
+            )),
+            Err(err) => panic!("expected too deep a nesting, got {err}"),
+            Ok(_) => panic!("accepted element values nested {depth} deep"),
+        }
+    }
+}
+
+#[test]
+fn nesting_attributes_keep_their_indices()
+{
+    let mut class_file = ClassFile::new("A");
+    let inner_classes_name = class_file.utf8("InnerClasses");
+    let outer = class_file.class("Outer");
+    let enclosing_method_name = class_file.utf8("EnclosingMethod");
+    let run = class_file.utf8("run");
+    let descriptor = class_file.utf8("()V");
+    let mut name_and_type = run.to_be_bytes().to_vec();
+    name_and_type.extend_from_slice(&descriptor.to_be_bytes());
+    let method = class_file.constant(12, &name_and_type);
+    let nest_host_name = class_file.utf8("NestHost");
+    class_file.class("Outer$Named");
+    class_file.utf8("Named");
+
+    let inner_classes = [
+        0, 2, // two classes
+        0, 2, 0, 0, 0, 0, 0, 0, // A, anonymous
+        0, 12, 0, 5, 0, 13, 0, 9, // Outer$Named, a public static member of Outer
+    ];
+    class_file.add_attribute(inner_classes_name, &inner_classes);
+    let mut enclosing_method = outer.to_be_bytes().to_vec();
+    enclosing_method.extend_from_slice(&method.to_be_bytes());
+    class_file.add_attribute(enclosing_method_name, &enclosing_method);
+    class_file.add_attribute(nest_host_name, &outer.to_be_bytes());
+    let bytes = class_file.to_bytes();
+    let class = rjvm::parse(&bytes).unwrap();
+
+    match class.attributes() {
+        [Attribute::InnerClasses { classes }, Attribute::EnclosingMethod {
+            class_index: 5,
+            method_index: Some(9),
+        }, Attribute::NestHost {
+            host_class_index: 5,
+        }] => {
+            assert_eq!(classes[0].inner_class_info_index(), 2);
+            assert_eq!(classes[0].outer_class_info_index(), None);
+            assert_eq!(classes[0].inner_name_index(), None);
+            assert_eq!(classes[1].inner_class_info_index(), 12);
+            assert_eq!(classes[1].outer_class_info_index(), Some(5));
+            assert_eq!(classes[1].inner_name_index(), Some(13));
+        }
+        attributes => panic!("expected the nesting attributes, got {attributes:?}"),
+    }
+
+    let inner_classes = class.inner_classes().collect::<Vec<_>>();
+    assert_eq!(inner_classes[1].class_name, "Outer$Named");
+    assert_eq!(inner_classes[1].outer_class_name, Some("Outer"));
+    assert_eq!(inner_classes[1].simple_name, Some("Named"));
+    // An anonymous class is declared in the class of its enclosing method
+    assert_eq!(class.outer_class(), Some("Outer"));
+    let enclosing_method = class.enclosing_method().unwrap();
+    assert_eq!(
+        (enclosing_method.class_name, enclosing_method.name),
+        ("Outer", "run")
+    );
+    assert_eq!(class.nest_host(), Some("Outer"));
+}
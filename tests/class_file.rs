@@ -0,0 +1,91 @@
+//! Tests for reading the header of class files
+
+use rjvm::{
+    error::Error,
+    parse::{
+        context::Located,
+        raw,
+        version::{self, SupportedVersions, Version},
+    },
+};
+
+/// The header of a class file of the given version, followed by bytes
+/// that are not a constant pool, which is never read for these tests
+fn header(major: u16) -> Vec<u8>
+{
+    let mut bytes = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0];
+    bytes.extend_from_slice(&major.to_be_bytes());
+    bytes.extend_from_slice(&[0x00, 0x02, 0xff]);
+
+    bytes
+}
+
+fn raw_error(result: rjvm::error::Result<rjvm::parse::Class<'_>>) -> Located<raw::error::Error>
+{
+    match result {
+        Err(Error::ParseRaw(err)) => err,
+        Err(err) => panic!("expected an error reading the class file, got {err}"),
+        Ok(_) => panic!("accepted an invalid class file"),
+    }
+}
+
+#[test]
+fn other_files_are_rejected_by_their_magic()
+{
+    // The local file header of a zip archive, such as a jar
+    let bytes = b"PK\x03\x04\x14\x00\x00\x00\x08\x00";
+
+    let err = raw_error(rjvm::parse(bytes));
+    assert!(matches!(
+        err.error(),
+        raw::error::Error::InvalidMagic { magic: 0x504b0304 }
+    ));
+    assert_eq!(err.context().offset(), 0);
+}
+
+#[test]
+fn unsupported_versions_are_rejected_before_the_constant_pool()
+{
+    let bytes = header(99);
+
+    let err = raw_error(rjvm::parse(&bytes));
+    assert!(matches!(
+        err.error(),
+        raw::error::Error::Version(version::error::Error::Unsupported { .. })
+    ));
+    assert_eq!(err.context().offset(), 4);
+}
+
+#[test]
+fn supported_versions_reach_the_constant_pool()
+{
+    let bytes = header(99);
+    let supported_versions =
+        SupportedVersions::new(Version::new(45, 0), Version::new(99, 0), false);
+
+    // The version is accepted, so the invalid constant is what fails
+    let err = raw_error(rjvm::parse_with_versions(&bytes, &[], &supported_versions));
+    assert!(matches!(
+        err.error(),
+        raw::error::Error::UnexpectedConstantTag(0xff)
+    ));
+}
+
+#[test]
+fn major_versions_are_named_after_their_release()
+{
+    let cases = [
+        (44, None),
+        (45, Some("1.1")),
+        (48, Some("1.4")),
+        (49, Some("5.0")),
+        (50, Some("6")),
+        (65, Some("21")),
+        (71, Some("27")),
+        (72, None),
+    ];
+
+    for (major, name) in cases {
+        assert_eq!(Version::new(major, 0).java_se_name(), name, "{major}");
+    }
+}
@@ -0,0 +1,144 @@
+//! Tests for looking up the lines and local variables of code
+//! at an instruction, on class files built up from their bytes
+
+mod common;
+
+use common::{code, ClassFile};
+use rjvm::parse::attribute::LocalVariable;
+
+/// A class file named `A` with a static method `m(I)V`, whose code is
+/// 10 nops and a return, with two LineNumberTables out of order, a
+/// LocalVariableTable of `a`, `b`, `c` and `d` and a LocalVariableTypeTable
+/// giving the generic type of `c`
+fn class_file() -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("A");
+    class_file.set_super_class("java/lang/Object");
+    let code_name = class_file.utf8("Code");
+    let line_number_table = class_file.utf8("LineNumberTable");
+    let local_variable_table = class_file.utf8("LocalVariableTable");
+    let local_variable_type_table = class_file.utf8("LocalVariableTypeTable");
+    let name = class_file.utf8("m");
+    let descriptor = class_file.utf8("(I)V");
+    let int = class_file.utf8("I");
+    let long = class_file.utf8("J");
+    let list = class_file.utf8("Ljava/util/List;");
+    let list_of_t = class_file.utf8("Ljava/util/List<TT;>;");
+    let names = ["a", "b", "c", "d"].map(|name| class_file.utf8(name));
+
+    // Lines 20 from pc 4 and 10 from pc 2, then line 30 from pc 7 in a table of its own
+    let lines = table(&[[4, 20], [2, 10]]);
+    let more_lines = table(&[[7, 30]]);
+    // a over the whole code, b from 2 up to 7 with d from 4 up to 7
+    // within it, and c from 7 on in the slot b had
+    let locals = table(&[
+        [0, 11, names[0], int, 0],
+        [2, 5, names[1], long, 1],
+        [7, 4, names[2], list, 1],
+        [4, 3, names[3], int, 3],
+    ]);
+    let local_types = table(&[[7, 4, names[2], list_of_t, 1]]);
+
+    let mut instructions = vec![0x00; 10];
+    instructions.push(0xb1);
+    let code = code(
+        2,
+        4,
+        &instructions,
+        &[
+            (line_number_table, &lines),
+            (local_variable_table, &locals),
+            (line_number_table, &more_lines),
+            (local_variable_type_table, &local_types),
+        ],
+    );
+    class_file.add_method(0x0009, name, descriptor, &[(code_name, &code)]);
+
+    class_file.to_bytes()
+}
+
+/// A table of the given entries, each made of u16 items, preceded by their count
+fn table<const N: usize>(entries: &[[u16; N]]) -> Vec<u8>
+{
+    let mut table = (entries.len() as u16).to_be_bytes().to_vec();
+    for item in entries.iter().flatten() {
+        table.extend_from_slice(&item.to_be_bytes());
+    }
+
+    table
+}
+
+#[test]
+fn lines_start_at_the_closest_entry_before_the_instruction()
+{
+    let bytes = class_file();
+    let class = rjvm::parse(&bytes).unwrap();
+    let code = class.methods()[0].code().unwrap();
+
+    let lines = (0..=10).map(|pc| code.line_for_pc(pc)).collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            // Nothing covers the code before the first entry
+            None,
+            None,
+            Some(10),
+            Some(10),
+            // Entries start at their pc
+            Some(20),
+            Some(20),
+            Some(20),
+            Some(30),
+            Some(30),
+            Some(30),
+            Some(30),
+        ]
+    );
+}
+
+#[test]
+fn locals_are_those_whose_range_covers_the_instruction()
+{
+    let bytes = class_file();
+    let class = rjvm::parse(&bytes).unwrap();
+    let code = class.methods()[0].code().unwrap();
+
+    let names_at = |pc| {
+        code.locals_at(pc)
+            .map(LocalVariable::name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names_at(0), ["a"]);
+    assert_eq!(names_at(2), ["a", "b"]);
+    // The ranges of b and d overlap
+    assert_eq!(names_at(4), ["a", "b", "d"]);
+    assert_eq!(names_at(6), ["a", "b", "d"]);
+    // Ranges end before the pc their length takes them to
+    assert_eq!(names_at(7), ["a", "c"]);
+    assert_eq!(names_at(10), ["a", "c"]);
+    assert!(names_at(11).is_empty());
+
+    let b = code.locals_at(2).nth(1).unwrap();
+    assert_eq!((b.descriptor(), b.index()), ("J", 1));
+}
+
+#[test]
+fn local_types_are_those_whose_range_covers_the_instruction()
+{
+    let bytes = class_file();
+    let class = rjvm::parse(&bytes).unwrap();
+    let code = class.methods()[0].code().unwrap();
+
+    assert_eq!(code.local_types_at(6).count(), 0);
+    for pc in [7, 10] {
+        let local_types = code.local_types_at(pc).collect::<Vec<_>>();
+        assert_eq!(
+            local_types
+                .iter()
+                .map(|local_type| (local_type.name(), local_type.signature()))
+                .collect::<Vec<_>>(),
+            [("c", "Ljava/util/List<TT;>;")]
+        );
+    }
+    assert_eq!(code.local_types_at(11).count(), 0);
+}
@@ -0,0 +1,209 @@
+//! Builds class files up from their bytes, for the tests to
+//! state only the constants, members and attributes they need
+
+// Every test crate includes this module, and none uses all of it
+#![allow(dead_code)]
+
+/// A class file being laid out, whose constants are numbered in the order they are added
+pub struct ClassFile
+{
+    major: u16,
+    constant_count: u16,
+    constants: Vec<u8>,
+    access_flags: u16,
+    this_class: u16,
+    super_class: u16,
+    interfaces: Vec<u16>,
+    fields: Vec<Vec<u8>>,
+    methods: Vec<Vec<u8>>,
+    attributes: Vec<Vec<u8>>,
+}
+
+impl ClassFile
+{
+    /// A public class of version 52 with the given name, whose Utf8 and Class
+    /// constants are at #1 and #2, without a superclass, members or attributes
+    pub fn new(name: &str) -> Self
+    {
+        let mut class_file = ClassFile::empty();
+        class_file.this_class = class_file.class(name);
+
+        class_file
+    }
+
+    /// A public class of version 52 without any constants, for
+    /// tests that lay out the whole constant pool themselves
+    pub fn empty() -> Self
+    {
+        ClassFile {
+            major: 52,
+            constant_count: 0,
+            constants: Vec::new(),
+            access_flags: 0x0021,
+            this_class: 0,
+            super_class: 0,
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds a constant with the given tag and operands, giving its index
+    pub fn constant(&mut self, tag: u8, operands: &[u8]) -> u16
+    {
+        let index = self.constant_count + 1;
+        self.constants.push(tag);
+        self.constants.extend_from_slice(operands);
+
+        // Longs and doubles take up two indices
+        self.constant_count += if matches!(tag, 5 | 6) { 2 } else { 1 };
+
+        index
+    }
+
+    /// Adds a Utf8 constant, given in modified UTF-8 if it is not valid UTF-8
+    pub fn utf8(&mut self, string: impl AsRef<[u8]>) -> u16
+    {
+        let string = string.as_ref();
+        let mut operands = (string.len() as u16).to_be_bytes().to_vec();
+        operands.extend_from_slice(string);
+
+        self.constant(1, &operands)
+    }
+
+    /// Adds a Class constant along with the Utf8 constant of its name, giving its index
+    pub fn class(&mut self, name: &str) -> u16
+    {
+        let name_index = self.utf8(name);
+
+        self.constant(7, &name_index.to_be_bytes())
+    }
+
+    pub fn set_major(&mut self, major: u16)
+    {
+        self.major = major;
+    }
+
+    pub fn set_access_flags(&mut self, access_flags: u16)
+    {
+        self.access_flags = access_flags;
+    }
+
+    pub fn set_this_class(&mut self, this_class: u16)
+    {
+        self.this_class = this_class;
+    }
+
+    pub fn set_super_class(&mut self, name: &str)
+    {
+        self.super_class = self.class(name);
+    }
+
+    pub fn add_interface(&mut self, name: &str)
+    {
+        let interface = self.class(name);
+        self.interfaces.push(interface);
+    }
+
+    /// Adds a field with attributes made of the index of their name and their info
+    pub fn add_field(
+        &mut self,
+        access_flags: u16,
+        name_index: u16,
+        descriptor_index: u16,
+        attributes: &[(u16, &[u8])],
+    )
+    {
+        let field = member(access_flags, name_index, descriptor_index, attributes);
+        self.fields.push(field);
+    }
+
+    /// Adds a method with attributes made of the index of their name and their info
+    pub fn add_method(
+        &mut self,
+        access_flags: u16,
+        name_index: u16,
+        descriptor_index: u16,
+        attributes: &[(u16, &[u8])],
+    )
+    {
+        let method = member(access_flags, name_index, descriptor_index, attributes);
+        self.methods.push(method);
+    }
+
+    pub fn add_attribute(&mut self, name_index: u16, info: &[u8])
+    {
+        self.attributes.push(attribute(name_index, info));
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let mut bytes = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0];
+        bytes.extend_from_slice(&self.major.to_be_bytes());
+        bytes.extend_from_slice(&(self.constant_count + 1).to_be_bytes());
+        bytes.extend_from_slice(&self.constants);
+
+        for item in [self.access_flags, self.this_class, self.super_class] {
+            bytes.extend_from_slice(&item.to_be_bytes());
+        }
+        bytes.extend_from_slice(&(self.interfaces.len() as u16).to_be_bytes());
+        for interface in &self.interfaces {
+            bytes.extend_from_slice(&interface.to_be_bytes());
+        }
+
+        for items in [&self.fields, &self.methods, &self.attributes] {
+            bytes.extend_from_slice(&(items.len() as u16).to_be_bytes());
+            bytes.extend(items.iter().flatten());
+        }
+
+        bytes
+    }
+}
+
+/// An attribute made of the index of its name and its info
+pub fn attribute(name_index: u16, info: &[u8]) -> Vec<u8>
+{
+    let mut bytes = name_index.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&(info.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(info);
+
+    bytes
+}
+
+/// The info of a Code attribute without an exception table
+pub fn code(max_stack: u16, max_locals: u16, code: &[u8], attributes: &[(u16, &[u8])]) -> Vec<u8>
+{
+    let mut info = max_stack.to_be_bytes().to_vec();
+    info.extend_from_slice(&max_locals.to_be_bytes());
+    info.extend_from_slice(&(code.len() as u32).to_be_bytes());
+    info.extend_from_slice(code);
+    info.extend_from_slice(&[0, 0]);
+
+    info.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+    for (name_index, attribute_info) in attributes {
+        info.extend_from_slice(&attribute(*name_index, attribute_info));
+    }
+
+    info
+}
+
+fn member(
+    access_flags: u16,
+    name_index: u16,
+    descriptor_index: u16,
+    attributes: &[(u16, &[u8])],
+) -> Vec<u8>
+{
+    let mut bytes = Vec::new();
+    for item in [access_flags, name_index, descriptor_index] {
+        bytes.extend_from_slice(&item.to_be_bytes());
+    }
+
+    bytes.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+    for (name_index, info) in attributes {
+        bytes.extend_from_slice(&attribute(*name_index, info));
+    }
+
+    bytes
+}
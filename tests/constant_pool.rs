@@ -0,0 +1,1046 @@
+//! Property tests for resolving the constant pool, comparing the parser against
+//! a model of which constants are valid over randomly generated constant pools
+//!
+//! The constant pools are generated from a fixed seed per case, which a failing
+//! case reports, so that it can be reproduced with `check_seed`
+
+mod common;
+
+use common::ClassFile;
+use rjvm::{
+    error::Error,
+    parse::{
+        constant_pool::{
+            error::{ConstantKind, Error as ConstantPoolError},
+            ConstantPool, ConstantPoolBuilder, ConstantRef, Loadable, MemberRef, ReferenceKind,
+        },
+        context::Segment,
+        Class,
+    },
+};
+
+const CASES: u64 = 2_000;
+
+const NAMES: [&str; 3] = ["a", "<init>", "java/lang/Object"];
+const DESCRIPTORS: [&str; 4] = ["I", "()V", "(J)D", "[Ljava/lang/String;"];
+
+/// The name of the attribute, among the constants of classes with bootstrap methods
+const BOOTSTRAP_METHODS: Constant = Constant::Utf8("BootstrapMethods");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind
+{
+    Utf8,
+    Class,
+    String,
+    Integer,
+    Long,
+    NameAndType,
+    FieldRef,
+    MethodRef,
+    InterfaceMethodRef,
+    MethodHandle,
+    MethodType,
+    Dynamic,
+    InvokeDynamic,
+}
+
+const KINDS: [Kind; 13] = [
+    Kind::Utf8,
+    Kind::Class,
+    Kind::String,
+    Kind::Integer,
+    Kind::Long,
+    Kind::NameAndType,
+    Kind::FieldRef,
+    Kind::MethodRef,
+    Kind::InterfaceMethodRef,
+    Kind::MethodHandle,
+    Kind::MethodType,
+    Kind::Dynamic,
+    Kind::InvokeDynamic,
+];
+
+/// The kinds of constants bootstrap methods take as static arguments
+const LOADABLE: [Kind; 7] = [
+    Kind::Class,
+    Kind::String,
+    Kind::Integer,
+    Kind::Long,
+    Kind::MethodHandle,
+    Kind::MethodType,
+    Kind::Dynamic,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Constant
+{
+    Utf8(&'static str),
+    Class(u16),
+    String(u16),
+    Integer(i32),
+    Long(i64),
+    NameAndType(u16, u16),
+    FieldRef(u16, u16),
+    MethodRef(u16, u16),
+    InterfaceMethodRef(u16, u16),
+    MethodHandle(u8, u16),
+    MethodType(u16),
+    Dynamic(u16, u16),
+    InvokeDynamic(u16, u16),
+}
+
+/// A constant pool, where index `n` is at `n - 1` and the second indices
+/// of long constants are `None`, along with the bootstrap methods of the class
+/// as the indices of their method handles and static arguments
+#[derive(Debug)]
+struct Pool
+{
+    constants: Vec<Option<Constant>>,
+    bootstrap_methods: Vec<(u16, Vec<u16>)>,
+}
+
+impl Pool
+{
+    fn kind(&self, index: u16) -> Option<Kind>
+    {
+        let constant = self
+            .constants
+            .get((index as usize).checked_sub(1)?)?
+            .as_ref()?;
+
+        Some(match constant {
+            Constant::Utf8(..) => Kind::Utf8,
+            Constant::Class(..) => Kind::Class,
+            Constant::String(..) => Kind::String,
+            Constant::Integer(..) => Kind::Integer,
+            Constant::Long(..) => Kind::Long,
+            Constant::NameAndType(..) => Kind::NameAndType,
+            Constant::FieldRef(..) => Kind::FieldRef,
+            Constant::MethodRef(..) => Kind::MethodRef,
+            Constant::InterfaceMethodRef(..) => Kind::InterfaceMethodRef,
+            Constant::MethodHandle(..) => Kind::MethodHandle,
+            Constant::MethodType(..) => Kind::MethodType,
+            Constant::Dynamic(..) => Kind::Dynamic,
+            Constant::InvokeDynamic(..) => Kind::InvokeDynamic,
+        })
+    }
+
+    fn utf8(&self, index: u16) -> &'static str
+    {
+        match self.constants[index as usize - 1] {
+            Some(Constant::Utf8(string)) => string,
+            ref constant => panic!("expected a Utf8 constant at #{index}, found {constant:?}"),
+        }
+    }
+
+    /// The name of the member a field or method reference refers to, if it resolves
+    fn member_name(&self, index: u16) -> Option<&'static str>
+    {
+        let (Some(Constant::FieldRef(_, name_and_type_index))
+        | Some(Constant::MethodRef(_, name_and_type_index))
+        | Some(Constant::InterfaceMethodRef(_, name_and_type_index))) =
+            *self.constants.get((index as usize).checked_sub(1)?)?
+        else {
+            return None;
+        };
+        let Some(Constant::NameAndType(name_index, _)) = *self
+            .constants
+            .get((name_and_type_index as usize).checked_sub(1)?)?
+        else {
+            return None;
+        };
+
+        (self.kind(name_index) == Some(Kind::Utf8)).then(|| self.utf8(name_index))
+    }
+
+    /// Whether a constant refers to constants of the kinds it has to, where
+    /// an index is valid from 1 through the number of indices the constants
+    /// take up, unless it is the second index of a long constant, and
+    /// whether it refers to one of the bootstrap methods if it has one
+    fn is_valid(&self, index: u16) -> bool
+    {
+        let refers_to = |index, kind| self.kind(index) == Some(kind);
+        let member_ref = |class_index, name_and_type_index| {
+            refers_to(class_index, Kind::Class) && refers_to(name_and_type_index, Kind::NameAndType)
+        };
+
+        match self.constants[index as usize - 1] {
+            None
+            | Some(Constant::Utf8(..))
+            | Some(Constant::Integer(..))
+            | Some(Constant::Long(..)) => true,
+            Some(Constant::Class(name_index)) => refers_to(name_index, Kind::Utf8),
+            Some(Constant::String(string_index)) => refers_to(string_index, Kind::Utf8),
+            Some(Constant::MethodType(descriptor_index)) => refers_to(descriptor_index, Kind::Utf8),
+            Some(Constant::NameAndType(name_index, descriptor_index)) => {
+                refers_to(name_index, Kind::Utf8)
+                    && refers_to(descriptor_index, Kind::Utf8)
+                    && DESCRIPTORS.contains(&self.utf8(descriptor_index))
+            }
+            Some(Constant::FieldRef(class_index, name_and_type_index))
+            | Some(Constant::MethodRef(class_index, name_and_type_index))
+            | Some(Constant::InterfaceMethodRef(class_index, name_and_type_index)) => {
+                member_ref(class_index, name_and_type_index)
+            }
+            Some(Constant::MethodHandle(reference_kind, reference_index)) => {
+                let kind = self.kind(reference_index);
+                let refers_to_kind = match reference_kind {
+                    1..=4 => kind == Some(Kind::FieldRef),
+                    5 | 8 => kind == Some(Kind::MethodRef),
+                    6 | 7 => {
+                        kind == Some(Kind::MethodRef) || kind == Some(Kind::InterfaceMethodRef)
+                    }
+                    9 => kind == Some(Kind::InterfaceMethodRef),
+                    _ => false,
+                };
+
+                // Only constructors are created through REF_newInvokeSpecial, and
+                // only once the constants resolve is the name known to the parser
+                let refers_to_name = match self.member_name(reference_index) {
+                    Some(name) if reference_kind == 8 => name == "<init>",
+                    Some(name) if (5..=9).contains(&reference_kind) => name != "<init>",
+                    _ => true,
+                };
+
+                refers_to_kind && refers_to_name
+            }
+            Some(Constant::Dynamic(bootstrap_method_attr_index, name_and_type_index))
+            | Some(Constant::InvokeDynamic(bootstrap_method_attr_index, name_and_type_index)) => {
+                refers_to(name_and_type_index, Kind::NameAndType)
+                    && (bootstrap_method_attr_index as usize) < self.bootstrap_methods.len()
+            }
+        }
+    }
+
+    /// Whether the bootstrap methods refer to method handles and loadable constants
+    fn bootstrap_methods_are_valid(&self) -> bool
+    {
+        self.bootstrap_methods
+            .iter()
+            .all(|(method_handle_index, arguments)| {
+                self.kind(*method_handle_index) == Some(Kind::MethodHandle)
+                    && arguments.iter().all(|argument| {
+                        self.kind(*argument)
+                            .is_some_and(|kind| LOADABLE.contains(&kind))
+                    })
+            })
+    }
+
+    fn len(&self) -> u16
+    {
+        self.constants.len() as u16
+    }
+
+    /// A class file with this constant pool, whose first two
+    /// constants must be the Utf8 and Class of its name
+    fn class_file(&self) -> Vec<u8>
+    {
+        let mut class_file = ClassFile::empty();
+        for constant in self.constants.iter().flatten() {
+            let (tag, operands): (u8, Vec<u8>) = match *constant {
+                Constant::Utf8(string) => {
+                    let mut operands = (string.len() as u16).to_be_bytes().to_vec();
+                    operands.extend_from_slice(string.as_bytes());
+
+                    (1, operands)
+                }
+                Constant::Class(index) => (7, index.to_be_bytes().to_vec()),
+                Constant::String(index) => (8, index.to_be_bytes().to_vec()),
+                Constant::Integer(value) => (3, value.to_be_bytes().to_vec()),
+                Constant::Long(value) => (5, value.to_be_bytes().to_vec()),
+                Constant::NameAndType(first, second) => (12, pair(first, second)),
+                Constant::FieldRef(first, second) => (9, pair(first, second)),
+                Constant::MethodRef(first, second) => (10, pair(first, second)),
+                Constant::InterfaceMethodRef(first, second) => (11, pair(first, second)),
+                Constant::MethodHandle(reference_kind, index) => {
+                    let mut operands = vec![reference_kind];
+                    operands.extend_from_slice(&index.to_be_bytes());
+
+                    (15, operands)
+                }
+                Constant::MethodType(index) => (16, index.to_be_bytes().to_vec()),
+                Constant::Dynamic(first, second) => (17, pair(first, second)),
+                Constant::InvokeDynamic(first, second) => (18, pair(first, second)),
+            };
+
+            class_file.constant(tag, &operands);
+        }
+        class_file.set_this_class(2);
+
+        if !self.bootstrap_methods.is_empty() {
+            // The name of the attribute is among the constants of classes with bootstrap methods
+            let name_index = (1..=self.len())
+                .find(|index| self.constants[*index as usize - 1] == Some(BOOTSTRAP_METHODS))
+                .unwrap();
+
+            let mut info = (self.bootstrap_methods.len() as u16).to_be_bytes().to_vec();
+            for (method_handle_index, arguments) in &self.bootstrap_methods {
+                info.extend_from_slice(&pair(*method_handle_index, arguments.len() as u16));
+                for argument in arguments {
+                    info.extend_from_slice(&argument.to_be_bytes());
+                }
+            }
+
+            class_file.add_attribute(name_index, &info);
+        }
+
+        class_file.to_bytes()
+    }
+}
+
+fn pair(first: u16, second: u16) -> Vec<u8>
+{
+    let mut bytes = first.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&second.to_be_bytes());
+
+    bytes
+}
+
+/// A xorshift generator, as good as any for picking constants
+struct Random(u64);
+
+impl Random
+{
+    fn new(seed: u64) -> Self
+    {
+        // The state must never be zero
+        Random(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64
+    {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize
+    {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T
+    {
+        items[self.below(items.len())]
+    }
+}
+
+/// Generates a constant pool whose references point at constants of the right kind,
+/// before or after them, and for half of the constant pools sometimes point at index 0,
+/// the last index, the index past it, the constant itself or any other index instead
+///
+/// The same goes for the bootstrap methods and the indices of them in the constant pool
+fn generate(random: &mut Random) -> Pool
+{
+    // The name of the class the constant pool is in
+    let mut constants = vec![Some(Constant::Utf8("T")), Some(Constant::Class(1))];
+    let mut kinds = vec![Some(Kind::Utf8), Some(Kind::Class)];
+    for _ in 0..1 + random.below(40) {
+        let kind = random.pick(&KINDS);
+        kinds.push(Some(kind));
+        if kind == Kind::Long {
+            kinds.push(None);
+        }
+    }
+
+    let invalid = random.below(2) == 0;
+    let bootstrap_method_count = if invalid {
+        random.below(4)
+    } else {
+        1 + random.below(3)
+    };
+    if bootstrap_method_count > 0 {
+        // Valid bootstrap methods need a method handle to refer to
+        if !invalid {
+            kinds.push(Some(Kind::MethodHandle));
+        }
+        kinds.push(Some(Kind::Utf8));
+    }
+
+    // Strings are picked up front, so that references to descriptors can find them
+    let strings = kinds
+        .iter()
+        .enumerate()
+        .map(|(position, kind)| match kind {
+            Some(Kind::Utf8) if position == 0 => Some("T"),
+            Some(Kind::Utf8) if bootstrap_method_count > 0 && position == kinds.len() - 1 => {
+                Some("BootstrapMethods")
+            }
+            Some(Kind::Utf8) if random.below(2) == 0 => Some(random.pick(&NAMES)),
+            Some(Kind::Utf8) => Some(random.pick(&DESCRIPTORS)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let len = kinds.len() as u16;
+    let reference = |random: &mut Random, this: u16, kind, descriptor: bool| {
+        let candidates = (1..=len)
+            .filter(|index| {
+                let position = *index as usize - 1;
+
+                kinds[position] == Some(kind)
+                    && (!descriptor || DESCRIPTORS.contains(&strings[position].unwrap()))
+            })
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() || invalid && random.below(8) == 0 {
+            let any = random.next() as u16;
+            random.pick(&[0, len, len + 1, this, any])
+        } else {
+            random.pick(&candidates)
+        }
+    };
+
+    for (position, kind) in kinds.iter().enumerate().skip(constants.len()) {
+        let this = position as u16 + 1;
+        let constant = match kind {
+            None => None,
+            Some(Kind::Utf8) => Some(Constant::Utf8(strings[position].unwrap())),
+            Some(Kind::Class) => Some(Constant::Class(reference(random, this, Kind::Utf8, false))),
+            Some(Kind::String) => {
+                Some(Constant::String(reference(random, this, Kind::Utf8, false)))
+            }
+            Some(Kind::Integer) => Some(Constant::Integer(random.next() as i32)),
+            Some(Kind::Long) => Some(Constant::Long(random.next() as i64)),
+            Some(Kind::NameAndType) => {
+                let descriptor = !invalid || random.below(2) == 0;
+
+                Some(Constant::NameAndType(
+                    reference(random, this, Kind::Utf8, false),
+                    reference(random, this, Kind::Utf8, descriptor),
+                ))
+            }
+            Some(Kind::FieldRef) => Some(Constant::FieldRef(
+                reference(random, this, Kind::Class, false),
+                reference(random, this, Kind::NameAndType, false),
+            )),
+            Some(Kind::MethodRef) => Some(Constant::MethodRef(
+                reference(random, this, Kind::Class, false),
+                reference(random, this, Kind::NameAndType, false),
+            )),
+            Some(Kind::InterfaceMethodRef) => Some(Constant::InterfaceMethodRef(
+                reference(random, this, Kind::Class, false),
+                reference(random, this, Kind::NameAndType, false),
+            )),
+            Some(Kind::MethodHandle) => {
+                let reference_kind = if invalid {
+                    random.below(11) as u8
+                } else {
+                    1 + random.below(9) as u8
+                };
+                let kind = match reference_kind {
+                    1..=4 => Kind::FieldRef,
+                    9 => Kind::InterfaceMethodRef,
+                    6 | 7 if random.below(2) == 0 => Kind::InterfaceMethodRef,
+                    _ => Kind::MethodRef,
+                };
+
+                Some(Constant::MethodHandle(
+                    reference_kind,
+                    reference(random, this, kind, false),
+                ))
+            }
+            Some(Kind::MethodType) => Some(Constant::MethodType(reference(
+                random,
+                this,
+                Kind::Utf8,
+                false,
+            ))),
+            Some(Kind::Dynamic) => Some(Constant::Dynamic(
+                bootstrap_method_attr_index(random, invalid, bootstrap_method_count),
+                reference(random, this, Kind::NameAndType, false),
+            )),
+            Some(Kind::InvokeDynamic) => Some(Constant::InvokeDynamic(
+                bootstrap_method_attr_index(random, invalid, bootstrap_method_count),
+                reference(random, this, Kind::NameAndType, false),
+            )),
+        };
+
+        constants.push(constant);
+    }
+
+    let mut pool = Pool {
+        constants,
+        bootstrap_methods: Vec::new(),
+    };
+
+    // The names of members are only known once all constants are, so for
+    // valid constant pools the method handles are fixed to match them
+    if !invalid {
+        for position in 0..pool.constants.len() {
+            let Some(Constant::MethodHandle(reference_kind, reference_index)) =
+                pool.constants[position]
+            else {
+                continue;
+            };
+            let reference_kind = match pool.member_name(reference_index) {
+                Some("<init>") if pool.kind(reference_index) == Some(Kind::MethodRef) => 8,
+                Some(name) if reference_kind == 8 && name != "<init>" => 5,
+                _ => reference_kind,
+            };
+
+            pool.constants[position] =
+                Some(Constant::MethodHandle(reference_kind, reference_index));
+        }
+    }
+
+    let bootstrap_methods = (0..bootstrap_method_count)
+        .map(|_| {
+            let method_handle_index = reference(random, 0, Kind::MethodHandle, false);
+            let arguments = (0..random.below(3))
+                .map(|_| {
+                    let kind = random.pick(&LOADABLE);
+                    reference(random, 0, kind, false)
+                })
+                .collect();
+
+            (method_handle_index, arguments)
+        })
+        .collect();
+
+    pool.bootstrap_methods = bootstrap_methods;
+
+    pool
+}
+
+/// An index of one of `count` bootstrap methods, or for invalid
+/// constant pools sometimes the index of one past them
+fn bootstrap_method_attr_index(random: &mut Random, invalid: bool, count: usize) -> u16
+{
+    if count == 0 || invalid && random.below(8) == 0 {
+        count as u16
+    } else {
+        random.below(count) as u16
+    }
+}
+
+fn member_ref(pool: &Pool, class_index: u16, name_and_type_index: u16) -> (&str, &str, &str)
+{
+    let Some(Constant::Class(name_index)) = pool.constants[class_index as usize - 1] else {
+        unreachable!()
+    };
+    let Some(Constant::NameAndType(name, descriptor)) =
+        pool.constants[name_and_type_index as usize - 1]
+    else {
+        unreachable!()
+    };
+
+    (
+        pool.utf8(name_index),
+        pool.utf8(name),
+        pool.utf8(descriptor),
+    )
+}
+
+fn assert_member_ref(reference: MemberRef<'_>, expected: (&str, &str, &str))
+{
+    assert_eq!(
+        (reference.class_name, reference.name, reference.descriptor),
+        expected
+    );
+}
+
+/// Checks that every constant resolves to what the model says it refers to
+fn assert_resolved(pool: &Pool, constant_pool: &ConstantPool<'_>)
+{
+    assert!(constant_pool.get(0).is_none());
+    assert!(constant_pool.get(pool.len() + 1).is_none());
+    assert_eq!(constant_pool.len(), pool.len() as usize);
+
+    for index in 1..=pool.len() {
+        let resolved = constant_pool.get(index);
+        let Some(constant) = pool.constants[index as usize - 1] else {
+            assert!(resolved.is_none(), "#{index} is unusable, got {resolved:?}");
+            continue;
+        };
+        let resolved = resolved.unwrap_or_else(|| panic!("#{index} did not resolve"));
+
+        match (constant, resolved) {
+            (Constant::Utf8(expected), ConstantRef::Utf8(string)) => assert_eq!(string, expected),
+            (Constant::Class(name_index), ConstantRef::Class(name)) => {
+                assert_eq!(name, pool.utf8(name_index))
+            }
+            (Constant::String(string_index), ConstantRef::String(string)) => {
+                assert_eq!(string, pool.utf8(string_index))
+            }
+            (Constant::Integer(expected), ConstantRef::Integer(value)) => {
+                assert_eq!(value, expected)
+            }
+            (Constant::Long(expected), ConstantRef::Long(value)) => assert_eq!(value, expected),
+            (
+                Constant::NameAndType(name_index, descriptor_index),
+                ConstantRef::NameAndType { name, descriptor },
+            ) => {
+                assert_eq!(name, pool.utf8(name_index));
+                assert_eq!(descriptor, pool.utf8(descriptor_index));
+            }
+            (
+                Constant::FieldRef(class_index, name_and_type_index),
+                ConstantRef::FieldRef(reference),
+            )
+            | (
+                Constant::MethodRef(class_index, name_and_type_index),
+                ConstantRef::MethodRef(reference),
+            )
+            | (
+                Constant::InterfaceMethodRef(class_index, name_and_type_index),
+                ConstantRef::InterfaceMethodRef(reference),
+            ) => assert_member_ref(
+                reference,
+                member_ref(pool, class_index, name_and_type_index),
+            ),
+            (
+                Constant::MethodHandle(expected_kind, reference_index),
+                ConstantRef::MethodHandle {
+                    reference_kind,
+                    reference,
+                },
+            ) => {
+                assert_eq!(reference_kind as u8, expected_kind);
+
+                let (Some(Constant::FieldRef(class_index, name_and_type_index))
+                | Some(Constant::MethodRef(class_index, name_and_type_index))
+                | Some(Constant::InterfaceMethodRef(class_index, name_and_type_index))) =
+                    pool.constants[reference_index as usize - 1]
+                else {
+                    unreachable!()
+                };
+                assert_member_ref(
+                    reference,
+                    member_ref(pool, class_index, name_and_type_index),
+                );
+            }
+            (Constant::MethodType(descriptor_index), ConstantRef::MethodType(descriptor)) => {
+                assert_eq!(descriptor, pool.utf8(descriptor_index))
+            }
+            (
+                Constant::Dynamic(expected_index, name_and_type_index),
+                ConstantRef::Dynamic {
+                    bootstrap_method_attr_index,
+                    name,
+                    descriptor,
+                },
+            )
+            | (
+                Constant::InvokeDynamic(expected_index, name_and_type_index),
+                ConstantRef::InvokeDynamic {
+                    bootstrap_method_attr_index,
+                    name,
+                    descriptor,
+                },
+            ) => {
+                let Some(Constant::NameAndType(name_index, descriptor_index)) =
+                    pool.constants[name_and_type_index as usize - 1]
+                else {
+                    unreachable!()
+                };
+
+                assert_eq!(bootstrap_method_attr_index, expected_index as usize);
+                assert_eq!(name, pool.utf8(name_index));
+                assert_eq!(descriptor, pool.utf8(descriptor_index));
+            }
+            (constant, resolved) => {
+                panic!("#{index} is {constant:?}, but resolved to {resolved:?}")
+            }
+        }
+    }
+}
+
+/// Checks that every call site links to the bootstrap method the model says it does
+fn assert_linked(pool: &Pool, class: &Class<'_>)
+{
+    for index in 1..=pool.len() {
+        let (Some(Constant::Dynamic(bootstrap_method_attr_index, _))
+        | Some(Constant::InvokeDynamic(bootstrap_method_attr_index, _))) =
+            pool.constants[index as usize - 1]
+        else {
+            continue;
+        };
+        let call_site = class
+            .call_site(index)
+            .unwrap_or_else(|err| panic!("#{index} did not link: {err}"));
+
+        let (method_handle_index, ref arguments) =
+            pool.bootstrap_methods[bootstrap_method_attr_index as usize];
+        let bootstrap_method = &class.bootstrap_methods()[bootstrap_method_attr_index as usize];
+        assert_eq!(bootstrap_method.bootstrap_method_ref(), method_handle_index);
+        assert_eq!(bootstrap_method.bootstrap_arguments(), arguments);
+        let Some(Constant::MethodHandle(reference_kind, reference_index)) =
+            pool.constants[method_handle_index as usize - 1]
+        else {
+            unreachable!()
+        };
+        let (Some(Constant::FieldRef(class_index, name_and_type_index))
+        | Some(Constant::MethodRef(class_index, name_and_type_index))
+        | Some(Constant::InterfaceMethodRef(class_index, name_and_type_index))) =
+            pool.constants[reference_index as usize - 1]
+        else {
+            unreachable!()
+        };
+
+        assert_eq!(call_site.reference_kind as u8, reference_kind);
+        assert_member_ref(
+            call_site.method,
+            member_ref(pool, class_index, name_and_type_index),
+        );
+        assert_eq!(call_site.arguments.len(), arguments.len());
+
+        for (argument, &argument_index) in call_site.arguments.iter().zip(arguments) {
+            let expected = pool.constants[argument_index as usize - 1].unwrap();
+            let matches = matches!(
+                (argument, expected),
+                (Loadable::Class(_), Constant::Class(_))
+                    | (Loadable::String(_), Constant::String(_))
+                    | (Loadable::Integer(_), Constant::Integer(_))
+                    | (Loadable::Long(_), Constant::Long(_))
+                    | (Loadable::MethodHandle { .. }, Constant::MethodHandle(..))
+                    | (Loadable::MethodType(_), Constant::MethodType(_))
+                    | (Loadable::Dynamic { .. }, Constant::Dynamic(..))
+            );
+            assert!(
+                matches,
+                "#{argument_index} is {expected:?}, but loaded {argument:?}"
+            );
+        }
+    }
+}
+
+fn check(pool: &Pool)
+{
+    let bytes = pool.class_file();
+    let invalid = (1..=pool.len())
+        .filter(|index| !pool.is_valid(*index))
+        .collect::<Vec<_>>();
+    let bootstrap_methods_are_valid = pool.bootstrap_methods_are_valid();
+
+    match rjvm::parse(&bytes) {
+        Ok(class) => {
+            assert!(invalid.is_empty(), "accepted invalid constants {invalid:?}");
+            assert!(
+                bootstrap_methods_are_valid,
+                "accepted invalid bootstrap methods"
+            );
+            assert_resolved(pool, class.constant_pool());
+            assert_linked(pool, &class);
+
+            // Rebuilding the constant pool from its resolved form gives back the original
+            let rebuilt = ConstantPoolBuilder::from_pool(class.constant_pool());
+            assert_eq!(&*rebuilt.constants(), class.raw().constant_pool());
+        }
+        Err(Error::Parse(err)) => match err.context().path().first() {
+            Some(&Segment::Constant(index)) => assert!(
+                invalid.contains(&(index as u16)),
+                "rejected valid constant #{index} with {err}, the invalid ones are {invalid:?}"
+            ),
+            Some(Segment::Attribute { .. }) => assert!(
+                !bootstrap_methods_are_valid,
+                "rejected valid bootstrap methods with {err}"
+            ),
+            _ => panic!("expected an error in a constant or attribute, got {err}"),
+        },
+        Err(err) => panic!("failed to parse the class file: {err}"),
+    }
+}
+
+fn check_seed(seed: u64)
+{
+    let pool = generate(&mut Random::new(seed));
+
+    let result = std::panic::catch_unwind(|| check(&pool));
+    if let Err(panic) = result {
+        eprintln!("failed with seed {seed} and constant pool {pool:#?}");
+        std::panic::resume_unwind(panic);
+    }
+}
+
+#[test]
+fn random_constant_pools_resolve_like_the_model()
+{
+    for seed in 0..CASES {
+        check_seed(seed);
+    }
+}
+
+#[test]
+fn random_constant_pools_are_accepted()
+{
+    // Make sure enough of the cases exercise resolution rather than rejection
+    let accepted = (0..CASES)
+        .map(|seed| generate(&mut Random::new(seed)))
+        .filter(|pool| {
+            (1..=pool.len()).all(|index| pool.is_valid(index)) && pool.bootstrap_methods_are_valid()
+        })
+        .count();
+
+    assert!(
+        accepted as u64 >= CASES / 10,
+        "only {accepted} of {CASES} constant pools are valid"
+    );
+}
+
+fn pool(constants: &[Option<Constant>]) -> Pool
+{
+    let mut pool = vec![Some(Constant::Utf8("T")), Some(Constant::Class(1))];
+    pool.extend_from_slice(constants);
+
+    Pool {
+        constants: pool,
+        bootstrap_methods: Vec::new(),
+    }
+}
+
+#[test]
+fn first_index_resolves()
+{
+    check(&pool(&[Some(Constant::String(1))]));
+}
+
+#[test]
+fn last_index_resolves()
+{
+    check(&pool(&[
+        Some(Constant::String(4)),
+        Some(Constant::Utf8("last")),
+    ]));
+}
+
+#[test]
+fn forward_references_resolve()
+{
+    check(&pool(&[
+        Some(Constant::MethodHandle(6, 4)),
+        Some(Constant::MethodRef(2, 5)),
+        Some(Constant::NameAndType(6, 7)),
+        Some(Constant::Utf8("main")),
+        Some(Constant::Utf8("()V")),
+    ]));
+}
+
+#[test]
+fn index_zero_is_rejected()
+{
+    let pool = pool(&[Some(Constant::String(0))]);
+
+    assert!(!pool.is_valid(3));
+    check(&pool);
+}
+
+#[test]
+fn index_past_the_end_is_rejected()
+{
+    let pool = pool(&[Some(Constant::String(4))]);
+
+    assert!(!pool.is_valid(3));
+    check(&pool);
+}
+
+#[test]
+fn second_index_of_long_is_rejected()
+{
+    let pool = pool(&[Some(Constant::Long(1)), None, Some(Constant::String(4))]);
+
+    assert!(!pool.is_valid(5));
+    check(&pool);
+}
+
+#[test]
+fn self_references_are_rejected()
+{
+    let pool = pool(&[Some(Constant::Class(3))]);
+
+    assert!(!pool.is_valid(3));
+    check(&pool);
+}
+
+#[test]
+fn cycles_are_rejected()
+{
+    let pool = pool(&[
+        Some(Constant::FieldRef(2, 4)),
+        Some(Constant::NameAndType(3, 3)),
+    ]);
+
+    assert!(!pool.is_valid(4));
+    check(&pool);
+}
+
+#[test]
+fn method_handle_names_are_checked()
+{
+    let pool = pool(&[
+        Some(Constant::MethodHandle(8, 4)),
+        Some(Constant::MethodRef(2, 5)),
+        Some(Constant::NameAndType(6, 7)),
+        Some(Constant::Utf8("main")),
+        Some(Constant::Utf8("()V")),
+    ]);
+
+    assert!(!pool.is_valid(3));
+    check(&pool);
+}
+
+#[test]
+fn call_sites_link_to_bootstrap_methods()
+{
+    let mut pool = pool(&[
+        Some(Constant::InvokeDynamic(0, 6)),
+        Some(Constant::MethodHandle(6, 5)),
+        Some(Constant::MethodRef(2, 6)),
+        Some(Constant::NameAndType(7, 8)),
+        Some(Constant::Utf8("main")),
+        Some(Constant::Utf8("()V")),
+        Some(BOOTSTRAP_METHODS),
+    ]);
+    pool.bootstrap_methods.push((4, vec![2, 4]));
+
+    check(&pool);
+}
+
+#[test]
+fn bootstrap_method_attr_index_past_the_end_is_rejected()
+{
+    let mut pool = pool(&[
+        Some(Constant::Dynamic(1, 6)),
+        Some(Constant::MethodHandle(6, 5)),
+        Some(Constant::MethodRef(2, 6)),
+        Some(Constant::NameAndType(7, 8)),
+        Some(Constant::Utf8("main")),
+        Some(Constant::Utf8("I")),
+        Some(BOOTSTRAP_METHODS),
+    ]);
+    pool.bootstrap_methods.push((4, Vec::new()));
+
+    assert!(!pool.is_valid(3));
+    check(&pool);
+}
+
+/// A class file of the given version and access flags, whose constants
+/// are a Module of `java.base` at #4 and a Package of `java/lang` at #6
+fn module_info(major: u16, access_flags: u16) -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("module-info");
+    class_file.set_major(major);
+    class_file.set_access_flags(access_flags);
+    for (tag, name) in [(19, "java.base"), (20, "java/lang")] {
+        let name_index = class_file.utf8(name);
+        class_file.constant(tag, &name_index.to_be_bytes());
+    }
+
+    class_file.to_bytes()
+}
+
+#[test]
+fn module_and_package_constants_resolve_in_modules()
+{
+    let bytes = module_info(53, 0x8000);
+    let class = rjvm::parse(&bytes).unwrap();
+    let constant_pool = class.constant_pool();
+
+    assert_eq!(constant_pool.module(4).unwrap(), "java.base");
+    assert_eq!(constant_pool.package(6).unwrap(), "java/lang");
+    assert!(matches!(
+        constant_pool.get(4),
+        Some(ConstantRef::Module("java.base"))
+    ));
+    assert!(matches!(
+        constant_pool.get(6),
+        Some(ConstantRef::Package("java/lang"))
+    ));
+    assert!(constant_pool.package(4).is_err());
+
+    let mut rebuilt = ConstantPoolBuilder::from_pool(constant_pool);
+    assert_eq!(&*rebuilt.constants(), class.raw().constant_pool());
+    assert_eq!(rebuilt.module("java.base").unwrap(), 4);
+    assert_eq!(rebuilt.package("java/lang").unwrap(), 6);
+}
+
+#[test]
+fn module_constants_are_rejected_outside_modules()
+{
+    // Module constants came with Java 9, and only module-info classes declare them
+    for (major, access_flags) in [(53, 0x0021), (52, 0x8000)] {
+        match rjvm::parse(&module_info(major, access_flags)) {
+            Err(Error::Parse(err)) => assert!(
+                matches!(err.context().path().first(), Some(&Segment::Constant(4))),
+                "expected the module constant to be rejected, got {err}"
+            ),
+            Ok(_) => panic!("accepted a module constant in version {major} with {access_flags:#x}"),
+            Err(err) => panic!("failed to parse the class file: {err}"),
+        }
+    }
+}
+
+#[test]
+fn call_sites_resolve_their_bootstrap_method_and_arguments()
+{
+    let mut class_file = ClassFile::new("A");
+    // Dynamic constants came with Java 11
+    class_file.set_major(55);
+    let bootstrap_name = class_file.utf8("bootstrap");
+    let bootstrap_descriptor = class_file.utf8("()Ljava/lang/invoke/CallSite;");
+    let bootstrap_name_and_type =
+        class_file.constant(12, &pair(bootstrap_name, bootstrap_descriptor));
+    let bootstrap_method = class_file.constant(10, &pair(2, bootstrap_name_and_type));
+    // REF_invokeStatic A.bootstrap
+    let mut handle = vec![6];
+    handle.extend_from_slice(&bootstrap_method.to_be_bytes());
+    let handle = class_file.constant(15, &handle);
+    let integer = class_file.constant(3, &42i32.to_be_bytes());
+    let hello = class_file.utf8("hello");
+    let string = class_file.constant(8, &hello.to_be_bytes());
+
+    let mut call_sites = Vec::new();
+    for (tag, name, descriptor) in [(18, "run", "()Ljava/lang/Runnable;"), (17, "value", "I")] {
+        let name = class_file.utf8(name);
+        let descriptor = class_file.utf8(descriptor);
+        let name_and_type = class_file.constant(12, &pair(name, descriptor));
+        call_sites.push(class_file.constant(tag, &pair(0, name_and_type)));
+    }
+
+    let bootstrap_methods = class_file.utf8("BootstrapMethods");
+    let mut info = pair(1, handle);
+    info.extend_from_slice(&pair(2, integer));
+    info.extend_from_slice(&string.to_be_bytes());
+    class_file.add_attribute(bootstrap_methods, &info);
+
+    let bytes = class_file.to_bytes();
+    let class = rjvm::parse(&bytes).unwrap();
+
+    let invoke_dynamic = class.call_site(call_sites[0]).unwrap();
+    assert_eq!(
+        (invoke_dynamic.name, invoke_dynamic.descriptor),
+        ("run", "()Ljava/lang/Runnable;")
+    );
+    let dynamic = class.call_site(call_sites[1]).unwrap();
+    assert_eq!((dynamic.name, dynamic.descriptor), ("value", "I"));
+
+    for call_site in [invoke_dynamic, dynamic] {
+        assert_eq!(call_site.bootstrap_method_attr_index, 0);
+        assert_eq!(call_site.reference_kind, ReferenceKind::InvokeStatic);
+        assert_member_ref(
+            call_site.method,
+            ("A", "bootstrap", "()Ljava/lang/invoke/CallSite;"),
+        );
+        assert!(matches!(
+            *call_site.arguments,
+            [Loadable::Integer(42), Loadable::String("hello")]
+        ));
+    }
+
+    // Only InvokeDynamic and Dynamic constants have a bootstrap method
+    for (index, kind) in [
+        (handle, ConstantKind::MethodHandle),
+        (string, ConstantKind::String),
+    ] {
+        match class.call_site(index) {
+            Err(ConstantPoolError::UnexpectedConstantKind {
+                expected: ConstantKind::InvokeDynamic,
+                actual,
+            }) => assert_eq!(actual, kind),
+            result => panic!("expected a constant of the wrong kind at #{index}, got {result:?}"),
+        }
+    }
+}
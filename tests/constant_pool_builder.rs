@@ -0,0 +1,203 @@
+//! Tests for building constant pools, whose constants are checked
+//! by parsing the class files they are written into
+
+mod common;
+
+use common::ClassFile;
+use rjvm::parse::constant_pool::{
+    error::Error, ConstantPoolBuilder, ConstantRef, MemberRef, ReferenceKind,
+};
+
+/// A class file named `A`, whose Utf8 and Class constants are at #1 and #2
+fn class_file() -> Vec<u8>
+{
+    ClassFile::new("A").to_bytes()
+}
+
+/// The class file with its constant pool replaced by the one built
+fn written(bytes: &[u8], builder: &ConstantPoolBuilder<'_>) -> Vec<u8>
+{
+    let mut raw = rjvm::parse_raw_class_file(bytes).unwrap();
+    raw.set_constant_pool(builder.constants());
+
+    raw.to_bytes().unwrap()
+}
+
+const FIELD: MemberRef<'static> = MemberRef {
+    class_name: "A",
+    name: "x",
+    descriptor: "I",
+};
+
+const METHOD: MemberRef<'static> = MemberRef {
+    class_name: "A",
+    name: "m",
+    descriptor: "()V",
+};
+
+#[test]
+fn equal_constants_are_interned_once()
+{
+    let bytes = class_file();
+    let raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+    let mut builder = ConstantPoolBuilder::from_raw(raw.constant_pool());
+
+    // The constants of the class file are found rather than added again
+    assert_eq!(builder.utf8("A").unwrap(), 1);
+    assert_eq!(builder.class("A").unwrap(), 2);
+
+    let name_and_type = builder.name_and_type("x", "I").unwrap();
+    assert_eq!(name_and_type, 5);
+    assert_eq!(builder.utf8("x").unwrap(), 3);
+    assert_eq!(builder.utf8("I").unwrap(), 4);
+    assert_eq!(builder.name_and_type("x", "I").unwrap(), name_and_type);
+
+    let field_ref = builder.field_ref(FIELD).unwrap();
+    assert_eq!(builder.field_ref(FIELD).unwrap(), field_ref);
+    // A handle to a field refers to the field reference already in the pool
+    let get_field = builder
+        .method_handle(ReferenceKind::GetField, FIELD, false)
+        .unwrap();
+    assert_eq!(get_field, field_ref + 1);
+    assert_eq!(
+        builder
+            .method_handle(ReferenceKind::GetField, FIELD, false)
+            .unwrap(),
+        get_field
+    );
+
+    // Handles of other kinds are other constants, referring to methods of classes or interfaces
+    let get_static = builder
+        .method_handle(ReferenceKind::GetStatic, FIELD, false)
+        .unwrap();
+    let invoke_static = builder
+        .method_handle(ReferenceKind::InvokeStatic, METHOD, false)
+        .unwrap();
+    let invoke_interface_static = builder
+        .method_handle(ReferenceKind::InvokeStatic, METHOD, true)
+        .unwrap();
+    assert_eq!(get_static, get_field + 1);
+    assert_ne!(invoke_static, invoke_interface_static);
+    let count = builder.constants().len();
+    let method_ref = builder.method_ref(METHOD).unwrap();
+    let interface_method_ref = builder.interface_method_ref(METHOD).unwrap();
+    assert_ne!(method_ref, interface_method_ref);
+    assert_eq!(builder.constants().len(), count);
+
+    let written = written(&bytes, &builder);
+    let class = rjvm::parse(&written).unwrap();
+    let constant_pool = class.constant_pool();
+    assert_eq!(constant_pool.len(), count);
+    assert_eq!(
+        constant_pool.name_and_type(name_and_type).unwrap(),
+        ("x", "I")
+    );
+    let field = constant_pool.field_ref(field_ref).unwrap();
+    assert_eq!(
+        (field.class_name, field.name, field.descriptor),
+        ("A", "x", "I")
+    );
+    assert!(matches!(
+        constant_pool.method_handle(get_field).unwrap(),
+        (ReferenceKind::GetField, MemberRef { name: "x", .. })
+    ));
+    assert!(matches!(
+        constant_pool.get(invoke_interface_static),
+        Some(ConstantRef::MethodHandle {
+            reference_kind: ReferenceKind::InvokeStatic,
+            ..
+        })
+    ));
+    assert!(matches!(
+        constant_pool.get(interface_method_ref),
+        Some(ConstantRef::InterfaceMethodRef(MemberRef { name: "m", .. }))
+    ));
+}
+
+#[test]
+fn longs_and_doubles_take_up_two_indices()
+{
+    let bytes = class_file();
+    let raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+    let mut builder = ConstantPoolBuilder::from_raw(raw.constant_pool());
+
+    assert_eq!(builder.long(-1).unwrap(), 3);
+    assert_eq!(builder.double(0.5).unwrap(), 5);
+    assert_eq!(builder.integer(7).unwrap(), 7);
+    assert_eq!(builder.long(-1).unwrap(), 3);
+
+    let written = written(&bytes, &builder);
+    let class = rjvm::parse(&written).unwrap();
+    let constant_pool = class.constant_pool();
+    assert_eq!(constant_pool.long(3).unwrap(), -1);
+    assert_eq!(constant_pool.double(5).unwrap(), 0.5);
+    assert_eq!(constant_pool.integer(7).unwrap(), 7);
+    // The indices following them are never to be referred to
+    assert!(constant_pool.get(4).is_none());
+    assert!(constant_pool.get(6).is_none());
+}
+
+#[test]
+fn floats_and_doubles_are_interned_by_their_bits()
+{
+    let other_nan = f32::from_bits(f32::NAN.to_bits() | 1);
+
+    let bytes = class_file();
+    let raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+    let mut builder = ConstantPoolBuilder::from_raw(raw.constant_pool());
+
+    let floats = [0.0, -0.0, f32::NAN, other_nan].map(|value| builder.float(value).unwrap());
+    assert_eq!(floats, [3, 4, 5, 6]);
+    // NaN is never equal to itself, but it is still found by its bits
+    assert_eq!(builder.float(f32::NAN).unwrap(), 5);
+    assert_eq!(builder.float(0.0).unwrap(), 3);
+
+    let doubles = [0.0, -0.0, f64::NAN].map(|value| builder.double(value).unwrap());
+    assert_eq!(doubles, [7, 9, 11]);
+    assert_eq!(builder.double(f64::NAN).unwrap(), 11);
+    assert_eq!(builder.double(-0.0).unwrap(), 9);
+
+    let written = written(&bytes, &builder);
+    let class = rjvm::parse(&written).unwrap();
+    let constant_pool = class.constant_pool();
+    for (index, value) in floats.into_iter().zip([0.0, -0.0, f32::NAN, other_nan]) {
+        assert_eq!(
+            constant_pool.float(index).unwrap().to_bits(),
+            value.to_bits()
+        );
+    }
+    for (index, value) in doubles.into_iter().zip([0.0, -0.0, f64::NAN]) {
+        assert_eq!(
+            constant_pool.double(index).unwrap().to_bits(),
+            value.to_bits()
+        );
+    }
+}
+
+#[test]
+fn full_constant_pools_are_not_extended()
+{
+    let bytes = class_file();
+    let raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+    let mut builder = ConstantPoolBuilder::from_raw(raw.constant_pool());
+
+    // Fill the pool up to one entry short of its 65534, after A and its name
+    for value in 0..65531 {
+        builder.integer(value).unwrap();
+    }
+    assert_eq!(builder.constants().len(), 65533);
+
+    // A long does not fit in the one entry left, but an integer does
+    assert!(matches!(builder.long(0), Err(Error::PoolFull)));
+    assert_eq!(builder.integer(-1).unwrap(), 65534);
+    assert!(matches!(builder.integer(-2), Err(Error::PoolFull)));
+    assert!(matches!(builder.utf8("B"), Err(Error::PoolFull)));
+    // Constants already in the pool are still found
+    assert_eq!(builder.integer(0).unwrap(), 3);
+    assert_eq!(builder.class("A").unwrap(), 2);
+
+    let written = written(&bytes, &builder);
+    let class = rjvm::parse(&written).unwrap();
+    assert_eq!(class.constant_pool().len(), 65534);
+    assert_eq!(class.constant_pool().integer(65534).unwrap(), -1);
+}
@@ -0,0 +1,147 @@
+//! Tests for parsing field and method descriptors
+
+use rjvm::parse::descriptor::{error::Error, BaseType, FieldType, MethodDescriptor};
+
+fn object(class_name: &str) -> FieldType
+{
+    FieldType::Object(class_name.into())
+}
+
+fn array(dimensions: u8, element: FieldType) -> FieldType
+{
+    FieldType::Array {
+        dimensions,
+        element: Box::new(element),
+    }
+}
+
+#[test]
+fn field_descriptors_are_parsed()
+{
+    let cases = [
+        ("I", FieldType::Base(BaseType::Int)),
+        ("J", FieldType::Base(BaseType::Long)),
+        ("Z", FieldType::Base(BaseType::Boolean)),
+        ("Ljava/lang/String;", object("java/lang/String")),
+        ("LA;", object("A")),
+        ("Lpkg/Outer$Inner;", object("pkg/Outer$Inner")),
+        ("[D", array(1, FieldType::Base(BaseType::Double))),
+        ("[[Ljava/lang/Object;", array(2, object("java/lang/Object"))),
+    ];
+
+    for (descriptor, expected) in cases {
+        let field_type = FieldType::parse(descriptor)
+            .unwrap_or_else(|err| panic!("rejected {descriptor:?}: {err}"));
+
+        assert_eq!(field_type, expected, "{descriptor:?}");
+        // Descriptors are written back the way they were read
+        assert_eq!(field_type.to_string(), descriptor);
+    }
+}
+
+#[test]
+fn arrays_have_at_most_255_dimensions()
+{
+    let descriptor = format!("{}I", "[".repeat(255));
+    assert_eq!(
+        FieldType::parse(&descriptor).unwrap(),
+        array(255, FieldType::Base(BaseType::Int))
+    );
+
+    let descriptor = format!("{}I", "[".repeat(256));
+    assert!(matches!(
+        FieldType::parse(&descriptor),
+        Err(Error::TooManyDimensions { position: 0 })
+    ));
+}
+
+#[test]
+fn invalid_field_descriptors_are_rejected()
+{
+    let cases = [
+        ("", "unexpected end"),
+        ("V", "void is not a field type"),
+        ("X", "unknown base type"),
+        ("II", "trailing characters"),
+        ("[", "array without an element type"),
+        ("Ljava/lang/String", "unterminated class name"),
+        ("L;", "empty class name"),
+        ("Ljava//String;", "empty package segment"),
+        ("Ljava/lang/;", "empty last segment"),
+        ("Ljava.lang.String;", "dots in a class name"),
+        ("L[I;", "array in a class name"),
+    ];
+
+    for (descriptor, reason) in cases {
+        assert!(
+            FieldType::parse(descriptor).is_err(),
+            "accepted {descriptor:?} with {reason}"
+        );
+    }
+}
+
+#[test]
+fn errors_point_at_the_offending_character()
+{
+    assert!(matches!(
+        FieldType::parse("[[X"),
+        Err(Error::UnexpectedCharacter {
+            character: 'X',
+            position: 2,
+        })
+    ));
+    assert!(matches!(
+        FieldType::parse("La//b;"),
+        Err(Error::EmptyName { position: 3 })
+    ));
+    assert!(matches!(
+        FieldType::parse("Ljava/lang"),
+        Err(Error::UnexpectedEnd)
+    ));
+}
+
+#[test]
+fn method_descriptors_are_parsed()
+{
+    let descriptor = MethodDescriptor::parse("(IJ[Ljava/lang/String;D)Ljava/lang/Object;").unwrap();
+    assert_eq!(
+        descriptor.parameters(),
+        [
+            FieldType::Base(BaseType::Int),
+            FieldType::Base(BaseType::Long),
+            array(1, object("java/lang/String")),
+            FieldType::Base(BaseType::Double),
+        ]
+    );
+    assert_eq!(descriptor.return_type(), Some(&object("java/lang/Object")));
+    // Longs and doubles take up two slots
+    assert_eq!(descriptor.parameter_slots(), 6);
+
+    let descriptor = MethodDescriptor::parse("()V").unwrap();
+    assert!(descriptor.parameters().is_empty());
+    assert_eq!(descriptor.return_type(), None);
+    assert_eq!(descriptor.to_string(), "()V");
+}
+
+#[test]
+fn invalid_method_descriptors_are_rejected()
+{
+    let cases = [
+        ("", "unexpected end"),
+        ("V", "missing parameters"),
+        ("()", "missing return type"),
+        ("(I", "unterminated parameters"),
+        ("(V)V", "void parameter"),
+        ("()VV", "trailing characters"),
+        ("()[V", "array of void"),
+        ("(Ljava/lang/String)V", "unterminated class name"),
+        ("I()V", "return type first"),
+    ];
+
+    for (descriptor, reason) in cases {
+        assert!(
+            MethodDescriptor::parse(descriptor).is_err(),
+            "accepted {descriptor:?} with {reason}"
+        );
+    }
+}
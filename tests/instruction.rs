@@ -0,0 +1,179 @@
+//! Tests for decoding code arrays into instructions
+
+use rjvm::parse::instruction::{error::Error, Instruction, Instructions, Wide};
+
+fn decode(code: &[u8]) -> Vec<(u32, Instruction)>
+{
+    Instructions::new(code)
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|err| panic!("failed to decode {code:02x?}: {err}"))
+}
+
+fn decode_err(code: &[u8]) -> Error
+{
+    match Instructions::new(code).find_map(Result::err) {
+        Some(err) => err,
+        None => panic!("decoded {code:02x?} without errors"),
+    }
+}
+
+/// A tableswitch at `offset` after as many nops, padded up to the next multiple
+/// of four and followed by a return, with the cases 1 and 2
+fn tableswitch(offset: usize) -> Vec<u8>
+{
+    let mut code = vec![0x00; offset];
+    code.push(0xaa);
+    code.resize(code.len() + (3 - offset % 4), 0);
+    for operand in [20, 1, 2, 24, 28] {
+        code.extend_from_slice(&i32::to_be_bytes(operand));
+    }
+    code.push(0xb1);
+
+    code
+}
+
+#[test]
+fn tableswitch_operands_are_aligned_to_four_bytes()
+{
+    for offset in 0..4 {
+        let code = tableswitch(offset);
+        let instructions = decode(&code);
+
+        assert_eq!(instructions.len(), offset + 2);
+        assert_eq!(
+            instructions[offset],
+            (
+                offset as u32,
+                Instruction::Tableswitch {
+                    default: 20,
+                    low: 1,
+                    high: 2,
+                    offsets: Box::new([24, 28]),
+                }
+            )
+        );
+        // The return follows the 20 bytes of operands
+        assert_eq!(
+            instructions[offset + 1],
+            ((code.len() - 1) as u32, Instruction::Return)
+        );
+        assert_eq!((code.len() - 1) % 4, 0);
+    }
+}
+
+#[test]
+fn tableswitch_with_low_above_high_is_rejected()
+{
+    let mut code = vec![0xaa, 0, 0, 0];
+    for operand in [0, 2, 1] {
+        code.extend_from_slice(&i32::to_be_bytes(operand));
+    }
+
+    assert!(matches!(
+        decode_err(&code),
+        Error::InvalidTableswitch {
+            offset: 0,
+            low: 2,
+            high: 1,
+        }
+    ));
+}
+
+#[test]
+fn lookupswitch_operands_are_aligned_to_four_bytes()
+{
+    // iconst_0 at 0 puts the lookupswitch at 1, with two bytes of padding
+    let mut code = vec![0x03, 0xab, 0, 0];
+    for operand in [36, 2, -1, 28, 7, 32] {
+        code.extend_from_slice(&i32::to_be_bytes(operand));
+    }
+    code.push(0xb1);
+
+    assert_eq!(
+        decode(&code),
+        [
+            (0, Instruction::Iconst0),
+            (
+                1,
+                Instruction::Lookupswitch {
+                    default: 36,
+                    pairs: Box::new([(-1, 28), (7, 32)]),
+                }
+            ),
+            (28, Instruction::Return),
+        ]
+    );
+}
+
+#[test]
+fn lookupswitch_with_unsorted_or_negative_pairs_is_rejected()
+{
+    let mut unsorted = vec![0xab, 0, 0, 0];
+    for operand in [0, 2, 7, 0, -1, 0] {
+        unsorted.extend_from_slice(&i32::to_be_bytes(operand));
+    }
+    assert!(matches!(
+        decode_err(&unsorted),
+        Error::InvalidLookupswitch { offset: 0 }
+    ));
+
+    let mut negative = vec![0xab, 0, 0, 0];
+    for operand in [0, -1] {
+        negative.extend_from_slice(&i32::to_be_bytes(operand));
+    }
+    assert!(matches!(
+        decode_err(&negative),
+        Error::InvalidLookupswitch { offset: 0 }
+    ));
+}
+
+#[test]
+fn wide_widens_local_variable_indices()
+{
+    let code = [
+        0xc4, 0x15, 0x01, 0x00, // wide iload 256
+        0xc4, 0x84, 0x01, 0x02, 0xff, 0xfe, // wide iinc 258, -2
+        0xc4, 0xa9, 0x00, 0x03, // wide ret 3
+        0x15, 0x04, // iload 4
+    ];
+
+    assert_eq!(
+        decode(&code),
+        [
+            (0, Instruction::Wide(Wide::Iload(256))),
+            (
+                4,
+                Instruction::Wide(Wide::Iinc {
+                    index: 258,
+                    value: -2,
+                })
+            ),
+            (10, Instruction::Wide(Wide::Ret(3))),
+            (14, Instruction::Iload(4)),
+        ]
+    );
+}
+
+#[test]
+fn wide_only_modifies_local_variable_instructions()
+{
+    // wide cannot modify iadd
+    assert!(matches!(
+        decode_err(&[0x00, 0xc4, 0x60]),
+        Error::InvalidWideOpcode {
+            offset: 1,
+            opcode: 0x60,
+        }
+    ));
+}
+
+#[test]
+fn decoding_stops_after_an_error()
+{
+    // A truncated sipush, after which the next instruction cannot be found
+    let mut instructions = Instructions::new(&[0x00, 0x11, 0x01]);
+
+    assert_eq!(instructions.next().unwrap().unwrap(), (0, Instruction::Nop));
+    assert!(matches!(instructions.next(), Some(Err(Error::Cursor(_)))));
+    assert!(instructions.next().is_none());
+}
@@ -0,0 +1,110 @@
+//! Tests for modified UTF-8, both on its own and through the constant pool
+
+mod common;
+
+use common::ClassFile;
+use rjvm::parse::{constant_pool::ConstantPoolBuilder, mutf8};
+
+#[test]
+fn nul_is_encoded_with_two_bytes()
+{
+    let encoded = mutf8::encode("a\0b");
+    assert_eq!(&*encoded, b"a\xc0\x80b");
+    assert_eq!(mutf8::decode(&encoded).unwrap(), "a\0b");
+}
+
+#[test]
+fn supplementary_characters_are_encoded_as_surrogate_pairs()
+{
+    // U+1F600 is the surrogate pair D83D DE00
+    let encoded = mutf8::encode("a\u{1f600}");
+    assert_eq!(&*encoded, b"a\xed\xa0\xbd\xed\xb8\x80");
+    assert_eq!(mutf8::decode(&encoded).unwrap(), "a\u{1f600}");
+    assert_eq!(
+        &*mutf8::decode_utf16(&encoded).unwrap(),
+        &[0x61, 0xd83d, 0xde00]
+    );
+}
+
+#[test]
+fn valid_utf8_is_borrowed()
+{
+    let bytes = "caf\u{e9}".as_bytes();
+    assert!(matches!(
+        mutf8::decode(bytes).unwrap(),
+        std::borrow::Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn lone_surrogates_round_trip_as_utf16()
+{
+    let units = [0x61, 0xd800, 0x62, 0xdfff];
+    let encoded = mutf8::encode_utf16(&units);
+    assert_eq!(&*encoded, b"a\xed\xa0\x80b\xed\xbf\xbf");
+    assert_eq!(&*mutf8::decode_utf16(&encoded).unwrap(), &units);
+
+    // They have no place in a str
+    let err = mutf8::decode(&encoded).unwrap_err();
+    assert!(
+        matches!(err, mutf8::error::Error::UnpairedSurrogate { position: 1 }),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn unpaired_surrogate_after_pair_is_located()
+{
+    let encoded = mutf8::encode_utf16(&[0xd83d, 0xde00, 0xd83d]);
+    let err = mutf8::decode(&encoded).unwrap_err();
+    assert!(
+        matches!(err, mutf8::error::Error::UnpairedSurrogate { position: 6 }),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn standard_four_byte_sequences_are_rejected()
+{
+    assert!(mutf8::decode("\u{1f600}".as_bytes()).is_err());
+    assert!(mutf8::decode(b"a\0").is_err());
+    assert!(mutf8::decode(b"\xe0\x80").is_err());
+}
+
+/// A class file named `A` with a String constant of the given modified UTF-8 at #4
+fn class_with_string(string: &[u8]) -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("A");
+    let utf8 = class_file.utf8(string);
+    class_file.constant(8, &utf8.to_be_bytes());
+
+    class_file.to_bytes()
+}
+
+#[test]
+fn constants_with_lone_surrogates_are_kept()
+{
+    let units = [0x61, 0xdc00];
+    let bytes = class_with_string(&mutf8::encode_utf16(&units));
+    let class = rjvm::parse(&bytes).unwrap();
+    let constant_pool = class.constant_pool();
+
+    assert_eq!(constant_pool.string(4).unwrap(), "a\u{fffd}");
+    assert_eq!(&*constant_pool.string_utf16(4).unwrap(), &units);
+    assert_eq!(&*constant_pool.utf16(3).unwrap(), &units);
+
+    let rebuilt = ConstantPoolBuilder::from_pool(constant_pool);
+    assert_eq!(&*rebuilt.constants(), class.raw().constant_pool());
+}
+
+#[test]
+fn constants_with_nul_and_supplementary_characters_are_decoded()
+{
+    let bytes = class_with_string(&mutf8::encode("\0\u{1f600}"));
+    let class = rjvm::parse(&bytes).unwrap();
+
+    assert_eq!(class.constant_pool().string(4).unwrap(), "\0\u{1f600}");
+
+    let rebuilt = ConstantPoolBuilder::from_pool(class.constant_pool());
+    assert_eq!(&*rebuilt.constants(), class.raw().constant_pool());
+}
@@ -0,0 +1,177 @@
+//! Tests for parsing generic class, method and field signatures
+
+use rjvm::parse::{
+    descriptor::{error::Error, BaseType},
+    signature::{
+        ClassSignature, FieldSignature, JavaTypeSignature, MethodSignature, ReferenceTypeSignature,
+        TypeArgument,
+    },
+};
+
+#[test]
+fn class_signatures_are_parsed()
+{
+    let signature = "<K:Ljava/lang/Object;V::Ljava/lang/Comparable<-TV;>;>\
+                     Ljava/util/AbstractMap<TK;TV;>;Ljava/util/Map<TK;TV;>;";
+    let class_signature = ClassSignature::parse(signature).unwrap();
+
+    let type_parameters = class_signature.type_parameters();
+    assert_eq!(type_parameters.len(), 2);
+    assert_eq!(type_parameters[0].name(), "K");
+    assert!(type_parameters[0].interface_bounds().is_empty());
+    // V has no class bound, only an interface bound
+    assert_eq!(type_parameters[1].name(), "V");
+    assert!(type_parameters[1].class_bound().is_none());
+    assert_eq!(type_parameters[1].interface_bounds().len(), 1);
+
+    assert_eq!(
+        class_signature.superclass().binary_name(),
+        "java/util/AbstractMap"
+    );
+    assert_eq!(class_signature.superinterfaces().len(), 1);
+    assert_eq!(class_signature.to_string(), signature);
+}
+
+#[test]
+fn nested_class_types_are_parsed()
+{
+    let signature = "Ljava/util/Map<TK;TV;>.Entry<TK;*>;";
+    let field_signature = FieldSignature::parse(signature).unwrap();
+
+    let ReferenceTypeSignature::Class(class) = field_signature.field_type() else {
+        panic!("expected a class type, got {field_signature:?}");
+    };
+    assert_eq!(class.package(), "java/util");
+    assert_eq!(class.binary_name(), "java/util/Map$Entry");
+
+    let classes = class.classes();
+    assert_eq!(classes[0].name(), "Map");
+    assert_eq!(classes[1].name(), "Entry");
+    assert_eq!(
+        classes[1].type_arguments(),
+        [
+            TypeArgument::Exact(ReferenceTypeSignature::TypeVariable("K".into())),
+            TypeArgument::Any,
+        ]
+    );
+    assert_eq!(field_signature.to_string(), signature);
+}
+
+#[test]
+fn method_signatures_are_parsed()
+{
+    let signature = "<T:Ljava/lang/Object;X:Ljava/lang/Exception;>\
+                     (I[TT;Ljava/util/List<+Ljava/lang/Number;>;)TT;^TX;^Ljava/io/IOException;";
+    let method_signature = MethodSignature::parse(signature).unwrap();
+
+    assert_eq!(method_signature.type_parameters().len(), 2);
+    assert_eq!(
+        method_signature.parameters()[0],
+        JavaTypeSignature::Base(BaseType::Int)
+    );
+    assert_eq!(
+        method_signature.parameters()[1],
+        JavaTypeSignature::Reference(ReferenceTypeSignature::Array {
+            dimensions: 1,
+            element: Box::new(JavaTypeSignature::Reference(
+                ReferenceTypeSignature::TypeVariable("T".into())
+            )),
+        })
+    );
+    assert_eq!(
+        method_signature.result(),
+        Some(&JavaTypeSignature::Reference(
+            ReferenceTypeSignature::TypeVariable("T".into())
+        ))
+    );
+    assert_eq!(method_signature.throws().len(), 2);
+    assert_eq!(method_signature.to_string(), signature);
+
+    let void = MethodSignature::parse("()V").unwrap();
+    assert_eq!(void.result(), None);
+}
+
+#[test]
+fn invalid_class_signatures_are_rejected()
+{
+    let cases = [
+        ("", "no superclass"),
+        ("I", "primitive superclass"),
+        ("TT;", "type variable as superclass"),
+        ("<>Ljava/lang/Object;", "empty type parameters"),
+        ("<T>Ljava/lang/Object;", "type parameter without bounds"),
+        (
+            "<:Ljava/lang/Object;>Ljava/lang/Object;",
+            "nameless type parameter",
+        ),
+        ("Ljava/lang/Object;I", "primitive superinterface"),
+    ];
+
+    for (signature, reason) in cases {
+        assert!(
+            ClassSignature::parse(signature).is_err(),
+            "accepted {signature:?} with {reason}"
+        );
+    }
+}
+
+#[test]
+fn invalid_method_signatures_are_rejected()
+{
+    let cases = [
+        ("", "unexpected end"),
+        ("V", "missing parameters"),
+        ("(V)V", "void parameter"),
+        ("()", "missing result"),
+        ("()V^I", "primitive exception"),
+        ("()V^", "missing exception"),
+        ("()VI", "trailing characters"),
+    ];
+
+    for (signature, reason) in cases {
+        assert!(
+            MethodSignature::parse(signature).is_err(),
+            "accepted {signature:?} with {reason}"
+        );
+    }
+}
+
+#[test]
+fn invalid_field_signatures_are_rejected()
+{
+    let cases = [
+        ("I", "primitive field type"),
+        ("T;", "nameless type variable"),
+        ("TT", "unterminated type variable"),
+        ("Ljava/util/List<>;", "empty type arguments"),
+        ("Ljava/util/List<I>;", "primitive type argument"),
+        ("Ljava/util/List<TT;>", "unterminated class type"),
+        ("Ljava/lang/Object;;", "trailing characters"),
+    ];
+
+    for (signature, reason) in cases {
+        assert!(
+            FieldSignature::parse(signature).is_err(),
+            "accepted {signature:?} with {reason}"
+        );
+    }
+}
+
+#[test]
+fn deeply_nested_type_arguments_are_rejected()
+{
+    let nested = |depth: usize| format!("{}LA;{}", "LA<".repeat(depth), ">;".repeat(depth));
+
+    assert!(FieldSignature::parse(&nested(256)).is_ok());
+    // Nesting is bounded before it can run out of stack
+    for signature in [nested(257), nested(13000)] {
+        assert!(matches!(
+            FieldSignature::parse(&signature),
+            Err(Error::NestingTooDeep { position: 770 })
+        ));
+        assert!(matches!(
+            ClassSignature::parse(&signature),
+            Err(Error::NestingTooDeep { position: 770 })
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! Tests for expanding the frames of StackMapTable attributes into the types of
+//! the locals and the stack, on class files built up from their bytes
+
+mod common;
+
+use std::borrow::Cow;
+
+use common::{code, ClassFile};
+use rjvm::{
+    error::Error,
+    parse::{
+        self,
+        attribute::{self, StackMapFrame, VerificationType},
+    },
+};
+
+/// The length of the code of every method, nops followed by a return
+const CODE_LENGTH: usize = 20;
+
+/// A class file named `A` with a single method, whose code has a
+/// StackMapTable attribute with `frames`, numbering `count`
+fn class_file(access_flags: u16, name: &str, descriptor: &str, count: u16, frames: &[u8])
+    -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("A");
+    class_file.set_super_class("java/lang/Object");
+    let code_name = class_file.utf8("Code");
+    let stack_map_table_name = class_file.utf8("StackMapTable");
+    let name = class_file.utf8(name);
+    let descriptor = class_file.utf8(descriptor);
+    // java/lang/String at #10, for frames to refer to
+    class_file.class("java/lang/String");
+
+    let mut stack_map_table = count.to_be_bytes().to_vec();
+    stack_map_table.extend_from_slice(frames);
+
+    let mut instructions = vec![0x00; CODE_LENGTH - 1];
+    instructions.push(0xb1);
+    let code = code(
+        2,
+        4,
+        &instructions,
+        &[(stack_map_table_name, &stack_map_table)],
+    );
+    class_file.add_method(access_flags, name, descriptor, &[(code_name, &code)]);
+
+    class_file.to_bytes()
+}
+
+fn object(class_name: &str) -> VerificationType<'_>
+{
+    VerificationType::Object(Cow::Borrowed(class_name))
+}
+
+#[test]
+fn frames_start_from_the_receiver_and_parameters()
+{
+    // A frame at offset 3 with the same locals as the start of the method
+    let constructor = class_file(0x0001, "<init>", "(I)V", 1, &[3]);
+    let class = rjvm::parse(&constructor).unwrap();
+    let frames = class
+        .stack_map_frames(&class.methods()[0])
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].offset(), 3);
+    // The receiver of a constructor is uninitialized until it calls another constructor
+    assert_eq!(
+        frames[0].locals(),
+        [
+            VerificationType::UninitializedThis,
+            VerificationType::Integer
+        ]
+    );
+    assert!(frames[0].stack().is_empty());
+
+    // Longs and doubles take up a single entry, and static methods have no receiver
+    let method = class_file(0x0009, "m", "(JLjava/lang/String;)V", 1, &[3]);
+    let class = rjvm::parse(&method).unwrap();
+    let frames = class
+        .stack_map_frames(&class.methods()[0])
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        frames[0].locals(),
+        [VerificationType::Long, object("java/lang/String")]
+    );
+}
+
+#[test]
+fn frames_are_applied_one_after_another()
+{
+    let frames = [
+        2, // same, at 2
+        65, 1, // same_locals_1_stack_item with an int, at 2 + 1 + 1
+        252, 0, 0, 2, // append a float, at 4 + 0 + 1
+        249, 0, 1, // chop 2, at 5 + 1 + 1
+        247, 0, 0, 5, // same_locals_1_stack_item_extended with null, at 7 + 0 + 1
+        251, 0, 1, // same_frame_extended, at 8 + 1 + 1
+        // full_frame with a String and top, and an object created at 0 on the stack, at 10 + 2 + 1
+        255, 0, 2, 0, 2, 7, 0, 10, 0, 0, 1, 8, 0, 0,
+    ];
+    let bytes = class_file(0x0009, "m", "(JLjava/lang/String;)V", 7, &frames);
+    let class = rjvm::parse(&bytes).unwrap();
+    let method = &class.methods()[0];
+
+    let entries = method.code().unwrap().stack_map_table().unwrap().entries();
+    assert_eq!(
+        entries
+            .iter()
+            .map(StackMapFrame::frame_type)
+            .collect::<Vec<_>>(),
+        [2, 65, 252, 249, 247, 251, 255]
+    );
+
+    let frames = class.stack_map_frames(method).unwrap().unwrap();
+    let expanded = frames
+        .iter()
+        .map(|frame| (frame.offset(), frame.locals(), frame.stack()))
+        .collect::<Vec<_>>();
+    let string = object("java/lang/String");
+    assert_eq!(
+        expanded,
+        [
+            (2, &[VerificationType::Long, string.clone()][..], &[][..]),
+            (
+                4,
+                &[VerificationType::Long, string.clone()],
+                &[VerificationType::Integer]
+            ),
+            (
+                5,
+                &[
+                    VerificationType::Long,
+                    string.clone(),
+                    VerificationType::Float
+                ],
+                &[]
+            ),
+            (7, &[VerificationType::Long], &[]),
+            (8, &[VerificationType::Long], &[VerificationType::Null]),
+            (10, &[VerificationType::Long], &[]),
+            (
+                13,
+                &[string, VerificationType::Top],
+                &[VerificationType::Uninitialized { offset: 0 }]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn chopping_more_locals_than_there_are_is_rejected()
+{
+    // chop 3 with only the long and the String as locals
+    let bytes = class_file(0x0009, "m", "(JLjava/lang/String;)V", 1, &[248, 0, 0]);
+    let class = rjvm::parse(&bytes).unwrap();
+
+    assert!(matches!(
+        class.stack_map_frames(&class.methods()[0]),
+        Some(Err(attribute::error::Error::ChopUnderflow {
+            offset: 0,
+            count: 3,
+            locals: 2,
+        }))
+    ));
+}
+
+#[test]
+fn frames_past_the_end_of_the_code_are_rejected()
+{
+    // same at 2, then same at 2 + 17 + 1, just past the return
+    let bytes = class_file(0x0009, "m", "()V", 2, &[2, 17]);
+
+    match rjvm::parse(&bytes) {
+        Err(Error::Parse(err)) => assert!(matches!(
+            err.error(),
+            parse::error::Error::Attribute(attribute::error::Error::InvalidStackMapFrameOffset {
+                offset: 20,
+                code_length: 20,
+            })
+        )),
+        Err(err) => panic!("expected an invalid frame offset, got {err}"),
+        Ok(_) => panic!("accepted a frame past the end of the code"),
+    }
+}
+
+#[test]
+fn uninitialized_this_is_disassembled_as_this()
+{
+    // full_frame at 3 with the receiver and the int, and nothing on the stack
+    let frames = [255, 0, 3, 0, 2, 6, 1, 0, 0];
+    let constructor = class_file(0x0001, "<init>", "(I)V", 1, &frames);
+    let class = rjvm::parse(&constructor).unwrap();
+
+    let disassembled = class.to_string();
+    assert!(
+        disassembled.contains("locals = [ this, int ]"),
+        "{disassembled}"
+    );
+}
@@ -0,0 +1,141 @@
+//! Tests for writing class files, which reproduce the bytes they were
+//! parsed from unless they were changed
+
+mod common;
+
+use common::{code, ClassFile};
+use rjvm::parse::{attribute::Attribute, constant_pool::ConstantPoolBuilder, raw::AttributeInfo};
+
+const MAIN: &[u8] = include_bytes!("../Main.class");
+
+/// A class `A` implementing `java/lang/Runnable`, with a long constant field, a method
+/// with code and a vendor attribute, whose constants include a long and a double that
+/// take up two indices and a string in modified UTF-8 with a NUL in it
+fn class_file() -> Vec<u8>
+{
+    let mut class_file = ClassFile::new("A");
+    class_file.set_super_class("java/lang/Object");
+    let long = class_file.constant(5, &0x1122334455667788u64.to_be_bytes());
+    class_file.constant(6, &1.5f64.to_be_bytes());
+    let long_descriptor = class_file.utf8("J");
+    let x = class_file.utf8("x");
+    let constant_value = class_file.utf8("ConstantValue");
+    let run = class_file.utf8("run");
+    let void_descriptor = class_file.utf8("()V");
+    let code_name = class_file.utf8("Code");
+    let vendor = class_file.utf8("com.example.Vendor");
+    class_file.utf8(b"a\xc0\x80b");
+    class_file.add_interface("java/lang/Runnable");
+
+    // public static final long x = #5
+    class_file.add_field(
+        0x0019,
+        x,
+        long_descriptor,
+        &[(constant_value, &long.to_be_bytes())],
+    );
+    // public void run() with nothing but a return
+    class_file.add_method(
+        0x0001,
+        run,
+        void_descriptor,
+        &[(code_name, &code(0, 1, &[0xb1], &[]))],
+    );
+    class_file.add_attribute(vendor, &[1, 2, 3]);
+
+    class_file.to_bytes()
+}
+
+#[test]
+fn class_files_are_written_back_byte_for_byte()
+{
+    for bytes in [MAIN.to_vec(), class_file()] {
+        let raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+        assert_eq!(rjvm::write_raw_class_file(&raw).unwrap(), bytes);
+    }
+
+    // The parsed class keeps the class file it was parsed from for writing it
+    let class = rjvm::parse(MAIN).unwrap();
+    assert_eq!(class.to_bytes().unwrap(), MAIN);
+}
+
+#[test]
+fn lengths_are_written_from_what_they_measure()
+{
+    let bytes = class_file();
+    let mut raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+
+    let info = [9; 300];
+    raw.attributes_mut()[0].set_info(&info);
+    let written = raw.to_bytes().unwrap();
+    assert_eq!(written.len(), bytes.len() + 297);
+
+    let reparsed = rjvm::parse_raw_class_file(&written).unwrap();
+    assert_eq!(reparsed.attributes()[0].attribute_name_index(), 15);
+    assert_eq!(reparsed.attributes()[0].info(), info);
+    // What follows the patched attribute is read from where it moved to
+    assert_eq!(reparsed.methods()[0].attributes()[0].info().len(), 13);
+}
+
+#[test]
+fn attributes_are_written_after_those_they_were_parsed_with()
+{
+    let bytes = class_file();
+    let mut raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+
+    raw.push_attribute(AttributeInfo::new(15, &[7]));
+    raw.methods_mut()[0].push_attribute(AttributeInfo::new(15, &[]));
+    let written = raw.to_bytes().unwrap();
+    assert_eq!(written.len(), bytes.len() + 7 + 6);
+
+    let reparsed = rjvm::parse_raw_class_file(&written).unwrap();
+    let infos = |attributes: &[AttributeInfo<'_>]| {
+        attributes
+            .iter()
+            .map(|attribute| (attribute.attribute_name_index(), attribute.info().to_vec()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        infos(reparsed.attributes()),
+        [(15, vec![1, 2, 3]), (15, vec![7])]
+    );
+    assert_eq!(
+        infos(reparsed.methods()[0].attributes()),
+        [
+            (14, vec![0, 0, 0, 1, 0, 0, 0, 1, 0xb1, 0, 0, 0, 0]),
+            (15, vec![])
+        ]
+    );
+}
+
+#[test]
+fn attributes_are_added_along_with_their_names()
+{
+    let bytes = class_file();
+
+    let parsed = rjvm::parse_raw_class_file(&bytes).unwrap();
+    let mut builder = ConstantPoolBuilder::from_raw(parsed.constant_pool());
+    let name_index = builder.utf8("com.example.Added").unwrap();
+    // The name of the existing attribute is found rather than added again
+    assert_eq!(builder.utf8("com.example.Vendor").unwrap(), 15);
+
+    let mut raw = rjvm::parse_raw_class_file(&bytes).unwrap();
+    raw.set_constant_pool(builder.constants());
+    raw.push_attribute(AttributeInfo::new(name_index, &[7]));
+    raw.methods_mut()[0].push_attribute(AttributeInfo::new(name_index, &[]));
+    let written = raw.to_bytes().unwrap();
+
+    let class = rjvm::parse(&written).unwrap();
+    assert_eq!(
+        class.constant_pool().utf8(name_index).unwrap(),
+        "com.example.Added"
+    );
+    assert!(matches!(
+        class.attributes(),
+        [Attribute::Unknown { .. }, Attribute::Unknown { name, info: [7] }] if name == "com.example.Added"
+    ));
+    assert!(matches!(
+        class.methods()[0].attributes(),
+        [Attribute::Code(_), Attribute::Unknown { info: [], .. }]
+    ));
+}